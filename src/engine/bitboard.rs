@@ -0,0 +1,299 @@
+use super::Square;
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+/// A set of squares on a chess board, stored as one bit per square.
+/// Bit `n` is set when square `n` is in the set, so a1 is the lowest bit and h8 the highest.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Default)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    /// The set containing no squares.
+    pub const EMPTY: Bitboard = Bitboard(0);
+    /// The set containing every square.
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+    /// The light squares of the board, like h1 and a8.
+    pub const LIGHT_SQUARES: Bitboard = Bitboard(0x55aa_55aa_55aa_55aa);
+
+    /// A set containing only the given square.
+    pub const fn from_square(square: Square) -> Self {
+        Self(1 << square)
+    }
+
+    /// All squares on the given rank, counting from 0.
+    pub const fn rank(rank: u8) -> Self {
+        Self(0xff << (rank * 8))
+    }
+
+    /// All squares on the given file, counting from 0.
+    pub const fn file(file: u8) -> Self {
+        Self(0x0101_0101_0101_0101 << file)
+    }
+
+    /// Is the square part of this set?
+    pub const fn contains(self, square: Square) -> bool {
+        self.0 & (1 << square) != 0
+    }
+
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// The number of squares in the set.
+    pub const fn count(self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// The lowest square in the set, if any.
+    pub const fn first(self) -> Option<Square> {
+        match self.0 {
+            0 => None,
+            bits => Some(bits.trailing_zeros() as Square),
+        }
+    }
+
+    /// The highest square in the set, if any.
+    pub const fn last(self) -> Option<Square> {
+        match self.0 {
+            0 => None,
+            bits => Some(63 - bits.leading_zeros() as Square),
+        }
+    }
+}
+
+/// Iterating a bitboard yields its squares in ascending order.
+impl Iterator for Bitboard {
+    type Item = Square;
+
+    fn next(&mut self) -> Option<Square> {
+        let square = self.first()?;
+        self.0 &= self.0 - 1;
+        Some(square)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.count() as usize;
+        (count, Some(count))
+    }
+}
+
+impl BitAnd for Bitboard {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for Bitboard {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitXor for Bitboard {
+    type Output = Self;
+    fn bitxor(self, rhs: Self) -> Self {
+        Self(self.0 ^ rhs.0)
+    }
+}
+
+impl Not for Bitboard {
+    type Output = Self;
+    fn not(self) -> Self {
+        Self(!self.0)
+    }
+}
+
+impl BitAndAssign for Bitboard {
+    fn bitand_assign(&mut self, rhs: Self) {
+        self.0 &= rhs.0;
+    }
+}
+
+impl BitOrAssign for Bitboard {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitXorAssign for Bitboard {
+    fn bitxor_assign(&mut self, rhs: Self) {
+        self.0 ^= rhs.0;
+    }
+}
+
+/// Builds a table of the squares reachable from every square by a single step along each (rank, file) offset.
+const fn step_table(offsets: &[(i8, i8)]) -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+    let mut square = 0;
+    while square < 64 {
+        let (rank, file) = ((square / 8) as i8, (square % 8) as i8);
+        let mut bits = 0u64;
+        let mut i = 0;
+        while i < offsets.len() {
+            let (r, f) = (rank + offsets[i].0, file + offsets[i].1);
+            if r >= 0 && r < 8 && f >= 0 && f < 8 {
+                bits |= 1 << (r * 8 + f);
+            }
+            i += 1;
+        }
+        table[square] = Bitboard(bits);
+        square += 1;
+    }
+    table
+}
+
+/// Builds a table of the squares along a ray in one direction from every square, up to the board edge.
+const fn ray_table(direction: (i8, i8)) -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+    let mut square = 0;
+    while square < 64 {
+        let (mut r, mut f) = ((square / 8) as i8, (square % 8) as i8);
+        let mut bits = 0u64;
+        loop {
+            r += direction.0;
+            f += direction.1;
+            if r < 0 || r > 7 || f < 0 || f > 7 {
+                break;
+            }
+            bits |= 1 << (r * 8 + f);
+        }
+        table[square] = Bitboard(bits);
+        square += 1;
+    }
+    table
+}
+
+const KNIGHT_ATTACKS: [Bitboard; 64] = step_table(&[
+    (2, 1),
+    (2, -1),
+    (-2, 1),
+    (-2, -1),
+    (1, 2),
+    (1, -2),
+    (-1, 2),
+    (-1, -2),
+]);
+
+const KING_ATTACKS: [Bitboard; 64] = step_table(&[
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+    (0, 1),
+    (0, -1),
+]);
+
+/// Squares attacked by a pawn, for white and black pawns respectively.
+const PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
+    step_table(&[(1, 1), (1, -1)]),
+    step_table(&[(-1, 1), (-1, -1)]),
+];
+
+/// Rays going towards higher square indices: north, east, north-east and north-west.
+const POSITIVE_RAYS: [[Bitboard; 64]; 4] = [
+    ray_table((1, 0)),
+    ray_table((0, 1)),
+    ray_table((1, 1)),
+    ray_table((1, -1)),
+];
+
+/// Rays going towards lower square indices: south, west, south-west and south-east.
+const NEGATIVE_RAYS: [[Bitboard; 64]; 4] = [
+    ray_table((-1, 0)),
+    ray_table((0, -1)),
+    ray_table((-1, -1)),
+    ray_table((-1, 1)),
+];
+
+/// Builds a table of the squares strictly between every pair of squares that share a rank, file or diagonal.
+const fn between_table() -> [[Bitboard; 64]; 64] {
+    let mut table = [[Bitboard::EMPTY; 64]; 64];
+    let mut from = 0;
+    while from < 64 {
+        let mut direction = 0;
+        while direction < 4 {
+            let rays = [POSITIVE_RAYS[direction], NEGATIVE_RAYS[direction]];
+            let mut r = 0;
+            while r < 2 {
+                let ray = rays[r][from].0;
+                // The squares before `to` are on the ray from `from`, but not on the ray from `to`.
+                let mut to = 0;
+                while to < 64 {
+                    if ray & (1 << to) != 0 {
+                        table[from][to] = Bitboard(ray & !rays[r][to].0 & !(1 << to));
+                    }
+                    to += 1;
+                }
+                r += 1;
+            }
+            direction += 1;
+        }
+        from += 1;
+    }
+    table
+}
+
+static BETWEEN: [[Bitboard; 64]; 64] = between_table();
+
+/// Squares attacked along a ray, stopping at (and including) the first occupied square.
+fn positive_ray_attacks(direction: usize, square: Square, occupied: Bitboard) -> Bitboard {
+    let ray = POSITIVE_RAYS[direction][square as usize];
+    match (ray & occupied).first() {
+        Some(blocker) => ray ^ POSITIVE_RAYS[direction][blocker as usize],
+        None => ray,
+    }
+}
+
+/// Squares attacked along a ray, stopping at (and including) the first occupied square.
+fn negative_ray_attacks(direction: usize, square: Square, occupied: Bitboard) -> Bitboard {
+    let ray = NEGATIVE_RAYS[direction][square as usize];
+    match (ray & occupied).last() {
+        Some(blocker) => ray ^ NEGATIVE_RAYS[direction][blocker as usize],
+        None => ray,
+    }
+}
+
+/// Squares strictly between two squares on the same rank, file or diagonal.
+/// Empty if the squares don't share a line, or are next to each other.
+pub fn between(a: Square, b: Square) -> Bitboard {
+    BETWEEN[a as usize][b as usize]
+}
+
+/// Squares a knight on `square` attacks.
+pub fn knight_attacks(square: Square) -> Bitboard {
+    KNIGHT_ATTACKS[square as usize]
+}
+
+/// Squares a king on `square` attacks.
+pub fn king_attacks(square: Square) -> Bitboard {
+    KING_ATTACKS[square as usize]
+}
+
+/// Squares a pawn of the given color on `square` attacks diagonally.
+pub fn pawn_attacks(square: Square, is_white: bool) -> Bitboard {
+    PAWN_ATTACKS[!is_white as usize][square as usize]
+}
+
+/// Squares a rook on `square` attacks, given the occupied squares of the board.
+pub fn rook_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    positive_ray_attacks(0, square, occupied)
+        | positive_ray_attacks(1, square, occupied)
+        | negative_ray_attacks(0, square, occupied)
+        | negative_ray_attacks(1, square, occupied)
+}
+
+/// Squares a bishop on `square` attacks, given the occupied squares of the board.
+pub fn bishop_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    positive_ray_attacks(2, square, occupied)
+        | positive_ray_attacks(3, square, occupied)
+        | negative_ray_attacks(2, square, occupied)
+        | negative_ray_attacks(3, square, occupied)
+}
+
+/// Squares a queen on `square` attacks, given the occupied squares of the board.
+pub fn queen_attacks(square: Square, occupied: Bitboard) -> Bitboard {
+    rook_attacks(square, occupied) | bishop_attacks(square, occupied)
+}
@@ -1,33 +1,198 @@
-use super::r#move::Move;
+use super::bitboard::{
+    Bitboard, between, bishop_attacks, king_attacks, knight_attacks, pawn_attacks, queen_attacks,
+    rook_attacks,
+};
+use super::r#move::{Move, MoveKind};
+use super::movelist::MoveList;
 use super::piece::Piece;
-use super::{Square, square_from_algebraic, square_to_algebraic, to_board_square};
+use super::zobrist;
+use super::{Square, square_from_algebraic, square_to_algebraic};
+use std::error::Error;
 use std::fmt::Display;
 
-#[derive(PartialEq, Eq, Clone, Copy)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
 pub struct Board {
-    pieces: [Option<Piece>; 64],
+    /// The piece on each square, for looking up a single square quickly.
+    squares: [Option<Piece>; 64],
+    /// The squares occupied by each piece, indexed by the [Piece] discriminant.
+    pieces: [Bitboard; 14],
+    /// The squares occupied by white and black pieces respectively.
+    colors: [Bitboard; 2],
     pub white_to_move: bool,
     en_pessant_square: Option<Square>,
-    can_white_castle_king_side: bool,
-    can_white_castle_queen_side: bool,
-    can_black_castle_king_side: bool,
-    can_black_castle_queen_side: bool,
+    /// Files of the rooks each side may still castle with, indexed by [castling_index].
+    castling_rooks: [Option<u8>; 4],
+    /// Castling moves are written as the king capturing its own rook, like in Chess960.
+    chess960: bool,
     half_move_clock: u32,
     full_move_counter: u32,
+    /// Zobrist hash of the position, kept up to date as pieces and state change.
+    hash: u64,
+    /// Zobrist hash of only the pawns, kept up to date like `hash`.
+    pawn_hash: u64,
+}
+
+/// Reasons a FEN string could not be read by [Board::from_fen].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum FenError {
+    /// The FEN string had this many space separated fields, when it should have had 4 to 6.
+    FieldCount(usize),
+    /// The piece placement had this many ranks separated by '/', when it should have had 8.
+    RankCount(usize),
+    /// The piece placement of this rank (counting from 1) did not describe exactly 8 squares.
+    RankLength(u8),
+    /// The piece placement contained a character that is neither a piece nor a digit from 1 to 8.
+    UnknownPiece(char),
+    /// The side to move was neither 'w' nor 'b'.
+    SideToMove(String),
+    /// The castling field was neither '-' nor a combination of 'KQkq' and rook files like 'HAha',
+    /// with at most one right per side and wing.
+    Castling(String),
+    /// The en pessant field was neither '-' nor a square on the rank behind the pawn that just moved.
+    EnPessant(String),
+    /// The half move clock was not a number.
+    HalfMoveClock(String),
+    /// The full move counter was not a number.
+    FullMoveCounter(String),
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FenError::FieldCount(count) => write!(f, "expected 4 to 6 fields, found {count}"),
+            FenError::RankCount(count) => write!(f, "expected 8 ranks, found {count}"),
+            FenError::RankLength(rank) => write!(f, "rank {rank} does not have 8 squares"),
+            FenError::UnknownPiece(c) => write!(f, "unknown piece '{c}'"),
+            FenError::SideToMove(side) => write!(f, "invalid side to move '{side}'"),
+            FenError::Castling(castling) => write!(f, "invalid castling rights '{castling}'"),
+            FenError::EnPessant(square) => write!(f, "invalid en pessant square '{square}'"),
+            FenError::HalfMoveClock(clock) => write!(f, "invalid half move clock '{clock}'"),
+            FenError::FullMoveCounter(counter) => {
+                write!(f, "invalid full move counter '{counter}'")
+            }
+        }
+    }
+}
+
+impl Error for FenError {}
+
+/// Reasons a position is not one that could occur in a game, as found by [Board::validate].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum PositionError {
+    /// A side does not have exactly one king.
+    KingCount { white: bool, count: u32 },
+    /// A pawn is on the first or last rank.
+    PawnOnBackRank(Square),
+    /// The side that is not to move is in check.
+    OpponentInCheck,
+    /// A side has castling rights while its king or rook has left its starting square.
+    CastlingRights,
+    /// The en pessant square is not behind a pawn that just moved two squares.
+    EnPessant(Square),
+}
+
+impl Display for PositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PositionError::KingCount { white, count } => {
+                let side = if *white { "white" } else { "black" };
+                write!(f, "{side} has {count} kings")
+            }
+            PositionError::PawnOnBackRank(square) => {
+                write!(f, "pawn on back rank at {}", square_to_algebraic(*square))
+            }
+            PositionError::OpponentInCheck => write!(f, "side not to move is in check"),
+            PositionError::CastlingRights => {
+                write!(f, "castling rights without king and rook in place")
+            }
+            PositionError::EnPessant(square) => write!(
+                f,
+                "no pawn could have moved past en pessant square {}",
+                square_to_algebraic(*square)
+            ),
+        }
+    }
+}
+
+impl Error for PositionError {}
+
+/// State that is lost when a move is made, and that is needed to take the move back.
+/// Produced by [Board::make] and consumed by [Board::unmake].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Undo {
+    /// The piece that was moved.
+    pub moved: Piece,
+    /// The piece captured by the move, if any.
+    pub captured: Option<Piece>,
+    /// The square a pawn could be captured en pessant on before the move.
+    pub en_pessant_square: Option<Square>,
+    /// The files of the rooks each side could castle with before the move,
+    /// in the order white king side, white queen side, black king side, black queen side.
+    pub castling_rooks: [Option<u8>; 4],
+    /// The half move clock before the move.
+    pub half_move_clock: u32,
+    /// The [Board::hash] before the move.
+    pub hash: u64,
 }
 
 /// Additional information about a move.
+/// It lists every piece the move moved, removed and added, and the state of the board before the move,
+/// so the move can be taken back using only this information.
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct MoveInfo {
     /// Pairs of (from, to) square indicies, indicating that the piece on `from` was moved to `to`.
     /// Castling will result in both the rook and king move being added to this list.
     pub moved_pieces: Vec<(Square, Square)>,
-    /// List of squares from which a piece was removed.
+    /// Pairs of (square, piece) indicating that a piece of kind `piece` was removed from `square`.
     /// Promotions will add a removed pawn to this list and the promoted piece to `added_pieces`.
-    pub removed_pieces: Vec<Square>,
+    pub removed_pieces: Vec<(Square, Piece)>,
     /// Pairs of (square, piece) indicating that a piece of kind `piece` was added to `square`.
     /// Promotions will add the promoted piece to this list.
     pub added_pieces: Vec<(Square, Piece)>,
+    /// The captured piece and the square it was captured on, which is behind the target square for en pessant.
+    pub captured: Option<(Square, Piece)>,
+    /// The (from, to) squares of the rook when castling.
+    pub rook_move: Option<(Square, Square)>,
+    /// The state of the board before the move, which can be given to [Board::unmake] to take the move back.
+    pub undo: Undo,
+}
+
+impl MoveInfo {
+    /// The changes that take the move back: moved pieces move back in the opposite order,
+    /// removed pieces are added and added pieces are removed. Nothing is captured when taking a move back.
+    /// The [Undo] is kept, as it is the state the board returns to.
+    pub fn inverse(&self) -> MoveInfo {
+        MoveInfo {
+            moved_pieces: self
+                .moved_pieces
+                .iter()
+                .rev()
+                .map(|&(from, to)| (to, from))
+                .collect(),
+            removed_pieces: self.added_pieces.clone(),
+            added_pieces: self.removed_pieces.clone(),
+            captured: None,
+            rook_move: self.rook_move.map(|(from, to)| (to, from)),
+            undo: self.undo,
+        }
+    }
+}
+
+/// The squares attacked by one side, and how many of its pieces attack each of them.
+/// Created by [Board::attack_map].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct AttackMap {
+    /// Every square attacked by at least one piece.
+    pub attacked: Bitboard,
+    /// The number of pieces attacking each square.
+    pub counts: [u8; 64],
+}
+
+impl AttackMap {
+    /// The number of pieces attacking a square.
+    pub fn count(&self, square: Square) -> u8 {
+        self.counts[square as usize]
+    }
 }
 
 #[allow(dead_code)]
@@ -35,22 +200,23 @@ impl Board {
     /// Create a new chess board with no pieces placed.
     pub fn blank() -> Self {
         Self {
-            pieces: [None; 64],
+            squares: [None; 64],
+            pieces: [Bitboard::EMPTY; 14],
+            colors: [Bitboard::EMPTY; 2],
             white_to_move: true,
             en_pessant_square: None,
-            can_white_castle_king_side: true,
-            can_white_castle_queen_side: true,
-            can_black_castle_king_side: true,
-            can_black_castle_queen_side: true,
+            castling_rooks: [None; 4],
+            chess960: false,
             half_move_clock: 0,
             full_move_counter: 0,
+            hash: 0,
+            pawn_hash: 0,
         }
     }
 
     /// Create a board from a Forsyth-Edwards-Notation (FEN) string.
-    pub fn from_fen(fen: &str) -> Self {
-        // TODO: Make this return Result and don't panic
-
+    /// Only the syntax is checked, use [Board::validate] to check that the position makes sense.
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
         // FEN contains 6 fields separated by space.
         // They are:
         // 1. Piece placement.
@@ -62,7 +228,7 @@ impl Board {
         // Fields 5. and 6. may be left out.
         let fields: Vec<_> = fen.split_whitespace().collect();
         if fields.len() > 6 || fields.len() < 4 {
-            panic!("Not a valid FEN string: '{fen}'");
+            return Err(FenError::FieldCount(fields.len()));
         }
 
         // Read piece placement and place onto blank board.
@@ -72,55 +238,164 @@ impl Board {
         // For example here is the standard setup:
         // rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR
         let mut board = Self::blank();
-        let placement = fields[0];
-        for (rank_idx, rank_str) in placement.split('/').enumerate() {
+        let ranks: Vec<_> = fields[0].split('/').collect();
+        if ranks.len() != 8 {
+            return Err(FenError::RankCount(ranks.len()));
+        }
+        for (rank_idx, rank_str) in ranks.into_iter().enumerate() {
             let rank: u8 = 7 - (rank_idx as u8);
             let mut file: u8 = 0;
             for piece in rank_str.chars() {
+                if file >= 8 {
+                    return Err(FenError::RankLength(rank + 1));
+                }
                 match piece {
-                    '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' => {
+                    '1'..='8' => {
                         // Skip this amount of squares
-                        file += piece.to_string().parse::<u8>().unwrap();
+                        file += piece as u8 - b'0';
                     }
                     p => {
-                        if let Some(valid) = Piece::from_char(&p) {
-                            board.place(valid, rank * 8 + file);
-                            file += 1
-                        } else {
-                            panic!("Not a valid FEN string: '{fen}'")
-                        }
+                        let valid = Piece::from_char(&p).ok_or(FenError::UnknownPiece(p))?;
+                        board.place(valid, rank * 8 + file);
+                        file += 1
                     }
                 }
             }
+            if file != 8 {
+                return Err(FenError::RankLength(rank + 1));
+            }
         }
 
         // Read whose turn it is.
         match fields[1] {
             "w" => board.white_to_move = true,
             "b" => board.white_to_move = false,
-            _ => panic!("Not a valid FEN string: '{fen}'"),
+            side => return Err(FenError::SideToMove(side.to_owned())),
         }
 
         // Read castling rights.
+        // Besides the standard 'KQkq' the rights may name the file of the castling rook,
+        // like 'HAha' in Shredder-FEN, which is needed to tell rooks apart in Chess960.
+        // 'K' and 'Q' then refer to the outermost rook on that wing, like in X-FEN.
+        // Rights are dropped when the king and rook they name are not in place, since they could never be used.
         let castling = fields[2];
-        board.can_white_castle_king_side = castling.contains("K");
-        board.can_white_castle_queen_side = castling.contains("Q");
-        board.can_black_castle_king_side = castling.contains("k");
-        board.can_black_castle_queen_side = castling.contains("q");
+        let mut rights = [false; 4];
+        if castling != "-" {
+            for c in castling.chars() {
+                let white = c.is_ascii_uppercase();
+                let (king, rook) = match white {
+                    true => (Piece::KingWhite, Piece::RookWhite),
+                    false => (Piece::KingBlack, Piece::RookBlack),
+                };
+                let back_rank = Bitboard::rank(if white { 0 } else { 7 });
+                let king_file = (board.pieces(king) & back_rank).first().map(|s| s % 8);
+                let mut rook_files = (board.pieces(rook) & back_rank).map(|s| s % 8);
+                let (wing, file) = match c.to_ascii_lowercase() {
+                    'k' => {
+                        let outermost = rook_files.filter(|&f| king_file < Some(f)).last();
+                        (KING_SIDE, outermost.unwrap_or(7))
+                    }
+                    'q' => {
+                        let outermost = rook_files.find(|&f| king_file.is_some_and(|k| f < k));
+                        (QUEEN_SIDE, outermost.unwrap_or(0))
+                    }
+                    file @ 'a'..='h' => {
+                        let file = file as u8 - b'a';
+                        match king_file.is_some_and(|k| file < k) {
+                            true => (QUEEN_SIDE, file),
+                            false => (KING_SIDE, file),
+                        }
+                    }
+                    _ => return Err(FenError::Castling(castling.to_owned())),
+                };
+                let index = castling_index(white, wing);
+                if rights[index] {
+                    return Err(FenError::Castling(castling.to_owned()));
+                }
+                rights[index] = true;
+                if board.castling_rook_in_place(index, file) {
+                    board.castling_rooks[index] = Some(file);
+                }
+            }
+        }
+        board.chess960 = !board.has_standard_castling();
 
         // Read en pessant square.
-        match fields[3] {
-            "-" => {
-                board.en_pessant_square = None;
+        // It is behind a pawn that just moved, so on rank 6 when white is to move and rank 3 when black is.
+        board.en_pessant_square = match fields[3] {
+            "-" => None,
+            square => {
+                let expected_rank = if board.white_to_move { 5 } else { 2 };
+                let square = square_from_algebraic(square)
+                    .filter(|s| s / 8 == expected_rank)
+                    .ok_or_else(|| FenError::EnPessant(square.to_owned()))?;
+                Some(square)
             }
-            square => board.en_pessant_square = Some(square_from_algebraic(square)),
-        }
+        };
 
         // Read half and full clock counts.
-        board.half_move_clock = fields[4].parse::<u32>().unwrap();
-        board.full_move_counter = fields[5].parse::<u32>().unwrap();
+        if let Some(half_move_clock) = fields.get(4) {
+            board.half_move_clock = half_move_clock
+                .parse::<u32>()
+                .map_err(|_| FenError::HalfMoveClock(half_move_clock.to_string()))?;
+        }
+        board.full_move_counter = match fields.get(5) {
+            Some(full_move_counter) => full_move_counter
+                .parse::<u32>()
+                .map_err(|_| FenError::FullMoveCounter(full_move_counter.to_string()))?,
+            None => 1,
+        };
+
+        board.hash = board.compute_hash();
+        board.pawn_hash = board.compute_pawn_hash();
+        Ok(board)
+    }
+
+    /// Checks that the position on the board could occur in a game of chess.
+    /// That is, each side has exactly one king, no pawns are on the first or last rank,
+    /// the side that just moved is not left in check, castling rights match the king and rook placement,
+    /// and any en pessant square is behind a pawn that could just have moved two squares.
+    pub fn validate(&self) -> Result<(), PositionError> {
+        use Piece::*;
+
+        for (king, white) in [(KingWhite, true), (KingBlack, false)] {
+            let count = self.pieces(king).count();
+            if count != 1 {
+                return Err(PositionError::KingCount { white, count });
+            }
+        }
+
+        let back_ranks = Bitboard::rank(0) | Bitboard::rank(7);
+        let pawns = self.pieces(PawnWhite) | self.pieces(PawnBlack);
+        if let Some(square) = (pawns & back_ranks).first() {
+            return Err(PositionError::PawnOnBackRank(square));
+        }
+
+        if self.is_opponent_in_check() {
+            return Err(PositionError::OpponentInCheck);
+        }
 
-        board
+        for (index, rook_file) in self.castling_rooks.into_iter().enumerate() {
+            if rook_file.is_some_and(|file| !self.castling_rook_in_place(index, file)) {
+                return Err(PositionError::CastlingRights);
+            }
+        }
+
+        if let Some(square) = self.en_pessant_square {
+            // The pawn that moved is in front of the en pessant square, and passed over it from the square behind.
+            let (pawn, pawn_square, origin) = match self.white_to_move {
+                true => (PawnBlack, square - 8, square + 8),
+                false => (PawnWhite, square + 8, square - 8),
+            };
+            let pawn_just_moved = self.at(pawn_square) == Some(pawn)
+                && self.at(square).is_none()
+                && self.at(origin).is_none();
+            if !pawn_just_moved {
+                return Err(PositionError::EnPessant(square));
+            }
+        }
+
+        Ok(())
     }
 
     /// Create a Forsyth-Edwards-Notation (FEN) string from the current board.
@@ -169,25 +444,7 @@ impl Board {
 
         // Write castling rights.
         fen.push(' ');
-        if self.can_white_castle_king_side {
-            fen.push('K');
-        }
-        if self.can_white_castle_queen_side {
-            fen.push('Q');
-        }
-        if self.can_black_castle_king_side {
-            fen.push('k');
-        }
-        if self.can_black_castle_queen_side {
-            fen.push('q');
-        }
-        let cant_castle = !self.can_white_castle_king_side
-            && !self.can_white_castle_queen_side
-            && !self.can_black_castle_king_side
-            && !self.can_black_castle_queen_side;
-        if cant_castle {
-            fen.push('-');
-        }
+        fen.push_str(&self.castling_field(false));
 
         // Write en pessant square
         fen.push(' ');
@@ -206,127 +463,393 @@ impl Board {
         fen
     }
 
+    /// Create a FEN string like [Board::to_fen], but with castling rights written as the files of the
+    /// castling rooks (Shredder-FEN). This is unambiguous for every Chess960 position.
+    pub fn to_shredder_fen(&self) -> String {
+        let fen = self.to_fen();
+        let mut fields: Vec<_> = fen.split(' ').collect();
+        let castling = self.castling_field(true);
+        fields[2] = &castling;
+        fields.join(" ")
+    }
+
+    /// Write the castling rights of a FEN string.
+    /// Rights are written as 'KQkq' when it is clear which rook they refer to, like in X-FEN,
+    /// otherwise or when `shredder` is set, they are written as the file of the rook.
+    fn castling_field(&self, shredder: bool) -> String {
+        let mut field = String::new();
+        for (index, rook_file) in self.castling_rooks.into_iter().enumerate() {
+            let Some(rook_file) = rook_file else {
+                continue;
+            };
+            let (white, wing) = (index < 2, index % 2);
+            let (rook, back_rank) = match white {
+                true => (Piece::RookWhite, 0),
+                false => (Piece::RookBlack, 7),
+            };
+            let mut rook_files = (self.pieces(rook) & Bitboard::rank(back_rank)).map(|s| s % 8);
+            let is_outermost = match wing {
+                KING_SIDE => !rook_files.any(|f| f > rook_file),
+                _ => !rook_files.any(|f| f < rook_file),
+            };
+            let c = match (shredder || !is_outermost, wing) {
+                (true, _) => (b'a' + rook_file) as char,
+                (false, KING_SIDE) => 'k',
+                (false, _) => 'q',
+            };
+            field.push(if white { c.to_ascii_uppercase() } else { c });
+        }
+        if field.is_empty() {
+            field.push('-');
+        }
+        field
+    }
+
+    /// Is the board set up for Chess960?
+    /// When it is, castling moves are written as the king capturing its own rook.
+    pub fn is_chess960(&self) -> bool {
+        self.chess960
+    }
+
+    /// Choose whether castling moves are written as the king capturing its own rook, like in Chess960.
+    /// Positions where castling can't be written as a standard king move always use Chess960 castling.
+    pub fn set_chess960(&mut self, chess960: bool) {
+        self.chess960 = chess960 || !self.has_standard_castling();
+    }
+
+    /// Is the king of the castling right at `index` on its back rank, with a rook on `rook_file` on the right wing?
+    fn castling_rook_in_place(&self, index: usize, rook_file: u8) -> bool {
+        let (white, wing) = (index < 2, index % 2);
+        let (king, rook, back_rank) = match white {
+            true => (Piece::KingWhite, Piece::RookWhite, 0),
+            false => (Piece::KingBlack, Piece::RookBlack, 56),
+        };
+        let king_file = (self.pieces(king) & Bitboard::rank(back_rank / 8)).first();
+        let rook_is_on_wing = match king_file.map(|s| s % 8) {
+            Some(king_file) if wing == KING_SIDE => rook_file > king_file,
+            Some(king_file) => rook_file < king_file,
+            None => false,
+        };
+        rook_is_on_wing && self.at(back_rank + rook_file) == Some(rook)
+    }
+
+    /// Do all castling rights have the king and rook on their standard squares?
+    fn has_standard_castling(&self) -> bool {
+        self.castling_rooks
+            .into_iter()
+            .enumerate()
+            .all(|(index, rook_file)| match rook_file {
+                None => true,
+                Some(rook_file) => {
+                    let (king, king_square) = match index < 2 {
+                        true => (Piece::KingWhite, 4),
+                        false => (Piece::KingBlack, 60),
+                    };
+                    let standard_file = if index % 2 == KING_SIDE { 7 } else { 0 };
+                    rook_file == standard_file && self.at(king_square) == Some(king)
+                }
+            })
+    }
+
     /// Place a [Piece] within the board without updating any other state.
     fn place(&mut self, piece: Piece, at: Square) {
-        self.pieces[at as usize] = Some(piece);
+        self.remove(at);
+        let square = Bitboard::from_square(at);
+        self.squares[at as usize] = Some(piece);
+        self.pieces[piece as usize] |= square;
+        self.colors[color_index(piece.is_white())] |= square;
+        self.hash ^= zobrist::piece_square(piece as usize, at);
+        if matches!(piece, Piece::PawnWhite | Piece::PawnBlack) {
+            self.pawn_hash ^= zobrist::piece_square(piece as usize, at);
+        }
+    }
+
+    /// Remove whatever [Piece] is on a square without updating any other state.
+    fn remove(&mut self, at: Square) -> Option<Piece> {
+        let piece = self.squares[at as usize].take()?;
+        let square = Bitboard::from_square(at);
+        self.pieces[piece as usize] ^= square;
+        self.colors[color_index(piece.is_white())] ^= square;
+        self.hash ^= zobrist::piece_square(piece as usize, at);
+        if matches!(piece, Piece::PawnWhite | Piece::PawnBlack) {
+            self.pawn_hash ^= zobrist::piece_square(piece as usize, at);
+        }
+        Some(piece)
+    }
+
+    /// The number of half moves since the last capture or pawn move, used for the fifty move rule.
+    pub fn half_move_clock(&self) -> u32 {
+        self.half_move_clock
+    }
+
+    /// The number of the full move being played, starting at 1 and incremented after black moves.
+    pub fn full_move_counter(&self) -> u32 {
+        self.full_move_counter
+    }
+
+    /// The Zobrist hash of the position.
+    /// Covers the pieces, the side to move, castling rights and a capturable en pessant square.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// The Zobrist hash of the pawns alone, for caching what only depends on the pawn structure.
+    /// Positions without pawns hash to 0.
+    pub fn pawn_hash(&self) -> u64 {
+        self.pawn_hash
+    }
+
+    /// Compute the Zobrist hash of the pawns from scratch.
+    fn compute_pawn_hash(&self) -> u64 {
+        let pawns = self.pieces(Piece::PawnWhite) | self.pieces(Piece::PawnBlack);
+        pawns.fold(0, |hash, square| {
+            let piece = self.squares[square as usize].expect("occupied square to have a piece");
+            hash ^ zobrist::piece_square(piece as usize, square)
+        })
+    }
+
+    /// Compute the Zobrist hash of the position from scratch.
+    fn compute_hash(&self) -> u64 {
+        let mut hash = self.state_hash();
+        for square in self.occupied() {
+            let piece = self.squares[square as usize].expect("occupied square to have a piece");
+            hash ^= zobrist::piece_square(piece as usize, square);
+        }
+        hash
+    }
+
+    /// The part of the Zobrist hash that isn't about piece placement.
+    fn state_hash(&self) -> u64 {
+        let mut hash = 0;
+        if !self.white_to_move {
+            hash ^= zobrist::BLACK_TO_MOVE;
+        }
+        for (rook_file, key) in self.castling_rooks.into_iter().zip(zobrist::CASTLING) {
+            if rook_file.is_some() {
+                hash ^= key;
+            }
+        }
+        // The en pessant square only changes the position if a pawn is able to capture on it.
+        if let Some(square) = self.en_pessant_square {
+            let pawn = match self.white_to_move {
+                true => Piece::PawnWhite,
+                false => Piece::PawnBlack,
+            };
+            let capturers = pawn_attacks(square, !self.white_to_move) & self.pieces(pawn);
+            if !capturers.is_empty() {
+                hash ^= zobrist::EN_PESSANT_FILE[(square % 8) as usize];
+            }
+        }
+        hash
     }
 
     /// Applies a move to the board. The move is assummed to be legal.
+    /// Returns which pieces were moved, removed and added, for displaying the move.
     pub fn apply(&mut self, r#move: Move) -> MoveInfo {
-        let (from, to) = (r#move.from, r#move.to);
+        let undo = self.make(r#move);
+        self.move_info(r#move, &undo)
+    }
+
+    /// Describe which pieces were moved, removed and added by a move just made with [Board::make].
+    pub fn move_info(&self, r#move: Move, undo: &Undo) -> MoveInfo {
+        let (from, to) = (r#move.from(), r#move.to());
+        let castling = castling_squares(r#move, undo.castling_rooks);
+
         let mut moved_pieces = Vec::new();
         let mut removed_pieces = Vec::new();
         let mut added_pieces = Vec::new();
 
         // Record captured piece.
-        let is_capture = self.at(to).is_some();
-        if is_capture {
-            removed_pieces.push(to);
-        }
+        let captured = undo.captured.map(|piece| (capture_square(r#move), piece));
+        removed_pieces.extend(captured);
 
-        // Update the state of the moved piece.
-        let piece = self.at(from).expect("move to target a piece");
-        self.pieces[from as usize] = None;
-        if let Some(promote_to) = r#move.promote_to {
-            self.pieces[to as usize] = Some(promote_to);
-            removed_pieces.push(from);
+        // Record the state of the moved pieces. Castling moves both the king and the rook.
+        if let Some([king_move, rook_move]) = castling {
+            moved_pieces.push(king_move);
+            moved_pieces.push(rook_move);
+        } else if let Some(promote_to) = r#move.promote_to() {
+            removed_pieces.push((from, undo.moved));
             added_pieces.push((to, promote_to));
         } else {
-            self.pieces[to as usize] = Some(piece);
             moved_pieces.push((from, to));
         }
 
+        MoveInfo {
+            moved_pieces,
+            removed_pieces,
+            added_pieces,
+            captured,
+            rook_move: castling.map(|[_, rook_move]| rook_move),
+            undo: *undo,
+        }
+    }
+
+    /// Makes a move on the board. The move is assummed to be legal.
+    /// Returns an [Undo] which can be given to [Board::unmake] to take the move back.
+    pub fn make(&mut self, r#move: Move) -> Undo {
+        let (from, to) = (r#move.from(), r#move.to());
+        let piece = self.at(from).expect("move to target a piece");
+        let undo = Undo {
+            moved: piece,
+            captured: None,
+            en_pessant_square: self.en_pessant_square,
+            castling_rooks: self.castling_rooks,
+            half_move_clock: self.half_move_clock,
+            hash: self.hash,
+        };
+        self.hash ^= self.state_hash();
+
+        let captured = match castling_squares(r#move, self.castling_rooks) {
+            // Castling moves both the king and the rook, which may land on each others squares in Chess960.
+            Some([(king_from, king_to), (rook_from, rook_to)]) => {
+                self.remove(king_from);
+                let rook = self
+                    .remove(rook_from)
+                    .expect("castling rook to be in place");
+                self.place(piece, king_to);
+                self.place(rook, rook_to);
+                None
+            }
+            // Otherwise update the state of the moved piece and remove the captured piece,
+            // which for en pessant is not on the target square.
+            None => {
+                self.remove(from);
+                let captured = match r#move.is_capture() {
+                    true => self.remove(capture_square(r#move)),
+                    false => None,
+                };
+                self.place(r#move.promote_to().unwrap_or(piece), to);
+                captured
+            }
+        };
+
         // Set the half clock.
         let is_pawn_move = piece == Piece::PawnWhite || piece == Piece::PawnBlack;
-        if is_pawn_move || is_capture {
+        if is_pawn_move || captured.is_some() {
             self.half_move_clock = 0;
         } else {
             self.half_move_clock += 1;
         }
 
-        // Capture en-pessant
-        let changed_file = (from % 8) != (to % 8);
-        if is_pawn_move && changed_file && !is_capture {
-            // A pawn changed file without doing a capture. This only happens by en pessant.
-            let captured_pawn_square = match self.white_to_move {
-                true => to - 8,
-                false => to + 8,
+        // Set en pessant square, which is the square the pawn passed over.
+        self.en_pessant_square = match r#move.kind() {
+            MoveKind::DoublePush => Some((from + to) / 2),
+            _ => None,
+        };
+
+        // Update castling rights. They are lost when the king moves, or when the rook moves or is captured.
+        for (index, rook_file) in self.castling_rooks.iter_mut().enumerate() {
+            let white = index < 2;
+            let king = if white {
+                Piece::KingWhite
+            } else {
+                Piece::KingBlack
             };
-            self.pieces[captured_pawn_square as usize] = None;
-            removed_pieces.push(captured_pawn_square);
+            let rook_square = rook_file.map(|f| if white { f } else { 56 + f });
+            if piece == king || rook_square == Some(from) || rook_square == Some(to) {
+                *rook_file = None;
+            }
         }
 
-        // Set en pessant square.
-        if is_pawn_move {
-            self.en_pessant_square = match to as i8 - from as i8 {
-                16 => Some(from + 8),
-                -16 => Some(from - 8),
-                _ => None,
-            }
-        } else {
-            self.en_pessant_square = None;
+        // Update whose turn it is, and increment the move counter if needed.
+        self.white_to_move = !self.white_to_move;
+        if self.white_to_move {
+            self.full_move_counter += 1;
         }
 
-        // Move the rook when a player castles.
-        match (piece, r#move) {
-            (Piece::KingWhite, Move { from: 4, to: 2, .. }) => {
-                self.pieces[0] = None;
-                self.pieces[3] = Some(Piece::RookWhite);
-                moved_pieces.push((0, 3));
-            }
-            (Piece::KingWhite, Move { from: 4, to: 6, .. }) => {
-                self.pieces[7] = None;
-                self.pieces[5] = Some(Piece::RookWhite);
-                moved_pieces.push((7, 5));
-            }
-            (
-                Piece::KingBlack,
-                Move {
-                    from: 60, to: 62, ..
-                },
-            ) => {
-                self.pieces[63] = None;
-                self.pieces[61] = Some(Piece::RookBlack);
-                moved_pieces.push((63, 61));
+        self.hash ^= self.state_hash();
+        debug_assert_eq!(
+            self.hash,
+            self.compute_hash(),
+            "incremental hash to be correct"
+        );
+        debug_assert_eq!(
+            self.pawn_hash,
+            self.compute_pawn_hash(),
+            "incremental pawn hash to be correct"
+        );
+
+        Undo { captured, ..undo }
+    }
+
+    /// Takes back a move previously made with [Board::make],
+    /// restoring the board to exactly the state it was in before the move.
+    pub fn unmake(&mut self, r#move: Move, undo: Undo) {
+        let (from, to) = (r#move.from(), r#move.to());
+
+        // Give the turn back, and decrement the move counter if needed.
+        if self.white_to_move {
+            self.full_move_counter -= 1;
+        }
+        self.white_to_move = !self.white_to_move;
+
+        match castling_squares(r#move, undo.castling_rooks) {
+            // Move both the king and the rook back when the move was castling.
+            Some([(king_from, king_to), (rook_from, rook_to)]) => {
+                self.remove(king_to);
+                let rook = self.remove(rook_to).expect("castled rook to be in place");
+                self.place(undo.moved, king_from);
+                self.place(rook, rook_from);
             }
-            (
-                Piece::KingBlack,
-                Move {
-                    from: 60, to: 58, ..
-                },
-            ) => {
-                self.pieces[56] = None;
-                self.pieces[59] = Some(Piece::RookBlack);
-                moved_pieces.push((56, 59));
+            // Otherwise move the piece back, which undoes any promotion, and put back the captured piece.
+            None => {
+                self.remove(to);
+                self.place(undo.moved, from);
+                if let Some(captured) = undo.captured {
+                    self.place(captured, capture_square(r#move));
+                }
             }
-            _ => {}
         }
 
-        // Update castling rights
-        self.can_white_castle_queen_side &= !(piece == Piece::KingWhite || from == 0 || to == 0);
-        self.can_white_castle_king_side &= !(piece == Piece::KingWhite || from == 7 || to == 7);
-        self.can_black_castle_queen_side &= !(piece == Piece::KingBlack || from == 56 || to == 56);
-        self.can_black_castle_king_side &= !(piece == Piece::KingBlack || from == 63 || to == 63);
+        self.en_pessant_square = undo.en_pessant_square;
+        self.castling_rooks = undo.castling_rooks;
+        self.half_move_clock = undo.half_move_clock;
+        self.hash = undo.hash;
+    }
 
-        // Update whose turn it is, and increment the move counter if needed.
+    /// Passes the turn to the other side without moving a piece.
+    /// This isn't a legal move, but the search uses it to see if a position is good enough
+    /// that giving the opponent a free move doesn't spoil it. It must not be made while in check.
+    /// Take it back by keeping a copy of the board.
+    pub fn make_null(&mut self) {
+        self.hash ^= self.state_hash();
+        self.en_pessant_square = None;
+        self.half_move_clock += 1;
         self.white_to_move = !self.white_to_move;
         if self.white_to_move {
             self.full_move_counter += 1;
         }
+        self.hash ^= self.state_hash();
+    }
 
-        return MoveInfo {
-            moved_pieces,
-            removed_pieces,
-            added_pieces,
-        };
+    /// When a move is castling in the current position,
+    /// get the (from, to) squares of the king and the rook, in that order.
+    pub fn castling(&self, r#move: Move) -> Option<[(Square, Square); 2]> {
+        castling_squares(r#move, self.castling_rooks)
     }
 
     /// Lookup what piece is at a particular square in the board.
     pub fn at(&self, square: Square) -> Option<Piece> {
-        *self.pieces.get(square as usize)?
+        *self.squares.get(square as usize)?
+    }
+
+    /// The squares occupied by a particular piece.
+    pub fn pieces(&self, piece: Piece) -> Bitboard {
+        self.pieces[piece as usize]
+    }
+
+    /// The squares occupied by white or black pieces.
+    pub fn side(&self, white: bool) -> Bitboard {
+        self.colors[color_index(white)]
+    }
+
+    /// The squares occupied by any piece.
+    pub fn occupied(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
     }
 
-    fn is_in_check(&self) -> bool {
+    /// Is the side to move in check?
+    pub fn is_in_check(&self) -> bool {
         self.is_side_in_check(self.white_to_move)
     }
 
@@ -334,377 +857,503 @@ impl Board {
         self.is_side_in_check(!self.white_to_move)
     }
 
-    /// Does this move leave the current player in check?
-    /// The check is performed by applying the move,
-    /// then checking if the opponent is in check.
-    fn would_leave_in_check(&self, r#move: Move) -> bool {
-        let mut b = (*self).clone();
-        b.apply(r#move);
-        b.is_opponent_in_check()
-    }
-
     /// Is black or white in check?
     fn is_side_in_check(&self, check_white: bool) -> bool {
-        // Get position of players king
-        let king_pos = self.pieces.iter().position(|p| match p {
-            Some(Piece::KingWhite) if check_white => true,
-            Some(Piece::KingBlack) if !check_white => true,
-            _ => false,
-        });
-
-        // Pretend that the players king is any other piece of the same color.
-        // If the piece is able to capture a piece of the same kind,
-        // then the king is in check.
-        // So for example if we pretend the white king is a white knight,
-        // and that knight can capture a black knight,
-        // that means the black knight is checking the white king.
-        if let Some(king_pos) = king_pos {
-            use Piece::*;
-            let pieces = if check_white {
-                [
-                    QueenWhite,
-                    BishopWhite,
-                    RookWhite,
-                    KnightWhite,
-                    PawnWhite,
-                    KingWhite,
-                ]
-            } else {
-                [
-                    QueenBlack,
-                    BishopBlack,
-                    RookBlack,
-                    KnightBlack,
-                    PawnBlack,
-                    KingBlack,
-                ]
-            };
-            for piece in pieces {
-                let moves = generate_piece_moves(self, piece, king_pos as u8, true);
-                let has_capture = moves
-                    .iter()
-                    .find(|m| {
-                        self.at(m.to)
-                            .is_some_and(|other| other.is_same_kind(&piece))
-                    })
-                    .is_some();
-
-                if has_capture {
-                    return true;
-                }
+        let king = if check_white {
+            Piece::KingWhite
+        } else {
+            Piece::KingBlack
+        };
+        self.pieces(king)
+            .first()
+            .is_some_and(|king_pos| self.is_square_attacked(king_pos, !check_white))
+    }
+
+    /// Is a square attacked by any piece of the given color?
+    pub fn is_square_attacked(&self, square: Square, by_white: bool) -> bool {
+        !self.attackers_of(square, by_white).is_empty()
+    }
+
+    /// The squares of the pieces of the given color that attack a square.
+    pub fn attackers_of(&self, square: Square, white: bool) -> Bitboard {
+        self.attackers(square, white, self.occupied())
+    }
+
+    /// The squares attacked by the piece on a square, as used for generating its moves.
+    /// Empty if the square is empty.
+    pub fn attacks_from(&self, square: Square) -> Bitboard {
+        match self.squares[square as usize] {
+            Some(piece) => piece_attacks(piece, square, self.occupied()),
+            None => Bitboard::EMPTY,
+        }
+    }
+
+    /// Every square attacked by the given color, along with how many pieces attack each square.
+    /// Pieces attack the squares they could capture on, which includes squares with pieces of their own color.
+    pub fn attack_map(&self, white: bool) -> AttackMap {
+        let occupied = self.occupied();
+        let mut map = AttackMap {
+            attacked: Bitboard::EMPTY,
+            counts: [0; 64],
+        };
+        for square in self.side(white) {
+            let piece = self.squares[square as usize].expect("occupied square to have a piece");
+            let attacks = piece_attacks(piece, square, occupied);
+            map.attacked |= attacks;
+            for target in attacks {
+                map.counts[target as usize] += 1;
             }
         }
+        map
+    }
+
+    /// The pieces of the given color attacking a square,
+    /// as if the board was occupied by `occupied` for the purpose of blocking sliding pieces.
+    fn attackers(&self, square: Square, by_white: bool, occupied: Bitboard) -> Bitboard {
+        use Piece::*;
+
+        // Attacks are symmetric, so a piece attacks the square exactly when
+        // a piece of the same kind on the square would attack it back.
+        // Pawns are the exception, as they attack forwards,
+        // so look from the square with a pawn of the defending color instead.
+        let [pawn, knight, bishop, rook, queen, king] = if by_white {
+            [
+                PawnWhite,
+                KnightWhite,
+                BishopWhite,
+                RookWhite,
+                QueenWhite,
+                KingWhite,
+            ]
+        } else {
+            [
+                PawnBlack,
+                KnightBlack,
+                BishopBlack,
+                RookBlack,
+                QueenBlack,
+                KingBlack,
+            ]
+        };
+        (pawn_attacks(square, !by_white) & self.pieces(pawn))
+            | (knight_attacks(square) & self.pieces(knight))
+            | (bishop_attacks(square, occupied) & (self.pieces(bishop) | self.pieces(queen)))
+            | (rook_attacks(square, occupied) & (self.pieces(rook) | self.pieces(queen)))
+            | (king_attacks(square) & self.pieces(king))
+    }
+
+    /// Find the pieces of the given color that are pinned to their king.
+    /// Returns, for every square, the squares a piece on it may move to without exposing the king.
+    /// That is the line between the king and the pinning piece for pinned pieces, and everywhere otherwise.
+    fn pin_rays(&self, king_square: Square, white: bool) -> [Bitboard; 64] {
+        use Piece::*;
+
+        let (bishop, rook, queen) = match white {
+            true => (BishopBlack, RookBlack, QueenBlack),
+            false => (BishopWhite, RookWhite, QueenWhite),
+        };
+        let enemy = self.side(!white);
 
-        false
+        // Enemy sliders that would attack the king if none of the kings own pieces were in the way.
+        let snipers = (bishop_attacks(king_square, enemy)
+            & (self.pieces(bishop) | self.pieces(queen)))
+            | (rook_attacks(king_square, enemy) & (self.pieces(rook) | self.pieces(queen)));
+
+        let mut pin_rays = [Bitboard::FULL; 64];
+        for sniper in snipers {
+            let blockers = between(king_square, sniper) & self.occupied();
+            if let Some(pinned) = blockers.first().filter(|_| blockers.count() == 1) {
+                pin_rays[pinned as usize] =
+                    between(king_square, sniper) | Bitboard::from_square(sniper);
+            }
+        }
+        pin_rays
     }
 
     /// Generate all legal [Move]s possible within the current [Board].
-    pub fn generate_moves(&self) -> Vec<Move> {
-        self.generate_pseudo_moves()
-            .into_iter()
-            .filter(|&m| !self.would_leave_in_check(m))
-            .collect()
+    /// The pieces giving check and the pinned pieces are found up front,
+    /// so that only moves which keep the king safe are generated.
+    pub fn generate_moves(&self) -> MoveList {
+        self.generate_legal_moves(Generate::All)
     }
 
-    /// Generate all legal [Move]s possible within the current [Board].
-    pub fn generate_square_moves(&self, from: Square) -> Vec<Move> {
-        if let Some(piece) = self.at(from) {
-            return generate_piece_moves(self, piece, from, false)
-                .into_iter()
-                .filter(|&m| !self.would_leave_in_check(m))
-                .collect();
+    /// Generate the legal captures and promotions, including en pessant and promotions that capture nothing.
+    pub fn generate_captures(&self) -> MoveList {
+        self.generate_legal_moves(Generate::Captures)
+    }
+
+    /// Generate the legal moves that neither capture nor promote, including castling.
+    /// Together with [Board::generate_captures] this is every legal move.
+    pub fn generate_quiets(&self) -> MoveList {
+        self.generate_legal_moves(Generate::Quiets)
+    }
+
+    /// Generate the legal moves that neither capture nor promote, but give check.
+    pub fn generate_quiet_checks(&self) -> MoveList {
+        let mut moves = self.generate_quiets();
+        moves.retain(|&m| self.gives_check(m));
+        moves
+    }
+
+    /// Does a move put the opponent in check? The move is assummed to be legal.
+    pub fn gives_check(&self, r#move: Move) -> bool {
+        let mut after = *self;
+        after.make(r#move);
+        after.is_in_check()
+    }
+
+    /// Is a move legal in the current position?
+    /// Useful for checking moves that were found in another position, like a move from a hash table.
+    pub fn is_legal(&self, r#move: Move) -> bool {
+        let Some(piece) = self.at(r#move.from()) else {
+            return false;
+        };
+        let mut moves = MoveList::new();
+        generate_piece_moves(self, piece, r#move.from(), false, &mut moves);
+        if piece.is_white() != self.white_to_move || !moves.contains(&r#move) {
+            return false;
+        }
+        let mut after = *self;
+        after.make(r#move);
+        !after.is_opponent_in_check()
+    }
+
+    /// Generate the legal moves of a kind.
+    fn generate_legal_moves(&self, kind: Generate) -> MoveList {
+        let white = self.white_to_move;
+        let king = if white {
+            Piece::KingWhite
         } else {
-            return Vec::new();
+            Piece::KingBlack
+        };
+        let Some(king_square) = self.pieces(king).first() else {
+            // Without a king there's nothing to keep safe.
+            let mut moves = self.generate_pseudo_moves();
+            moves.retain(|m| kind.includes(m.is_tactical()));
+            return moves;
+        };
+        let king_bit = Bitboard::from_square(king_square);
+        let checkers = self.attackers(king_square, !white, self.occupied());
+
+        // Captures land on the opponent's pieces and quiet moves on empty squares.
+        let targets = match kind {
+            Generate::All => !self.side(white),
+            Generate::Captures => self.side(!white),
+            Generate::Quiets => !self.occupied(),
+        };
+
+        // The king may step to any square that isn't attacked once it has left its current square,
+        // since a slider checking the king also attacks the squares behind it.
+        let mut moves: MoveList = (king_attacks(king_square) & targets)
+            .filter(|&to| {
+                self.attackers(to, !white, self.occupied() ^ king_bit)
+                    .is_empty()
+            })
+            .map(|to| piece_move(self, king_square, to))
+            .collect();
+        if checkers.is_empty() && kind != Generate::Captures {
+            castling_moves(self, king_square, &mut moves);
+        }
+
+        // In double check only the king can move.
+        if checkers.count() > 1 {
+            return moves;
+        }
+
+        // In check other pieces must capture the checking piece or block the check.
+        let check_mask = match checkers.first() {
+            Some(checker) => checkers | between(king_square, checker),
+            None => Bitboard::FULL,
+        };
+        let pin_rays = self.pin_rays(king_square, white);
+
+        let mut candidates = MoveList::new();
+        for square in self.side(white) ^ king_bit {
+            let piece = self.squares[square as usize].expect("occupied square to have a piece");
+            generate_piece_moves(self, piece, square, true, &mut candidates);
+        }
+        for &m in candidates.iter() {
+            if !kind.includes(m.is_tactical()) {
+                continue;
+            }
+            let is_legal = match m.is_en_pessant() {
+                true => self.is_legal_en_pessant(m, king_square),
+                false => (check_mask & pin_rays[m.from() as usize]).contains(m.to()),
+            };
+            if is_legal {
+                moves.push(m);
+            }
         }
+
+        moves
+    }
+
+    /// Does capturing en pessant keep the king safe?
+    /// Both the capturing and the captured pawn leave their squares,
+    /// which can expose the king along the rank they were both on,
+    /// and the captured pawn may itself be the piece giving check.
+    fn is_legal_en_pessant(&self, r#move: Move, king_square: Square) -> bool {
+        let captured = Bitboard::from_square(capture_square(r#move));
+        let occupied = (self.occupied() ^ Bitboard::from_square(r#move.from()) ^ captured)
+            | Bitboard::from_square(r#move.to());
+        (self.attackers(king_square, !self.white_to_move, occupied) & !captured).is_empty()
+    }
+
+    /// Generate all legal [Move]s for the piece on a square.
+    pub fn generate_square_moves(&self, from: Square) -> MoveList {
+        let mut moves = self.generate_moves();
+        moves.retain(|m| m.from() == from);
+        moves
     }
 
     /// Generate all pseudo-legal [Move]s possible within the current [Board].
     /// A pseudo legal move may leave the player in check.
-    pub fn generate_pseudo_moves(&self) -> Vec<Move> {
-        let mut moves = Vec::new();
+    pub fn generate_pseudo_moves(&self) -> MoveList {
+        let mut moves = MoveList::new();
 
-        for rank in (0..8).rev() {
-            for file in 0..8 {
-                let square = rank * 8 + file;
-                match self.at(square) {
-                    // Square had a piece with the color whose turn it is
-                    Some(piece) if self.white_to_move == piece.is_white() => {
-                        let mut piece_moves = generate_piece_moves(self, piece, square, false);
-                        moves.append(&mut piece_moves);
-                    }
-                    // Square was empty, or had the wrong color piece.
-                    _ => continue,
-                }
-            }
+        // Only squares with a piece of the color whose turn it is can move.
+        for square in self.side(self.white_to_move) {
+            let piece = self.squares[square as usize].expect("occupied square to have a piece");
+            generate_piece_moves(self, piece, square, false, &mut moves);
         }
 
         moves
     }
 }
 
-/// Generate the valid moves for a particular piece on a certain square within a board.
-fn generate_piece_moves(board: &Board, piece: Piece, at: Square, skip_castling: bool) -> Vec<Move> {
-    match piece {
-        Piece::PawnWhite | Piece::PawnBlack => {
-            let rank = at / 8;
-            let file = at % 8;
+/// The kinds of legal moves to generate.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Generate {
+    All,
+    /// Captures and promotions.
+    Captures,
+    /// Every other move.
+    Quiets,
+}
 
-            let mut moves: Vec<Move> = vec![];
+impl Generate {
+    /// Should a move be generated, given whether it is a capture or promotion?
+    fn includes(self, is_tactical: bool) -> bool {
+        match self {
+            Generate::All => true,
+            Generate::Captures => is_tactical,
+            Generate::Quiets => !is_tactical,
+        }
+    }
+}
 
-            // A pawn may move one square towards the opposing player.
-            let advance_square = match piece.is_white() {
-                true if rank < 8 => Some((rank + 1) * 8 + file),
-                false if rank > 0 => Some((rank - 1) * 8 + file),
-                _ => None,
-            };
+/// Index into [Board::colors] for a side.
+fn color_index(white: bool) -> usize {
+    (!white) as usize
+}
 
-            // If it is in it's starting rank, it may leap two squares.
-            let leap_square = match piece.is_white() {
-                true if rank == 1 => Some((rank + 2) * 8 + file),
-                false if rank == 6 => Some((rank - 2) * 8 + file),
-                _ => None,
-            };
+/// Castling towards the h-file.
+const KING_SIDE: usize = 0;
+/// Castling towards the a-file.
+const QUEEN_SIDE: usize = 1;
 
-            if advance_square.is_some_and(|s| board.at(s).is_none()) {
-                moves.push(Move::new(at, advance_square.unwrap()));
-                if leap_square.is_some_and(|s| board.at(s).is_none()) {
-                    moves.push(Move::new(at, leap_square.unwrap()));
-                }
-            }
+/// Index into [Board::castling_rooks] for a side and wing,
+/// which orders them white king side, white queen side, black king side, black queen side.
+fn castling_index(white: bool, wing: usize) -> usize {
+    color_index(white) * 2 + wing
+}
 
-            // A pawn may capture diagonally.
-            let capture_l = match piece.is_white() {
-                true if rank < 8 && file > 0 => Some((rank + 1) * 8 + file - 1),
-                false if rank > 0 && file > 0 => Some((rank - 1) * 8 + file - 1),
-                _ => None,
-            };
+/// The square on which a move captures.
+/// This is the target square, except for en pessant where the captured pawn is behind it.
+fn capture_square(r#move: Move) -> Square {
+    let (from, to) = (r#move.from(), r#move.to());
+    match r#move.kind() {
+        // The captured pawn is beside the capturing pawn, on the file it moves to.
+        MoveKind::EnPessant => from / 8 * 8 + to % 8,
+        _ => to,
+    }
+}
 
-            let can_capture_l = capture_l.map(|s| {
-                let sees_enemy_piece = matches!(
-                    board.at(s),
-                    Some(other) if other.is_white() != piece.is_white()
-                );
-                let en_pessant = board.en_pessant_square.is_some_and(|es| es == s);
-                sees_enemy_piece || en_pessant
-            }) == Some(true);
-            if can_capture_l {
-                moves.push(Move::new(at, capture_l.unwrap()));
-            }
-            let capture_r = match piece.is_white() {
-                true if rank < 8 && file < 7 => Some((rank + 1) * 8 + file + 1),
-                false if rank > 0 && file < 7 => Some((rank - 1) * 8 + file + 1),
-                _ => None,
-            };
-            let can_capture_r = capture_r.map(|s| {
-                let sees_enemy_piece = matches!(
-                    board.at(s),
-                    Some(other) if other.is_white() != piece.is_white()
-                );
-                let en_pessant = board.en_pessant_square.is_some_and(|es| es == s);
-                sees_enemy_piece || en_pessant
-            }) == Some(true);
-
-            if can_capture_r {
-                moves.push(Move::new(at, capture_r.unwrap()));
-            }
+/// A move of a piece other than a pawn to a square, which captures if the square is occupied.
+fn piece_move(board: &Board, from: Square, to: Square) -> Move {
+    let kind = match board.occupied().contains(to) {
+        true => MoveKind::Capture,
+        false => MoveKind::Quiet,
+    };
+    Move::new(from, to, kind)
+}
 
-            moves
-                .iter()
-                // Handle promotions.
-                .flat_map(|&Move { from, to, .. }| {
-                    use Piece::*;
-                    match (piece.is_white(), to / 8) {
-                        // White pawn made it to rank 8. Expand the move to all possible promotions.
-                        (true, 7) => [KnightWhite, BishopWhite, RookWhite, QueenWhite]
-                            .iter()
-                            .map(|&p| Move {
-                                from,
-                                to,
-                                promote_to: Some(p),
-                            })
-                            .collect::<Vec<Move>>(),
-                        // Black pawn made it to rank 1. Expand the move to all possible promotions.
-                        (false, 0) => [KnightBlack, BishopBlack, RookBlack, QueenBlack]
-                            .iter()
-                            .map(|&p| Move {
-                                from,
-                                to,
-                                promote_to: Some(p),
-                            })
-                            .collect::<Vec<Move>>(),
-
-                        _ => vec![Move::new(from, to)],
-                    }
-                })
-                .collect()
-        }
-        Piece::KnightWhite | Piece::KnightBlack => {
-            // Knights may move two squares orthogonally and then one square along the other orthogonal axis.
-            // That comes out to 8 unique squares to land on.
-            // . x . x .
-            // x . . . x
-            // . . n . .
-            // x . . . x
-            // . x . x .
-            let rank = at as i8 / 8;
-            let file = at as i8 % 8;
-            [
-                (rank + 2, file + 1),
-                (rank + 2, file - 1),
-                (rank - 2, file + 1),
-                (rank - 2, file - 1),
-                (rank + 1, file + 2),
-                (rank + 1, file - 2),
-                (rank - 1, file + 2),
-                (rank - 1, file - 2),
-            ]
-            .into_iter()
-            .filter(|(r, f)| !(*r > 7 || *f > 7 || *r < 0 || *f < 0))
-            .map(|(r, f)| to_board_square(r * 8 + f).unwrap())
-            // A knight may land on a square with a opposite colored piece or no piece.
-            .filter(|&square| match board.at(square) {
-                None => true,
-                Some(other) if other.is_white() != piece.is_white() => true,
-                _ => false,
-            })
-            .map(|square| Move::new(at, square))
-            .collect()
-        }
-        Piece::KingWhite | Piece::KingBlack => {
-            // The king may move to any surrounding square.
-            let rank = at as i8 / 8;
-            let file = at as i8 % 8;
-            let mut moves: Vec<Move> = [
-                (rank + 1, file + 1),
-                (rank + 1, file),
-                (rank + 1, file - 1),
-                (rank - 1, file + 1),
-                (rank - 1, file),
-                (rank - 1, file - 1),
-                (rank, file + 1),
-                (rank, file - 1),
-            ]
-            .into_iter()
-            .filter(|(r, f)| !(*r > 7 || *f > 7 || *r < 0 || *f < 0))
-            .map(|(r, f)| to_board_square(r * 8 + f).unwrap())
-            // The king may land on a square with a opposite colored piece or no piece.
-            .filter(|&square| match board.at(square) {
-                Some(other) if other.is_white() != piece.is_white() => true,
-                None => true,
-                _ => false,
-            })
-            .map(|square| Move::new(at, square))
-            .collect();
+/// The squares a piece on a square attacks, given the occupied squares of the board.
+fn piece_attacks(piece: Piece, at: Square, occupied: Bitboard) -> Bitboard {
+    match piece {
+        // Pawns attack the two squares diagonally in front of them.
+        Piece::PawnWhite | Piece::PawnBlack => pawn_attacks(at, piece.is_white()),
+        // Knights may move two squares orthogonally and then one square along the other orthogonal axis.
+        Piece::KnightWhite | Piece::KnightBlack => knight_attacks(at),
+        Piece::RookWhite | Piece::RookBlack => rook_attacks(at, occupied),
+        Piece::BishopWhite | Piece::BishopBlack => bishop_attacks(at, occupied),
+        Piece::QueenWhite | Piece::QueenBlack => queen_attacks(at, occupied),
+        // The king may move to any surrounding square.
+        Piece::KingWhite | Piece::KingBlack => king_attacks(at),
+    }
+}
 
-            if !skip_castling {
-                // Castling queen side for white.
-                if board.white_to_move
-                // Still has castling rights
-                && board.can_white_castle_queen_side
-                // No piece is obstructing the castling
-                && board.pieces[3].is_none() && board.pieces[2].is_none() && board.pieces[1].is_none()
-                // Player is not in check
-                && !board.is_in_check()
-                // Check that d1 is not attacked, since the king can't castle through check.
-                && !board.would_leave_in_check(Move::new(4, 3))
-                {
-                    moves.push(Move::new(at, 2));
-                }
-                // Castling king side for white.
-                if board.white_to_move
-                // Still has castling rights
-                && board.can_white_castle_king_side
-                // No piece is obstructing the castling
-                && board.pieces[5].is_none() && board.pieces[6].is_none()
-                // Player is not in check
-                && !board.is_in_check()
-                // Check that e1 is not attacked, since the king can't castle through check.
-                && !board.would_leave_in_check(Move::new(4, 5))
-                {
-                    moves.push(Move::new(at, 6));
-                }
-                // Castling queen side for black.
-                if !board.white_to_move
-                // Still has castling rights
-                && board.can_black_castle_queen_side
-                // No piece is obstructing the castling
-                && board.pieces[59].is_none() && board.pieces[58].is_none() && board.pieces[57].is_none()
-                // Player is not in check
-                && !board.is_in_check()
-                // Check that d8 is not attacked, since the king can't castle through check.
-                && !board.would_leave_in_check(Move::new(60, 59))
-                {
-                    moves.push(Move::new(at, 58));
-                }
-                // Castling king side for black.
-                if !board.white_to_move
-                // Still has castling rights
-                && board.can_black_castle_king_side
-                // No piece is obstructing the castling
-                && board.pieces[61].is_none() && board.pieces[62].is_none()
-                // Player is not in check
-                && !board.is_in_check()
-                // Check that e8 is not attacked, since the king can't castle through check.
-                && !board.would_leave_in_check(Move::new(60, 61))
-                {
-                    moves.push(Move::new(at, 62));
-                }
-            }
+/// Generate the valid moves for a particular piece on a certain square within a board,
+/// adding them to `moves`.
+fn generate_piece_moves(
+    board: &Board,
+    piece: Piece,
+    at: Square,
+    skip_castling: bool,
+    moves: &mut MoveList,
+) {
+    let occupied = board.occupied();
+    let own = board.side(piece.is_white());
 
-            moves
-        }
-        Piece::RookWhite | Piece::RookBlack => {
-            let directions = [(1, 0), (-1, 0), (0, 1), (0, -1)];
-            sliding_moves(&directions, board, piece, at)
+    if piece == Piece::PawnWhite || piece == Piece::PawnBlack {
+        return pawn_moves(board, piece, at, moves);
+    }
+    let targets = piece_attacks(piece, at, occupied);
+
+    // Pieces may land on a square with a opposite colored piece or no piece.
+    moves.extend((targets & !own).map(|square| piece_move(board, at, square)));
+
+    let is_king = piece == Piece::KingWhite || piece == Piece::KingBlack;
+    if is_king && !skip_castling && piece.is_white() == board.white_to_move {
+        castling_moves(board, at, moves);
+    }
+}
+
+/// Generate the castling moves of the king on `at`, for the side to move, adding them to `moves`.
+fn castling_moves(board: &Board, at: Square, moves: &mut MoveList) {
+    let white = board.white_to_move;
+    let back_rank = if white { 0 } else { 56 };
+    let rook = if white {
+        Piece::RookWhite
+    } else {
+        Piece::RookBlack
+    };
+    if at / 8 != back_rank / 8 {
+        return;
+    }
+
+    for wing in [KING_SIDE, QUEEN_SIDE] {
+        // Still has castling rights, with the rook in place on its side of the king
+        let Some(rook_file) = board.castling_rooks[castling_index(white, wing)] else {
+            continue;
+        };
+        let rook_from = back_rank + rook_file;
+        let rook_is_on_wing = match wing {
+            KING_SIDE => rook_from > at,
+            _ => rook_from < at,
+        };
+        if !rook_is_on_wing || board.at(rook_from) != Some(rook) {
+            continue;
         }
-        Piece::BishopWhite | Piece::BishopBlack => {
-            let directions = [(1, 1), (-1, -1), (-1, 1), (1, -1)];
-            sliding_moves(&directions, board, piece, at)
+        let (king_to, rook_to) = match wing {
+            KING_SIDE => (back_rank + 6, back_rank + 5),
+            _ => (back_rank + 2, back_rank + 3),
+        };
+
+        // No piece other than the king and rook is obstructing the castling
+        let king_path = between(at, king_to) | Bitboard::from_square(king_to);
+        let rook_path = between(rook_from, rook_to) | Bitboard::from_square(rook_to);
+        let others =
+            board.occupied() ^ Bitboard::from_square(at) ^ Bitboard::from_square(rook_from);
+        if !(others & (king_path | rook_path)).is_empty() {
+            continue;
         }
-        Piece::QueenWhite | Piece::QueenBlack => {
-            let directions = [
-                (1, 0),
-                (-1, 0),
-                (0, 1),
-                (0, -1),
-                (1, 1),
-                (-1, -1),
-                (-1, 1),
-                (1, -1),
-            ];
-            sliding_moves(&directions, board, piece, at)
+
+        // Player is not in check, and the king does not move through or into an attacked square.
+        // The castling rook may be shielding the square the king lands on, so look past it.
+        let passes_attacked = between(at, king_to).any(|s| board.is_square_attacked(s, !white));
+        let occupied_after =
+            others | Bitboard::from_square(king_to) | Bitboard::from_square(rook_to);
+        let lands_attacked = !board.attackers(king_to, !white, occupied_after).is_empty();
+        if board.is_in_check() || passes_attacked || lands_attacked {
+            continue;
         }
+
+        let to = if board.chess960 { rook_from } else { king_to };
+        let kind = match wing {
+            KING_SIDE => MoveKind::KingCastle,
+            _ => MoveKind::QueenCastle,
+        };
+        moves.push(Move::new(at, to, kind));
     }
 }
 
-/// Helper function for computing sliding moves in both orthogonal and diagonal directions.
-fn sliding_moves(directions: &[(i8, i8)], board: &Board, piece: Piece, at: Square) -> Vec<Move> {
-    let mut moves: Vec<Move> = Vec::new();
-    let rank = at as i8 / 8;
-    let file = at as i8 % 8;
-    for (ro, fo) in directions {
-        let mut r = rank;
-        let mut f = file;
-        loop {
-            r += ro;
-            f += fo;
-            if r > 7 || f > 7 || r < 0 || f < 0 {
-                break;
-            }
-            let square = to_board_square(r * 8 + f).unwrap();
-            match board.at(square) {
-                None => moves.push(Move::new(at, square)),
-                Some(other) => {
-                    if other.is_white() != piece.is_white() {
-                        moves.push(Move::new(at, square))
-                    }
-                    break;
-                }
-            }
+/// Generate the pushes, captures and promotions of a pawn, adding them to `moves`.
+fn pawn_moves(board: &Board, piece: Piece, at: Square, moves: &mut MoveList) {
+    let is_white = piece.is_white();
+    let occupied = board.occupied();
+    let mut pushes = Bitboard::EMPTY;
+
+    // A pawn may move one square towards the opposing player.
+    // If it is in it's starting rank, it may leap two squares.
+    let (advance_square, leap_square) = match is_white {
+        true => ((at < 56).then(|| at + 8), (at / 8 == 1).then(|| at + 16)),
+        false => ((at >= 8).then(|| at - 8), (at / 8 == 6).then(|| at - 16)),
+    };
+    if let Some(advance_square) = advance_square.filter(|&s| !occupied.contains(s)) {
+        pushes |= Bitboard::from_square(advance_square);
+        if let Some(leap_square) = leap_square.filter(|&s| !occupied.contains(s)) {
+            moves.push(Move::new(at, leap_square, MoveKind::DoublePush));
         }
     }
 
-    moves
+    // A pawn may capture diagonally, including en pessant.
+    let attacks = pawn_attacks(at, is_white);
+    let captures = attacks & board.side(!is_white);
+    if let Some(en_pessant) = board.en_pessant_square.filter(|&s| attacks.contains(s)) {
+        moves.push(Move::new(at, en_pessant, MoveKind::EnPessant));
+    }
+
+    // Expand moves to the last rank into all possible promotions.
+    let promotions = {
+        use Piece::*;
+        [KnightWhite, BishopWhite, RookWhite, QueenWhite]
+    };
+    for to in pushes | captures {
+        let capture = captures.contains(to);
+        if to / 8 == 7 || to / 8 == 0 {
+            moves.extend(promotions.iter().map(|&p| {
+                let kind = MoveKind::promotion(p, capture).expect("pawns to promote to pieces");
+                Move::new(at, to, kind)
+            }));
+        } else {
+            let kind = match capture {
+                true => MoveKind::Capture,
+                false => MoveKind::Quiet,
+            };
+            moves.push(Move::new(at, to, kind));
+        }
+    }
+}
+
+/// When a move is castling, get the (from, to) squares of the king and the rook, in that order.
+/// Castling is written as the king moving two squares towards the rook,
+/// or as the king capturing its own rook in Chess960,
+/// so the rook is found from the castling rights the move was made with.
+fn castling_squares(
+    r#move: Move,
+    castling_rooks: [Option<u8>; 4],
+) -> Option<[(Square, Square); 2]> {
+    let wing = match r#move.kind() {
+        MoveKind::KingCastle => KING_SIDE,
+        MoveKind::QueenCastle => QUEEN_SIDE,
+        _ => return None,
+    };
+    let from = r#move.from();
+    let white = from < 8;
+    let back_rank = from / 8 * 8;
+    // Only a king on its own back rank can castle.
+    if back_rank != if white { 0 } else { 56 } {
+        return None;
+    }
+    let rook_file = castling_rooks[castling_index(white, wing)]?;
+
+    // The king and rook always end up on the same squares as in standard chess.
+    let (king_to, rook_to) = match wing {
+        KING_SIDE => (back_rank + 6, back_rank + 5),
+        _ => (back_rank + 2, back_rank + 3),
+    };
+    Some([(from, king_to), (back_rank + rook_file, rook_to)])
 }
 
 impl Display for Board {
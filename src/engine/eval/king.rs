@@ -0,0 +1,70 @@
+//! King safety: how hard the enemy pieces are attacking the king,
+//! and how well the pawns in front of it shelter it.
+
+use super::mobility::KingAttack;
+use super::params::{KING_ATTACK, KING_ATTACKERS, KING_FILE, PAWN_SHIELD, PAWN_STORM};
+use super::pawns::ranks_ahead;
+use super::{Bitboard, Board, Piece, Terms};
+
+impl Terms<'_> {
+    /// Score the attack of one side on the enemy king.
+    /// A single attacker rarely gets anywhere, so which pieces attack and how many squares
+    /// only counts once at least two pieces join in.
+    pub fn king_attack(&mut self, white: bool, attack: KingAttack) {
+        self.add(
+            KING_ATTACKERS,
+            attack.attackers.min(KING_ATTACKERS.len - 1),
+            white,
+            1,
+        );
+        if attack.attackers < 2 {
+            return;
+        }
+        for (kind, hits) in attack.hits.into_iter().enumerate() {
+            self.add(KING_ATTACK, kind, white, hits);
+        }
+    }
+
+    /// Score the pawns and open files on the file of the king of one side and the files next to it.
+    /// A king on the edge is sheltered by the three files closest to it.
+    pub fn king_shelter(&mut self, board: &Board, white: bool) {
+        let (king, own, enemy) = match white {
+            true => (Piece::KingWhite, Piece::PawnWhite, Piece::PawnBlack),
+            false => (Piece::KingBlack, Piece::PawnBlack, Piece::PawnWhite),
+        };
+        let Some(king) = board.pieces(king).first() else {
+            return;
+        };
+        let own = board.pieces(own);
+        let enemy = board.pieces(enemy);
+        let ahead = ranks_ahead(king, white);
+        // The number of ranks from the king to the closest of some pawns in front of it.
+        let closest = |pawns: Bitboard| {
+            let square = match white {
+                true => pawns.first(),
+                false => pawns.last(),
+            }?;
+            Some((square / 8).abs_diff(king / 8) as usize)
+        };
+
+        let center = (king % 8).clamp(1, 6);
+        for file in center - 1..=center + 1 {
+            let file = Bitboard::file(file);
+            let shield = match closest(own & file & ahead) {
+                Some(ranks @ 1..=2) => ranks - 1,
+                _ => 2,
+            };
+            self.add(PAWN_SHIELD, shield, white, 1);
+            if let Some(ranks) = closest(enemy & file & ahead) {
+                self.add(PAWN_STORM, ranks.min(PAWN_STORM.len) - 1, white, 1);
+            }
+            if (own & file).is_empty() {
+                let index = match (enemy & file).is_empty() {
+                    true => 0,
+                    false => 1,
+                };
+                self.add(KING_FILE, index, white, 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,55 @@
+//! Mobility: a piece that can go to many squares is worth more than one that is hemmed in.
+//! While counting the squares, the attacks on the enemy king are tallied for [king safety](super::king).
+
+use super::params::{MOBILITY, MOBILITY_OFFSETS};
+use super::{BLACK_PIECES, Bitboard, Board, Terms, WHITE_PIECES};
+use crate::bitboard::{king_attacks, pawn_attacks};
+
+/// The attacks of one side on the enemy king and the squares around it.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub struct KingAttack {
+    /// The number of pieces attacking the king zone.
+    pub attackers: usize,
+    /// The number of king zone squares attacked by knights, bishops, rooks and queens.
+    pub hits: [i32; 4],
+}
+
+impl Terms<'_> {
+    /// Score the mobility of the knights, bishops, rooks and queens of one side,
+    /// returning their attacks on the enemy king.
+    pub fn mobility(&mut self, board: &Board, white: bool) -> KingAttack {
+        let (pieces, enemy) = match white {
+            true => (WHITE_PIECES, BLACK_PIECES),
+            false => (BLACK_PIECES, WHITE_PIECES),
+        };
+        // Squares a piece could only go to by giving itself up to a pawn don't count.
+        let enemy_pawn_attacks = board
+            .pieces(enemy[0])
+            .fold(Bitboard::EMPTY, |attacks, square| {
+                attacks | pawn_attacks(square, !white)
+            });
+        let area = !(board.side(white) | enemy_pawn_attacks);
+        let zone = board
+            .pieces(enemy[5])
+            .first()
+            .map_or(Bitboard::EMPTY, |king| {
+                king_attacks(king) | Bitboard::from_square(king)
+            });
+
+        let mut attack = KingAttack::default();
+        for kind in 1..5 {
+            for square in board.pieces(pieces[kind]) {
+                let attacks = board.attacks_from(square);
+                let squares = (attacks & area).count() as usize;
+                self.add(MOBILITY, MOBILITY_OFFSETS[kind - 1] + squares, white, 1);
+
+                let hits = (attacks & zone).count() as i32;
+                if hits > 0 {
+                    attack.attackers += 1;
+                    attack.hits[kind - 1] += hits;
+                }
+            }
+        }
+        attack
+    }
+}
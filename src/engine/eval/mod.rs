@@ -0,0 +1,315 @@
+//! Static evaluation: a score in centipawns for a position, without searching any moves.
+//!
+//! Every term is computed separately as a middlegame and an endgame value, for white and for black.
+//! The difference is blended by the game phase, which goes down as pieces are traded,
+//! so that for example the king is kept safe early on but walks to the center in the endgame.
+
+mod king;
+mod mobility;
+pub mod params;
+mod pawns;
+
+use super::bitboard::Bitboard;
+use super::board::Board;
+use super::piece::Piece;
+use params::{
+    BISHOP_PAIR, MATERIAL, PARAM_COUNT, PIECE_SQUARE, Params, ROOK_FILE, TEMPO, TERMS, Taper, Term,
+};
+use pawns::PawnTable;
+use std::fmt::Display;
+
+/// The phase of the starting position. Positions with more material are counted as this.
+pub const MAX_PHASE: i32 = 24;
+
+/// How much each type of piece counts towards the game phase, from pawn to king.
+const PHASE_WEIGHTS: [i32; 6] = [0, 1, 1, 2, 4, 0];
+
+/// The pieces of each color, from pawn to king.
+const WHITE_PIECES: [Piece; 6] = {
+    use Piece::*;
+    [
+        PawnWhite,
+        KnightWhite,
+        BishopWhite,
+        RookWhite,
+        QueenWhite,
+        KingWhite,
+    ]
+};
+const BLACK_PIECES: [Piece; 6] = {
+    use Piece::*;
+    [
+        PawnBlack,
+        KnightBlack,
+        BishopBlack,
+        RookBlack,
+        QueenBlack,
+        KingBlack,
+    ]
+};
+
+/// Evaluates positions with a set of [Params], caching the evaluation of pawn structures.
+#[derive(Clone, Default, Debug)]
+pub struct Evaluator {
+    params: Params,
+    pawns: PawnTable,
+}
+
+impl Evaluator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_params(params: Params) -> Self {
+        Self {
+            params,
+            pawns: PawnTable::new(),
+        }
+    }
+
+    pub fn params(&self) -> &Params {
+        &self.params
+    }
+
+    /// Use other weights, forgetting the pawn structures scored with the old ones.
+    pub fn set_params(&mut self, params: Params) {
+        self.params = params;
+        self.pawns.clear();
+    }
+
+    /// The score of the position in centipawns, for the side to move.
+    pub fn evaluate(&mut self, board: &Board) -> i32 {
+        let mut terms = Terms {
+            params: &self.params,
+            total: Taper::default(),
+            pawns: Some(&mut self.pawns),
+            trace: None,
+        };
+        terms.evaluate(board)
+    }
+
+    /// Evaluate the position, listing how much each term contributes to the score.
+    /// The pawn structure is scored term by term rather than looked up.
+    pub fn trace(&self, board: &Board) -> Evaluation {
+        let mut evaluation = Evaluation {
+            terms: TERMS
+                .iter()
+                .map(|&term| TermScore {
+                    term,
+                    white: Taper::default(),
+                    black: Taper::default(),
+                })
+                .collect(),
+            phase: phase(board),
+            white_to_move: board.white_to_move,
+            score: 0,
+            coefficients: vec![0; PARAM_COUNT],
+        };
+        let mut terms = Terms {
+            params: &self.params,
+            total: Taper::default(),
+            pawns: None,
+            trace: Some(&mut evaluation),
+        };
+        let score = terms.evaluate(board);
+        evaluation.score = score;
+        evaluation
+    }
+}
+
+/// How much of the middlegame is left, from 0 with only pawns and kings to [MAX_PHASE] in the starting position.
+pub fn phase(board: &Board) -> i32 {
+    let phase = (0..6)
+        .map(|kind| {
+            let count =
+                board.pieces(WHITE_PIECES[kind]).count() + board.pieces(BLACK_PIECES[kind]).count();
+            PHASE_WEIGHTS[kind] * count as i32
+        })
+        .sum::<i32>();
+    phase.min(MAX_PHASE)
+}
+
+/// Blend a middlegame and an endgame value by the game phase.
+pub fn blend(value: Taper, phase: i32) -> i32 {
+    (value.mg * phase + value.eg * (MAX_PHASE - phase)) / MAX_PHASE
+}
+
+/// Adds up the weighted terms of the evaluation, from white's side, optionally recording them in a trace.
+struct Terms<'a> {
+    params: &'a Params,
+    total: Taper,
+    /// Where pawn structures are cached, unless every term has to be counted for a trace.
+    pawns: Option<&'a mut PawnTable>,
+    trace: Option<&'a mut Evaluation>,
+}
+
+impl Terms<'_> {
+    /// Count the parameter at `index` of a term `count` times for one side.
+    fn add(&mut self, term: Term, index: usize, white: bool, count: i32) {
+        let value = self.params.get(term, index) * count;
+        match white {
+            true => self.total += value,
+            false => self.total -= value,
+        }
+        if let Some(trace) = &mut self.trace {
+            let score = trace
+                .terms
+                .iter_mut()
+                .find(|score| score.term == term)
+                .expect("every term to be traced");
+            match white {
+                true => score.white += value,
+                false => score.black += value,
+            }
+            trace.coefficients[term.offset + index] += match white {
+                true => count,
+                false => -count,
+            };
+        }
+    }
+
+    fn evaluate(&mut self, board: &Board) -> i32 {
+        let passed = self.pawn_structure(board);
+        for (white, passed) in [true, false].into_iter().zip(passed) {
+            self.pieces(board, white);
+            self.passed_pawns(board, white, passed);
+            let attack = self.mobility(board, white);
+            self.king_attack(white, attack);
+            self.king_shelter(board, white);
+        }
+        self.add(TEMPO, 0, board.white_to_move, 1);
+
+        let score = blend(self.total, phase(board));
+        match board.white_to_move {
+            true => score,
+            false => -score,
+        }
+    }
+
+    /// Material, piece placement, and the bonuses for individual pieces of one side.
+    fn pieces(&mut self, board: &Board, white: bool) {
+        let (pieces, own_pawns, enemy_pawns) = match white {
+            true => (WHITE_PIECES, Piece::PawnWhite, Piece::PawnBlack),
+            false => (BLACK_PIECES, Piece::PawnBlack, Piece::PawnWhite),
+        };
+        for (kind, piece) in pieces.into_iter().enumerate() {
+            let squares = board.pieces(piece);
+            if kind < MATERIAL.len {
+                self.add(MATERIAL, kind, white, squares.count() as i32);
+            }
+            for square in squares {
+                // The tables are seen from white's side, so black's pieces use the mirrored square.
+                let relative = match white {
+                    true => square,
+                    false => square ^ 56,
+                };
+                self.add(PIECE_SQUARE, kind * 64 + relative as usize, white, 1);
+            }
+        }
+
+        if board.pieces(pieces[2]).count() >= 2 {
+            self.add(BISHOP_PAIR, 0, white, 1);
+        }
+        for square in board.pieces(pieces[3]) {
+            let file = Bitboard::file(square % 8);
+            if (file & board.pieces(own_pawns)).is_empty() {
+                let index = match (file & board.pieces(enemy_pawns)).is_empty() {
+                    true => 0,
+                    false => 1,
+                };
+                self.add(ROOK_FILE, index, white, 1);
+            }
+        }
+    }
+}
+
+/// What one term of the evaluation contributes for each side, before blending by phase.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct TermScore {
+    pub term: Term,
+    pub white: Taper,
+    pub black: Taper,
+}
+
+impl TermScore {
+    /// The contribution to the score from white's side.
+    pub fn total(&self) -> Taper {
+        self.white - self.black
+    }
+}
+
+/// The evaluation of a position broken down by term, created by [Evaluator::trace].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Evaluation {
+    /// Every term, in the order of [TERMS].
+    pub terms: Vec<TermScore>,
+    pub phase: i32,
+    pub white_to_move: bool,
+    /// The score for the side to move, as returned by [Evaluator::evaluate].
+    pub score: i32,
+    /// How many times each parameter counts for white minus for black, in the order of [Params::values].
+    /// The evaluation from white's side is each parameter times its coefficient, summed up and blended by phase.
+    pub coefficients: Vec<i32>,
+}
+
+impl Evaluation {
+    /// The sum of every term from white's side, before blending by phase.
+    pub fn total(&self) -> Taper {
+        self.terms
+            .iter()
+            .fold(Taper::default(), |total, score| total + score.total())
+    }
+}
+
+impl Display for Evaluation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:>20} | {:>6} {:>6} | {:>6} {:>6} | {:>6} {:>6} | {:>6}",
+            "term", "white", "", "black", "", "total", "", "blend"
+        )?;
+        writeln!(
+            f,
+            "{:>20} | {:>6} {:>6} | {:>6} {:>6} | {:>6} {:>6} |",
+            "", "mg", "eg", "mg", "eg", "mg", "eg"
+        )?;
+        for score in &self.terms {
+            let total = score.total();
+            writeln!(
+                f,
+                "{:>20} | {:>6} {:>6} | {:>6} {:>6} | {:>6} {:>6} | {:>6}",
+                score.term.name,
+                score.white.mg,
+                score.white.eg,
+                score.black.mg,
+                score.black.eg,
+                total.mg,
+                total.eg,
+                blend(total, self.phase)
+            )?;
+        }
+        let total = self.total();
+        writeln!(
+            f,
+            "{:>20} | {:>6} {:>6} | {:>6} {:>6} | {:>6} {:>6} | {:>6}",
+            "total",
+            "",
+            "",
+            "",
+            "",
+            total.mg,
+            total.eg,
+            blend(total, self.phase)
+        )?;
+        write!(
+            f,
+            "phase {}/{MAX_PHASE}, score {} for {}",
+            self.phase,
+            self.score,
+            match self.white_to_move {
+                true => "white",
+                false => "black",
+            }
+        )
+    }
+}
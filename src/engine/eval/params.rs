@@ -0,0 +1,568 @@
+//! The weights of the evaluation, kept in one flat list so that they can be listed, changed and tuned together.
+
+use std::error::Error;
+use std::fmt::Display;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+/// A pair of values, one for the middlegame and one for the endgame,
+/// which the evaluation blends by how much material is left on the board.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub struct Taper {
+    pub mg: i32,
+    pub eg: i32,
+}
+
+/// Shorthand for a [Taper] with the given middlegame and endgame values.
+pub const fn taper(mg: i32, eg: i32) -> Taper {
+    Taper { mg, eg }
+}
+
+impl Add for Taper {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        taper(self.mg + rhs.mg, self.eg + rhs.eg)
+    }
+}
+
+impl Sub for Taper {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        taper(self.mg - rhs.mg, self.eg - rhs.eg)
+    }
+}
+
+impl Neg for Taper {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        taper(-self.mg, -self.eg)
+    }
+}
+
+impl Mul<i32> for Taper {
+    type Output = Self;
+
+    fn mul(self, rhs: i32) -> Self {
+        taper(self.mg * rhs, self.eg * rhs)
+    }
+}
+
+impl AddAssign for Taper {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Taper {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl Display for Taper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.mg, self.eg)
+    }
+}
+
+/// A named group of related parameters, like the value of each type of piece,
+/// stored from `offset` to `offset + len` in [Params].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Term {
+    pub name: &'static str,
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl Term {
+    const fn first(name: &'static str, len: usize) -> Self {
+        Self {
+            name,
+            offset: 0,
+            len,
+        }
+    }
+
+    /// A term stored right after this one.
+    const fn then(self, name: &'static str, len: usize) -> Self {
+        Self {
+            name,
+            offset: self.end(),
+            len,
+        }
+    }
+
+    /// The offset just past the last parameter of the term.
+    pub const fn end(self) -> usize {
+        self.offset + self.len
+    }
+}
+
+/// The value of a pawn, knight, bishop, rook and queen. Kings can't be traded and have no value.
+pub const MATERIAL: Term = Term::first("material", 5);
+/// A bonus for each type of piece on each square, indexed by `piece * 64 + square` with pawns as piece 0.
+/// Squares are seen from white's side, so a black piece on e7 uses the entry for e2.
+pub const PIECE_SQUARE: Term = MATERIAL.then("piece_square", 6 * 64);
+/// A bonus for having two or more bishops.
+pub const BISHOP_PAIR: Term = PIECE_SQUARE.then("bishop_pair", 1);
+/// A bonus for a rook on a file without pawns, and on a file with only enemy pawns.
+pub const ROOK_FILE: Term = BISHOP_PAIR.then("rook_file", 2);
+/// A bonus for a pawn without enemy pawns in front of it on its own or the adjacent files, by its rank.
+/// Ranks are counted from the pawn's own side, from 0 to 7.
+pub const PASSED_PAWN: Term = ROOK_FILE.then("passed_pawn", 8);
+/// Weights for the distance of the own and the enemy king from the square in front of a passed pawn,
+/// counted once for each rank the pawn has advanced beyond its second rank.
+pub const PASSED_KING_DISTANCE: Term = PASSED_PAWN.then("passed_king_distance", 2);
+/// A penalty for a pawn without own pawns on the adjacent files.
+pub const ISOLATED_PAWN: Term = PASSED_KING_DISTANCE.then("isolated_pawn", 1);
+/// A penalty for each pawn behind another own pawn on its file.
+pub const DOUBLED_PAWN: Term = ISOLATED_PAWN.then("doubled_pawn", 1);
+/// A penalty for a pawn that has fallen behind the pawns on the adjacent files
+/// and can't advance without being captured by an enemy pawn.
+pub const BACKWARD_PAWN: Term = DOUBLED_PAWN.then("backward_pawn", 1);
+/// A bonus for a pawn defended by an own pawn or next to one, by its rank.
+pub const CONNECTED_PAWN: Term = BACKWARD_PAWN.then("connected_pawn", 8);
+/// A penalty for each group of pawns on adjacent files.
+pub const PAWN_ISLAND: Term = CONNECTED_PAWN.then("pawn_island", 1);
+/// A bonus for a pawn on a file without enemy pawns in front of it,
+/// that has at least as many own pawns to help it advance as enemy pawns to stop it, by its rank.
+pub const CANDIDATE_PASSER: Term = PAWN_ISLAND.then("candidate_passer", 8);
+/// A bonus for each number of squares a knight, bishop, rook and queen can move to,
+/// indexed by `MOBILITY_OFFSETS[piece] + squares` with knights as piece 0.
+/// Squares with own pieces or attacked by enemy pawns aren't counted.
+pub const MOBILITY: Term = CANDIDATE_PASSER.then("mobility", 9 + 14 + 15 + 28);
+/// Where the mobility of knights, bishops, rooks and queens starts within [MOBILITY].
+pub const MOBILITY_OFFSETS: [usize; 4] = [0, 9, 23, 38];
+/// A bonus for each square around the enemy king attacked by a knight, bishop, rook and queen,
+/// counted when at least two pieces attack the king.
+pub const KING_ATTACK: Term = MOBILITY.then("king_attack", 4);
+/// A bonus by the number of pieces attacking the squares around the enemy king, up to seven.
+pub const KING_ATTACKERS: Term = KING_ATTACK.then("king_attackers", 8);
+/// For each file next to and in front of the king: a bonus for an own pawn one or two ranks ahead of the king,
+/// and a penalty for having neither.
+pub const PAWN_SHIELD: Term = KING_ATTACKERS.then("pawn_shield", 3);
+/// A penalty for the closest enemy pawn advancing on each file next to and in front of the king,
+/// by how many ranks ahead of the king it is, from one to four or more.
+pub const PAWN_STORM: Term = PAWN_SHIELD.then("pawn_storm", 4);
+/// A penalty for each file next to or in front of the king without any pawns, and without own pawns.
+pub const KING_FILE: Term = PAWN_STORM.then("king_file", 2);
+/// A bonus for being the side to move.
+pub const TEMPO: Term = KING_FILE.then("tempo", 1);
+
+/// Every term of the evaluation, in the order they are stored.
+pub const TERMS: [Term; 19] = [
+    MATERIAL,
+    PIECE_SQUARE,
+    BISHOP_PAIR,
+    ROOK_FILE,
+    PASSED_PAWN,
+    PASSED_KING_DISTANCE,
+    ISOLATED_PAWN,
+    DOUBLED_PAWN,
+    BACKWARD_PAWN,
+    CONNECTED_PAWN,
+    PAWN_ISLAND,
+    CANDIDATE_PASSER,
+    MOBILITY,
+    KING_ATTACK,
+    KING_ATTACKERS,
+    PAWN_SHIELD,
+    PAWN_STORM,
+    KING_FILE,
+    TEMPO,
+];
+
+/// The number of parameters of the evaluation.
+pub const PARAM_COUNT: usize = TEMPO.end();
+
+/// Every weight of the evaluation, as a flat list of [Taper]s grouped by [Term].
+///
+/// Parameters are written to text one per line, as the name of the term, the index within the term
+/// and the middlegame and endgame values, like 'bishop_pair 0 30 50'. Reading them back starts from
+/// [DEFAULT_PARAMS] and replaces the parameters listed, so files written before a term was added still load.
+/// Empty lines and lines starting with '#' are skipped.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Params {
+    values: [Taper; PARAM_COUNT],
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        DEFAULT_PARAMS
+    }
+}
+
+impl Params {
+    /// The parameter at `index` within a term.
+    pub fn get(&self, term: Term, index: usize) -> Taper {
+        debug_assert!(
+            index < term.len,
+            "index {index} out of range for {}",
+            term.name
+        );
+        self.values[term.offset + index]
+    }
+
+    /// The parameters of a term.
+    pub fn term(&self, term: Term) -> &[Taper] {
+        &self.values[term.offset..term.end()]
+    }
+
+    pub fn term_mut(&mut self, term: Term) -> &mut [Taper] {
+        &mut self.values[term.offset..term.end()]
+    }
+
+    /// Every parameter, in the order of [TERMS].
+    pub fn values(&self) -> &[Taper] {
+        &self.values
+    }
+
+    pub fn values_mut(&mut self) -> &mut [Taper] {
+        &mut self.values
+    }
+}
+
+impl Display for Params {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# term index mg eg")?;
+        for term in TERMS {
+            for (index, value) in self.term(term).iter().enumerate() {
+                writeln!(f, "{} {index} {value}", term.name)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reasons parameters could not be read from text.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum ParamsError {
+    /// This line (counting from 1) did not have a term, an index and two values.
+    Syntax(usize),
+    /// There is no term of this name.
+    UnknownTerm(String),
+    /// The term has no parameter at this index.
+    Index(String, usize),
+}
+
+impl Display for ParamsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamsError::Syntax(line) => {
+                write!(f, "expected 'term index mg eg' on line {line}")
+            }
+            ParamsError::UnknownTerm(name) => write!(f, "unknown term '{name}'"),
+            ParamsError::Index(name, index) => write!(f, "no parameter {index} in term '{name}'"),
+        }
+    }
+}
+
+impl Error for ParamsError {}
+
+impl FromStr for Params {
+    type Err = ParamsError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut params = DEFAULT_PARAMS;
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let [name, index, mg, eg] = fields[..] else {
+                return Err(ParamsError::Syntax(number + 1));
+            };
+            let (Ok(index), Ok(mg), Ok(eg)) = (index.parse(), mg.parse(), eg.parse()) else {
+                return Err(ParamsError::Syntax(number + 1));
+            };
+            let term = TERMS
+                .into_iter()
+                .find(|term| term.name == name)
+                .ok_or_else(|| ParamsError::UnknownTerm(name.to_owned()))?;
+            if index >= term.len {
+                return Err(ParamsError::Index(name.to_owned(), index));
+            }
+            params.term_mut(term)[index] = taper(mg, eg);
+        }
+        Ok(params)
+    }
+}
+
+const MATERIAL_VALUES: [Taper; 5] = [
+    taper(82, 94),
+    taper(337, 281),
+    taper(365, 297),
+    taper(477, 512),
+    taper(1025, 936),
+];
+
+// Piece-square tables as seen from white's side, written with the eighth rank first like a diagram.
+
+#[rustfmt::skip]
+const PAWN_MG: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+     98, 134,  61,  95,  68, 126,  34, -11,
+     -6,   7,  26,  31,  65,  56,  25, -20,
+    -14,  13,   6,  21,  23,  12,  17, -23,
+    -27,  -2,  -5,  12,  17,   6,  10, -25,
+    -26,  -4,  -4, -10,   3,   3,  33, -12,
+    -35,  -1, -20, -23, -15,  24,  38, -22,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+#[rustfmt::skip]
+const PAWN_EG: [i32; 64] = [
+      0,   0,   0,   0,   0,   0,   0,   0,
+    178, 173, 158, 134, 147, 132, 165, 187,
+     94, 100,  85,  67,  56,  53,  82,  84,
+     32,  24,  13,   5,  -2,   4,  17,  17,
+     13,   9,  -3,  -7,  -7,  -8,   3,  -1,
+      4,   7,  -6,   1,   0,  -5,  -1,  -8,
+     13,   8,   8,  10,  13,   0,   2,  -7,
+      0,   0,   0,   0,   0,   0,   0,   0,
+];
+#[rustfmt::skip]
+const KNIGHT_MG: [i32; 64] = [
+   -167, -89, -34, -49,  61, -97, -15,-107,
+    -73, -41,  72,  36,  23,  62,   7, -17,
+    -47,  60,  37,  65,  84, 129,  73,  44,
+     -9,  17,  19,  53,  37,  69,  18,  22,
+    -13,   4,  16,  13,  28,  19,  21,  -8,
+    -23,  -9,  12,  10,  19,  17,  25, -16,
+    -29, -53, -12,  -3,  -1,  18, -14, -19,
+   -105, -21, -58, -33, -17, -28, -19, -23,
+];
+#[rustfmt::skip]
+const KNIGHT_EG: [i32; 64] = [
+    -58, -38, -13, -28, -31, -27, -63, -99,
+    -25,  -8, -25,  -2,  -9, -25, -24, -52,
+    -24, -20,  10,   9,  -1,  -9, -19, -41,
+    -17,   3,  22,  22,  22,  11,   8, -18,
+    -18,  -6,  16,  25,  16,  17,   4, -18,
+    -23,  -3,  -1,  15,  10,  -3, -20, -22,
+    -42, -20, -10,  -5,  -2, -20, -23, -44,
+    -29, -51, -23, -15, -22, -18, -50, -64,
+];
+#[rustfmt::skip]
+const BISHOP_MG: [i32; 64] = [
+    -29,   4, -82, -37, -25, -42,   7,  -8,
+    -26,  16, -18, -13,  30,  59,  18, -47,
+    -16,  37,  43,  40,  35,  50,  37,  -2,
+     -4,   5,  19,  50,  37,  37,   7,  -2,
+     -6,  13,  13,  26,  34,  12,  10,   4,
+      0,  15,  15,  15,  14,  27,  18,  10,
+      4,  15,  16,   0,   7,  21,  33,   1,
+    -33,  -3, -14, -21, -13, -12, -39, -21,
+];
+#[rustfmt::skip]
+const BISHOP_EG: [i32; 64] = [
+    -14, -21, -11,  -8,  -7,  -9, -17, -24,
+     -8,  -4,   7, -12,  -3, -13,  -4, -14,
+      2,  -8,   0,  -1,  -2,   6,   0,   4,
+     -3,   9,  12,   9,  14,  10,   3,   2,
+     -6,   3,  13,  19,   7,  10,  -3,  -9,
+    -12,  -3,   8,  10,  13,   3,  -7, -15,
+    -14, -18,  -7,  -1,   4,  -9, -15, -27,
+    -23,  -9, -23,  -5,  -9, -16,  -5, -17,
+];
+#[rustfmt::skip]
+const ROOK_MG: [i32; 64] = [
+     32,  42,  32,  51,  63,   9,  31,  43,
+     27,  32,  58,  62,  80,  67,  26,  44,
+     -5,  19,  26,  36,  17,  45,  61,  16,
+    -24, -11,   7,  26,  24,  35,  -8, -20,
+    -36, -26, -12,  -1,   9,  -7,   6, -23,
+    -45, -25, -16, -17,   3,   0,  -5, -33,
+    -44, -16, -20,  -9,  -1,  11,  -6, -71,
+    -19, -13,   1,  17,  16,   7, -37, -26,
+];
+#[rustfmt::skip]
+const ROOK_EG: [i32; 64] = [
+     13,  10,  18,  15,  12,  12,   8,   5,
+     11,  13,  13,  11,  -3,   3,   8,   3,
+      7,   7,   7,   5,   4,  -3,  -5,  -3,
+      4,   3,  13,   1,   2,   1,  -1,   2,
+      3,   5,   8,   4,  -5,  -6,  -8, -11,
+     -4,   0,  -5,  -1,  -7, -12,  -8, -16,
+     -6,  -6,   0,   2,  -9,  -9, -11,  -3,
+     -9,   2,   3,  -1,  -5, -13,   4, -20,
+];
+#[rustfmt::skip]
+const QUEEN_MG: [i32; 64] = [
+    -28,   0,  29,  12,  59,  44,  43,  45,
+    -24, -39,  -5,   1, -16,  57,  28,  54,
+    -13, -17,   7,   8,  29,  56,  47,  57,
+    -27, -27, -16, -16,  -1,  17,  -2,   1,
+     -9, -26,  -9, -10,  -2,  -4,   3,  -3,
+    -14,   2, -11,  -2,  -5,   2,  14,   5,
+    -35,  -8,  11,   2,   8,  15,  -3,   1,
+     -1, -18,  -9,  10, -15, -25, -31, -50,
+];
+#[rustfmt::skip]
+const QUEEN_EG: [i32; 64] = [
+     -9,  22,  22,  27,  27,  19,  10,  20,
+    -17,  20,  32,  41,  58,  25,  30,   0,
+    -20,   6,   9,  49,  47,  35,  19,   9,
+      3,  22,  24,  45,  57,  40,  57,  36,
+    -18,  28,  19,  47,  31,  34,  39,  23,
+    -16, -27,  15,   6,   9,  17,  10,   5,
+    -22, -23, -30, -16, -16, -23, -36, -32,
+    -33, -28, -22, -43,  -5, -32, -20, -41,
+];
+#[rustfmt::skip]
+const KING_MG: [i32; 64] = [
+    -65,  23,  16, -15, -56, -34,   2,  13,
+     29,  -1, -20,  -7,  -8,  -4, -38, -29,
+     -9,  24,   2, -16, -20,   6,  22, -22,
+    -17, -20, -12, -27, -30, -25, -14, -36,
+    -49,  -1, -27, -39, -46, -44, -33, -51,
+    -14, -14, -22, -46, -44, -30, -15, -27,
+      1,   7,  -8, -64, -43, -16,   9,   8,
+    -15,  36,  12, -54,   8, -28,  24,  14,
+];
+#[rustfmt::skip]
+const KING_EG: [i32; 64] = [
+    -74, -35, -18, -18, -11,  15,   4, -17,
+    -12,  17,  14,  17,  17,  38,  23,  11,
+     10,  17,  23,  15,  20,  45,  44,  13,
+     -8,  22,  24,  27,  26,  33,  26,   3,
+    -18,  -4,  21,  24,  27,  23,   9, -11,
+    -19,  -3,  11,  21,  23,  16,   7,  -9,
+    -27, -11,   4,  13,  14,   4,  -5, -17,
+    -53, -34, -21, -11, -28, -14, -24, -43,
+];
+
+const PASSED_PAWN_VALUES: [Taper; 8] = [
+    taper(0, 0),
+    taper(0, 5),
+    taper(5, 10),
+    taper(10, 20),
+    taper(25, 40),
+    taper(40, 70),
+    taper(60, 110),
+    taper(0, 0),
+];
+
+const CONNECTED_PAWN_VALUES: [Taper; 8] = [
+    taper(0, 0),
+    taper(3, 0),
+    taper(5, 3),
+    taper(8, 6),
+    taper(15, 15),
+    taper(30, 40),
+    taper(50, 80),
+    taper(0, 0),
+];
+
+const CANDIDATE_PASSER_VALUES: [Taper; 8] = [
+    taper(0, 0),
+    taper(3, 5),
+    taper(3, 8),
+    taper(6, 12),
+    taper(12, 25),
+    taper(20, 40),
+    taper(0, 0),
+    taper(0, 0),
+];
+
+/// For each of knights, bishops, rooks and queens: the number of squares it usually has,
+/// and what each square more or less is worth.
+const MOBILITY_WEIGHTS: [(i32, Taper); 4] = [
+    (4, taper(4, 4)),
+    (6, taper(5, 5)),
+    (6, taper(2, 4)),
+    (12, taper(1, 2)),
+];
+
+const KING_ATTACKERS_VALUES: [Taper; 8] = [
+    taper(0, 0),
+    taper(0, 0),
+    taper(10, 0),
+    taper(30, 5),
+    taper(60, 10),
+    taper(90, 15),
+    taper(120, 20),
+    taper(150, 25),
+];
+
+const PIECE_SQUARE_TABLES: [([i32; 64], [i32; 64]); 6] = [
+    (PAWN_MG, PAWN_EG),
+    (KNIGHT_MG, KNIGHT_EG),
+    (BISHOP_MG, BISHOP_EG),
+    (ROOK_MG, ROOK_EG),
+    (QUEEN_MG, QUEEN_EG),
+    (KING_MG, KING_EG),
+];
+
+/// The hand-picked weights the evaluation uses unless it is given others.
+pub const DEFAULT_PARAMS: Params = {
+    let mut values = [taper(0, 0); PARAM_COUNT];
+    let mut i = 0;
+    while i < MATERIAL.len {
+        values[MATERIAL.offset + i] = MATERIAL_VALUES[i];
+        i += 1;
+    }
+    let mut i = 0;
+    while i < PIECE_SQUARE.len {
+        let (mg, eg) = PIECE_SQUARE_TABLES[i / 64];
+        // Flip the rank, as the tables list the eighth rank first.
+        let square = (i % 64) ^ 56;
+        values[PIECE_SQUARE.offset + i] = taper(mg[square], eg[square]);
+        i += 1;
+    }
+    values[BISHOP_PAIR.offset] = taper(30, 50);
+    values[ROOK_FILE.offset] = taper(40, 10);
+    values[ROOK_FILE.offset + 1] = taper(20, 5);
+    let mut i = 0;
+    while i < 8 {
+        values[PASSED_PAWN.offset + i] = PASSED_PAWN_VALUES[i];
+        values[CONNECTED_PAWN.offset + i] = CONNECTED_PAWN_VALUES[i];
+        values[CANDIDATE_PASSER.offset + i] = CANDIDATE_PASSER_VALUES[i];
+        i += 1;
+    }
+    values[PASSED_KING_DISTANCE.offset] = taper(0, -3);
+    values[PASSED_KING_DISTANCE.offset + 1] = taper(0, 6);
+    values[ISOLATED_PAWN.offset] = taper(-10, -12);
+    values[DOUBLED_PAWN.offset] = taper(-10, -20);
+    values[BACKWARD_PAWN.offset] = taper(-8, -10);
+    values[PAWN_ISLAND.offset] = taper(-5, -10);
+    let mut piece = 0;
+    while piece < 4 {
+        let (usual, weight) = MOBILITY_WEIGHTS[piece];
+        let end = match piece {
+            3 => MOBILITY.len,
+            _ => MOBILITY_OFFSETS[piece + 1],
+        };
+        let mut i = MOBILITY_OFFSETS[piece];
+        while i < end {
+            let squares = (i - MOBILITY_OFFSETS[piece]) as i32;
+            values[MOBILITY.offset + i] =
+                taper(weight.mg * (squares - usual), weight.eg * (squares - usual));
+            i += 1;
+        }
+        piece += 1;
+    }
+    values[KING_ATTACK.offset] = taper(8, 0);
+    values[KING_ATTACK.offset + 1] = taper(6, 0);
+    values[KING_ATTACK.offset + 2] = taper(10, 0);
+    values[KING_ATTACK.offset + 3] = taper(15, 0);
+    let mut i = 0;
+    while i < KING_ATTACKERS.len {
+        values[KING_ATTACKERS.offset + i] = KING_ATTACKERS_VALUES[i];
+        i += 1;
+    }
+    values[PAWN_SHIELD.offset] = taper(15, 0);
+    values[PAWN_SHIELD.offset + 1] = taper(8, 0);
+    values[PAWN_SHIELD.offset + 2] = taper(-10, 0);
+    values[PAWN_STORM.offset] = taper(-5, 0);
+    values[PAWN_STORM.offset + 1] = taper(-25, 0);
+    values[PAWN_STORM.offset + 2] = taper(-12, 0);
+    values[PAWN_STORM.offset + 3] = taper(-4, 0);
+    values[KING_FILE.offset] = taper(-20, 0);
+    values[KING_FILE.offset + 1] = taper(-12, 0);
+    values[TEMPO.offset] = taper(15, 5);
+    Params { values }
+};
@@ -0,0 +1,218 @@
+//! Pawn structure: passed, isolated, doubled, backward and connected pawns, pawn islands and candidate passers.
+//!
+//! Pawns move rarely and never sideways, so the same structure comes up in most positions of a search.
+//! Everything that only depends on the pawns is cached in a [PawnTable] by [Board::pawn_hash].
+//! Passed pawns are also scored by how close the kings are, which is done outside the cache.
+
+use super::params::{
+    BACKWARD_PAWN, CANDIDATE_PASSER, CONNECTED_PAWN, DOUBLED_PAWN, ISOLATED_PAWN,
+    PASSED_KING_DISTANCE, PASSED_PAWN, PAWN_ISLAND, Taper,
+};
+use super::{Bitboard, Board, Piece, Terms};
+use crate::Square;
+use crate::bitboard::pawn_attacks;
+
+/// The number of entries in the pawn hash table.
+const PAWN_TABLE_SIZE: usize = 1 << 14;
+
+/// The score of a pawn structure from white's side, and the passed pawns of white and black.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+struct PawnEntry {
+    key: u64,
+    score: Taper,
+    passed: [Bitboard; 2],
+}
+
+/// Caches the evaluation of pawn structures by [Board::pawn_hash].
+/// An empty entry reads as the position without pawns, whose pawn hash is 0 and whose structure is worth nothing.
+#[derive(Clone, Debug)]
+pub struct PawnTable {
+    entries: Vec<PawnEntry>,
+}
+
+impl Default for PawnTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PawnTable {
+    pub fn new() -> Self {
+        Self {
+            entries: vec![PawnEntry::default(); PAWN_TABLE_SIZE],
+        }
+    }
+
+    /// Forget every cached structure, as when the weights of the evaluation change.
+    pub fn clear(&mut self) {
+        self.entries.fill(PawnEntry::default());
+    }
+
+    fn probe(&self, key: u64) -> Option<PawnEntry> {
+        let entry = self.entries[key as usize % PAWN_TABLE_SIZE];
+        (entry.key == key).then_some(entry)
+    }
+
+    fn store(&mut self, entry: PawnEntry) {
+        self.entries[entry.key as usize % PAWN_TABLE_SIZE] = entry;
+    }
+}
+
+impl Terms<'_> {
+    /// Score the pawn structure of both sides, looking it up in the pawn table if there is one.
+    /// Returns the passed pawns of white and black.
+    pub fn pawn_structure(&mut self, board: &Board) -> [Bitboard; 2] {
+        let key = board.pawn_hash();
+        if let Some(entry) = self.pawns.as_ref().and_then(|table| table.probe(key)) {
+            self.total += entry.score;
+            return entry.passed;
+        }
+        let before = self.total;
+        let passed = [true, false].map(|white| self.pawns(board, white));
+        let score = self.total - before;
+        if let Some(table) = &mut self.pawns {
+            table.store(PawnEntry { key, score, passed });
+        }
+        passed
+    }
+
+    /// Score the pawns of one side, returning its passed pawns.
+    fn pawns(&mut self, board: &Board, white: bool) -> Bitboard {
+        let (own, enemy) = match white {
+            true => (Piece::PawnWhite, Piece::PawnBlack),
+            false => (Piece::PawnBlack, Piece::PawnWhite),
+        };
+        let own = board.pieces(own);
+        let enemy = board.pieces(enemy);
+        let enemy_attacks = enemy.fold(Bitboard::EMPTY, |attacks, square| {
+            attacks | pawn_attacks(square, !white)
+        });
+
+        let mut passed = Bitboard::EMPTY;
+        for square in own {
+            let rank = relative_rank(square, white) as usize;
+            let ahead = ranks_ahead(square, white);
+            let file = Bitboard::file(square % 8);
+            let adjacent = adjacent_files(square);
+            // Pawns on the adjacent files that are level with or behind this one, and could defend it as it advances.
+            let helpers = own & adjacent & !ahead;
+
+            let doubled = !(own & file & ahead).is_empty();
+            let isolated = (own & adjacent).is_empty();
+            let connected = !(pawn_attacks(square, !white) & own).is_empty()
+                || !(own & adjacent & Bitboard::rank(square / 8)).is_empty();
+            let blocked = !((own | enemy) & file & ahead).is_empty();
+
+            if doubled {
+                self.add(DOUBLED_PAWN, 0, white, 1);
+            }
+            if isolated {
+                self.add(ISOLATED_PAWN, 0, white, 1);
+            }
+            if connected {
+                self.add(CONNECTED_PAWN, rank, white, 1);
+            }
+            if !doubled && (enemy & (file | adjacent) & ahead).is_empty() {
+                self.add(PASSED_PAWN, rank, white, 1);
+                passed |= Bitboard::from_square(square);
+                continue;
+            }
+
+            let stop = stop_square(square, white);
+            if !isolated && helpers.is_empty() && stop.is_some_and(|s| enemy_attacks.contains(s)) {
+                self.add(BACKWARD_PAWN, 0, white, 1);
+            }
+            // The enemy pawns on the adjacent files in front, which a candidate has to get past.
+            let sentries = enemy & adjacent & ahead;
+            if !blocked && helpers.count() >= sentries.count() {
+                self.add(CANDIDATE_PASSER, rank, white, 1);
+            }
+        }
+
+        let files = (0..8)
+            .filter(|&file| !(own & Bitboard::file(file)).is_empty())
+            .fold(0u8, |files, file| files | 1 << file);
+        // An island starts at every file with pawns that has no pawns on the file to its left.
+        let islands = (files & !(files << 1)).count_ones();
+        self.add(PAWN_ISLAND, 0, white, islands as i32);
+        passed
+    }
+
+    /// Score the passed pawns of one side by how close the kings are to the square in front of them.
+    /// The own king wants to escort the pawn and the enemy king wants to stop it,
+    /// which matters more the further the pawn has advanced.
+    pub fn passed_pawns(&mut self, board: &Board, white: bool, passed: Bitboard) {
+        let (own, enemy) = match white {
+            true => (Piece::KingWhite, Piece::KingBlack),
+            false => (Piece::KingBlack, Piece::KingWhite),
+        };
+        let (Some(own), Some(enemy)) = (board.pieces(own).first(), board.pieces(enemy).first())
+        else {
+            return;
+        };
+        for square in passed {
+            // A pawn on the last rank can only be there in a position that isn't valid, and has nowhere to go.
+            let Some(stop) = stop_square(square, white) else {
+                continue;
+            };
+            let advanced = relative_rank(square, white) as i32 - 1;
+            self.add(
+                PASSED_KING_DISTANCE,
+                0,
+                white,
+                distance(own, stop) * advanced,
+            );
+            self.add(
+                PASSED_KING_DISTANCE,
+                1,
+                white,
+                distance(enemy, stop) * advanced,
+            );
+        }
+    }
+}
+
+/// The rank of a square counted from the given side, from 0 to 7.
+pub fn relative_rank(square: Square, white: bool) -> u8 {
+    match white {
+        true => square / 8,
+        false => 7 - square / 8,
+    }
+}
+
+/// The square in front of a pawn, as seen from the given side, unless it is on the last rank.
+fn stop_square(square: Square, white: bool) -> Option<Square> {
+    match white {
+        true => square.checked_add(8).filter(|&s| s < 64),
+        false => square.checked_sub(8),
+    }
+}
+
+/// Every square on the ranks in front of a square, as seen from the given side.
+pub fn ranks_ahead(square: Square, white: bool) -> Bitboard {
+    let rank = (square / 8) as u32;
+    match white {
+        true => Bitboard(u64::MAX.checked_shl(8 * (rank + 1)).unwrap_or(0)),
+        false => Bitboard((1 << (8 * rank)) - 1),
+    }
+}
+
+/// Every square on the files next to the file of a square.
+fn adjacent_files(square: Square) -> Bitboard {
+    let file = square % 8;
+    let mut files = Bitboard::EMPTY;
+    if file > 0 {
+        files |= Bitboard::file(file - 1);
+    }
+    if file < 7 {
+        files |= Bitboard::file(file + 1);
+    }
+    files
+}
+
+/// The number of king moves between two squares.
+fn distance(a: Square, b: Square) -> i32 {
+    let files = (a % 8).abs_diff(b % 8);
+    let ranks = (a / 8).abs_diff(b / 8);
+    files.max(ranks) as i32
+}
@@ -1,71 +1,259 @@
 use std::io;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
 
-use justok::{board::Board, Move};
+use justok::{
+    Move,
+    board::Board,
+    eval::{
+        Evaluator,
+        params::{Params, ParamsError},
+    },
+    game::Game,
+    perft::Perft,
+    pgn::Pgn,
+    search::{DEFAULT_HASH_MB, Limits, SearchOptions, Searcher},
+};
+
+/// Read the limits of a 'go' command, like 'wtime 60000 btime 60000 winc 1000 binc 1000'.
+/// Only the clock of the side to move is kept.
+fn parse_limits<'a>(mut parts: impl Iterator<Item = &'a str>, white_to_move: bool) -> Limits {
+    let mut limits = Limits::default();
+    while let Some(part) = parts.next() {
+        let mut number = || parts.next().and_then(|n| n.parse::<u64>().ok());
+        let millis = |n: u64| Duration::from_millis(n);
+        match (part, white_to_move) {
+            ("depth", _) => limits.depth = number().map(|n| n as usize),
+            ("nodes", _) => limits.nodes = number(),
+            ("movetime", _) => limits.movetime = number().map(millis),
+            ("movestogo", _) => limits.moves_to_go = number().map(|n| n as u32),
+            ("wtime", true) | ("btime", false) => limits.time = number().map(millis),
+            ("winc", true) | ("binc", false) => {
+                limits.increment = number().map_or(Duration::ZERO, millis)
+            }
+            ("wtime" | "btime" | "winc" | "binc", _) => {
+                number();
+            }
+            ("infinite", _) => limits.infinite = true,
+            _ => {}
+        }
+    }
+    limits
+}
 
 fn main() -> io::Result<()> {
-    let mut board = Board::blank();
-    let mut best_move: Option<Move> = None;
+    let mut game = Game::new(Board::blank());
+    let mut chess960 = false;
+    let mut options = SearchOptions::default();
+    let mut eval_params = Params::default();
+
+    // The search runs on its own thread so that 'stop' and 'isready' are answered while it thinks.
+    let searcher = Arc::new(Mutex::new(Searcher::new()));
+    let stop = searcher
+        .lock()
+        .expect("searcher not to be poisoned")
+        .stop_flag();
+    let mut search: Option<JoinHandle<()>> = None;
+    let finish_search = |search: &mut Option<JoinHandle<()>>| {
+        if let Some(handle) = search.take() {
+            stop.store(true, Ordering::Relaxed);
+            handle.join().expect("search thread not to panic");
+        }
+    };
 
     let mut input = String::new();
     let stdin = io::stdin();
     loop {
         stdin.read_line(&mut input)?;
-        let mut parts = input.trim_end().split_whitespace();
+        let mut parts = input.split_whitespace();
         match parts.next() {
-            Some("quit") => break,
+            Some("quit") => {
+                finish_search(&mut search);
+                break;
+            }
             Some("uci") => {
                 println!("id name justok 1.0.0");
                 println!("id author herlufba");
+                println!("option name Hash type spin default {DEFAULT_HASH_MB} min 1 max 65536");
+                println!("option name UCI_Chess960 type check default false");
+                // Evaluation weights written by the tuner, or the built in ones when empty.
+                println!("option name EvalFile type string default <empty>");
+                // Parts of the search can be switched off and tuned to measure what they are worth.
+                for (name, default) in SearchOptions::default().switches() {
+                    println!("option name {name} type check default {default}");
+                }
+                println!(
+                    "option name LMRBase type spin default {} min 0 max 500",
+                    options.lmr_base
+                );
+                println!(
+                    "option name LMRDivisor type spin default {} min 1 max 1000",
+                    options.lmr_divisor
+                );
                 println!("uciok");
             }
             Some("isready") => println!("readyok"),
+            Some("ucinewgame") => {
+                finish_search(&mut search);
+                searcher
+                    .lock()
+                    .expect("searcher not to be poisoned")
+                    .clear();
+            }
+            Some("setoption") => {
+                // Options are given as 'setoption name <id> [value <x>]'.
+                let name = parts.nth(1);
+                // The value runs until the end of the line, as paths may contain spaces.
+                let value = parts
+                    .skip_while(|&part| part != "value")
+                    .skip(1)
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let value = (!value.is_empty()).then_some(value.as_str());
+                match (name, value) {
+                    (Some("UCI_Chess960"), Some(value)) => chess960 = value == "true",
+                    (Some("Hash"), Some(value)) => match value.parse::<usize>() {
+                        Ok(megabytes) => searcher
+                            .lock()
+                            .expect("searcher not to be poisoned")
+                            .set_hash_mb(megabytes.max(1)),
+                        Err(_) => println!("info string Hash must be a number of megabytes"),
+                    },
+                    (Some("EvalFile"), Some(path)) => {
+                        let params = match path {
+                            "<empty>" => Ok(Params::default()),
+                            path => std::fs::read_to_string(path)
+                                .map_err(|e| e.to_string())
+                                .and_then(|text| {
+                                    text.parse().map_err(|e: ParamsError| e.to_string())
+                                }),
+                        };
+                        match params {
+                            Ok(params) => {
+                                eval_params = params.clone();
+                                searcher
+                                    .lock()
+                                    .expect("searcher not to be poisoned")
+                                    .set_eval_params(params);
+                            }
+                            Err(e) => println!("info string Could not load EvalFile: {e}"),
+                        }
+                    }
+                    (Some("LMRBase"), Some(value)) => match value.parse() {
+                        Ok(base) => options.lmr_base = base,
+                        Err(_) => println!("info string LMRBase must be a number"),
+                    },
+                    (Some("LMRDivisor"), Some(value)) => match value.parse() {
+                        Ok(divisor) => options.lmr_divisor = divisor,
+                        Err(_) => println!("info string LMRDivisor must be a number"),
+                    },
+                    (Some(name), Some(value)) => {
+                        match options.switches().into_iter().find(|(n, _)| *n == name) {
+                            Some((_, switch)) => *switch = value == "true",
+                            None => println!("info string Unknown option"),
+                        }
+                    }
+                    _ => println!("info string Unknown option"),
+                }
+                searcher
+                    .lock()
+                    .expect("searcher not to be poisoned")
+                    .set_options(options);
+            }
             Some("position") => {
                 // Read either fen string or 'startpos' which is the standard position.
-                match parts.next() {
+                let position = match parts.next() {
                     Some("fen") => {
-                        let fen = parts.clone().collect::<Vec<_>>().join(" ");
-                        board = Board::from_fen(fen.as_str());
+                        // The FEN string runs until the optional list of moves.
+                        let fen = parts
+                            .by_ref()
+                            .take_while(|&part| part != "moves")
+                            .collect::<Vec<_>>()
+                            .join(" ");
+                        Board::from_fen(fen.as_str())
+                            .map_err(|e| e.to_string())
+                            .and_then(|b| b.validate().map(|_| b).map_err(|e| e.to_string()))
                     }
                     Some("startpos") => {
-                        board = Board::from_fen(
-                            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
-                        );
+                        parts.next(); // Skip 'moves' if present.
+                        Ok(*Game::default().start())
+                    }
+                    _ => Err("expected 'fen' or 'startpos'".to_owned()),
+                };
+                match position {
+                    Ok(mut position) => {
+                        // Castling moves are written as the king capturing its own rook in Chess960.
+                        position.set_chess960(chess960);
+                        game = Game::new(position);
+                    }
+                    Err(e) => {
+                        println!("info string Could not read position: {e}");
+                        input.clear();
+                        continue;
                     }
-                    _ => println!("Could not read position."),
                 }
 
-                // Read optional list of moves and apply them to the board.
-                if let Some("moves") = parts.next() {
-                    let moves: Vec<Move> = parts
-                        .enumerate()
-                        .map(|(idx, move_str)| Move::from_str(idx % 2 == 0, move_str))
-                        .collect();
-                    for m in moves {
-                        board.apply(m);
+                // Read optional list of moves and play them.
+                for text in parts {
+                    match Move::from_uci(game.board(), text) {
+                        Ok(m) => {
+                            game.push(m);
+                        }
+                        Err(e) => {
+                            println!("info string Could not play move: {e}");
+                            break;
+                        }
                     }
                 }
             }
-            Some("go") => match parts.next() {
-                Some(_) => {
-                    // TODO: implement all the sub commands like infinite, wtime and so on
-                    best_move = board.generate_moves().first().copied();
-                    // Just print the best move as soon as it is computed,
-                    // since theres no concept of time control yet.
-                    if let Some(m) = best_move {
-                        println!("info score cp 0");
-                        println!("bestmove {m}");
-                    }
-                }
-                _ => println!("Unknown subcommand to 'go'"),
-            },
-            Some("stop") => {
-                if let Some(m) = best_move {
-                    println!("info score cp 0");
-                    println!("bestmove {m}");
+            Some("go") if input.split_whitespace().nth(1) == Some("perft") => {
+                // Print the count below each move to narrow down mismatches with other engines.
+                let depth = parts.nth(1).and_then(|d| d.parse().ok()).unwrap_or(1);
+                let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+                let divide = Perft::new()
+                    .threads(threads)
+                    .cache_mb(64)
+                    .divide(game.board(), depth);
+                for (m, nodes) in &divide {
+                    println!("{m}: {nodes}");
                 }
+                let total: u64 = divide.iter().map(|(_, nodes)| nodes).sum();
+                println!();
+                println!("Nodes searched: {total}");
+            }
+            Some("go") => {
+                finish_search(&mut search);
+                stop.store(false, Ordering::Relaxed);
+                let limits = parse_limits(parts, game.board().white_to_move);
+                let board = *game.board();
+                let history = game.keys()[..game.ply()].to_vec();
+                let searcher = Arc::clone(&searcher);
+                let stop = Arc::clone(&stop);
+                search = Some(std::thread::spawn(move || {
+                    let mut searcher = searcher.lock().expect("searcher not to be poisoned");
+                    let result = searcher.search(&board, &history, &limits, |report| {
+                        println!("info {report}");
+                    });
+                    println!("info string {}", result.cutoffs);
+                    // Let options be set while waiting, so the main thread keeps answering commands.
+                    drop(searcher);
+                    // An infinite search may only answer once it is told to stop, even if it has found a mate.
+                    while limits.infinite && !stop.load(Ordering::Relaxed) {
+                        std::thread::sleep(Duration::from_millis(1));
+                    }
+                    match result.best_move() {
+                        Some(m) => println!("bestmove {m}"),
+                        // The game is over, which UCI signals with a null move.
+                        None => println!("bestmove 0000"),
+                    }
+                }));
             }
+            Some("stop") => finish_search(&mut search),
             // NON-UCI DEBUGGING COMMANDS
             Some("board") => {
+                let board = game.board();
                 println!("{}", board);
                 println!("fen: {}", board.to_fen());
                 println!(
@@ -73,11 +261,19 @@ fn main() -> io::Result<()> {
                     board
                         .generate_moves()
                         .iter()
-                        .map(|m| format!("{}", m))
+                        .map(|m| format!("{} ({m})", m.to_san(board)))
                         .collect::<Vec<String>>()
                         .join(", ")
                 );
+                if let Some(outcome) = game.outcome() {
+                    println!("outcome: {outcome}");
+                }
             }
+            Some("pgn") => print!("{}", Pgn::from_game(&game)),
+            Some("eval") => println!(
+                "{}",
+                Evaluator::with_params(eval_params.clone()).trace(game.board())
+            ),
             Some(c) => println!("Unknown command '{c}'"),
             None => {}
         }
@@ -0,0 +1,135 @@
+use super::Move;
+use super::board::{Board, FenError, MoveInfo};
+use super::outcome::GameOutcome;
+
+/// The standard starting position.
+pub const STANDARD_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// A move that has been played in a [Game].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PlayedMove {
+    pub r#move: Move,
+    /// Which pieces the move moved, removed and added, and the state of the board before it.
+    pub info: MoveInfo,
+}
+
+/// A game of chess, from its starting position through the moves played since.
+/// Moves that are taken back with [Game::undo] are kept until a different move is played,
+/// so they can be played again with [Game::redo].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Game {
+    start: Board,
+    board: Board,
+    /// The played moves, followed by any moves that were undone and can be redone.
+    moves: Vec<PlayedMove>,
+    /// The number of moves from `moves` that are currently played.
+    ply: usize,
+    /// The [Board::hash] of the start position and of the position after each played move.
+    keys: Vec<u64>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(Board::from_fen(STANDARD_FEN).expect("standard position to be valid"))
+    }
+}
+
+impl Game {
+    /// Start a new game from a position.
+    pub fn new(start: Board) -> Self {
+        Self {
+            start,
+            board: start,
+            moves: Vec::new(),
+            ply: 0,
+            keys: vec![start.hash()],
+        }
+    }
+
+    /// Start a new game from a position given as a FEN string.
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        Board::from_fen(fen).map(Self::new)
+    }
+
+    /// The position the game started from.
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    /// The current position.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// The number of moves played.
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// The [Board::hash] of every position in the game so far, starting with the start position.
+    pub fn keys(&self) -> &[u64] {
+        &self.keys
+    }
+
+    /// Play a move in the current position. The move is assummed to be legal.
+    /// Any moves that were undone can no longer be redone.
+    pub fn push(&mut self, r#move: Move) -> &MoveInfo {
+        self.moves.truncate(self.ply);
+        let undo = self.board.make(r#move);
+        let info = self.board.move_info(r#move, &undo);
+        self.moves.push(PlayedMove { r#move, info });
+        self.ply += 1;
+        self.keys.push(self.board.hash());
+        &self.moves[self.ply - 1].info
+    }
+
+    /// Take back the last played move, if any.
+    pub fn undo(&mut self) -> Option<&PlayedMove> {
+        self.ply = self.ply.checked_sub(1)?;
+        let played = &self.moves[self.ply];
+        self.board.unmake(played.r#move, played.info.undo);
+        self.keys.pop();
+        Some(played)
+    }
+
+    /// Play the last move that was taken back again, if any.
+    pub fn redo(&mut self) -> Option<&PlayedMove> {
+        let played = self.moves.get(self.ply)?;
+        self.board.make(played.r#move);
+        self.ply += 1;
+        self.keys.push(self.board.hash());
+        Some(played)
+    }
+
+    /// The position after the given number of played moves, where 0 is the start position.
+    pub fn position_at(&self, ply: usize) -> Option<Board> {
+        if ply > self.ply {
+            return None;
+        }
+        let mut board = self.start;
+        for played in &self.moves[..ply] {
+            board.make(played.r#move);
+        }
+        Some(board)
+    }
+
+    /// The moves played so far, in order.
+    pub fn moves(&self) -> &[PlayedMove] {
+        &self.moves[..self.ply]
+    }
+
+    /// Iterate over the played moves, each along with the position it was played in.
+    pub fn iter(&self) -> impl Iterator<Item = (Board, &PlayedMove)> {
+        self.moves().iter().scan(self.start, |board, played| {
+            let before = *board;
+            board.make(played.r#move);
+            Some((before, played))
+        })
+    }
+
+    /// Has the game ended in the current position?
+    /// See [Board::outcome].
+    pub fn outcome(&self) -> Option<GameOutcome> {
+        self.board.outcome(&self.keys[..self.ply])
+    }
+}
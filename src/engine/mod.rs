@@ -1,6 +1,23 @@
+pub mod bitboard;
 pub mod board;
+pub mod eval;
+pub mod game;
 pub mod r#move;
+pub mod movelist;
+pub mod movepick;
+pub mod ordering;
+pub mod outcome;
+pub mod perft;
+pub mod pgn;
 pub mod piece;
+pub mod san;
+pub mod search;
+mod see;
+pub mod texel;
+pub mod transposition;
+mod zobrist;
+
+pub use r#move::Move;
 
 /// Letters of the eight files on a chess board.
 pub const FILES: [char; 8] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
@@ -12,6 +29,7 @@ pub type Square = u8;
 
 /// Converts a i8 that may point to a square in a board to a valid Square if possible.
 /// Hint: If you have an iterator over maybe_squares, use filter_map(to_board_square) on it.
+#[allow(clippy::manual_range_contains)]
 pub fn to_board_square(maybe_square: i8) -> Option<Square> {
     (maybe_square >= 0 && maybe_square < 64)
         .then(|| u8::try_from(maybe_square).ok())
@@ -24,19 +42,18 @@ pub fn square_to_algebraic(square: Square) -> String {
     format!("{file}{rank}").to_owned()
 }
 
-pub fn square_from_algebraic(long_algebraic: &str) -> Square {
-    let mut chars = long_algebraic.chars();
-    let file = match chars.next() {
-        Some('a') => 0,
-        Some('b') => 1,
-        Some('c') => 2,
-        Some('d') => 3,
-        Some('e') => 4,
-        Some('f') => 5,
-        Some('g') => 6,
-        Some('h') => 7,
-        _ => panic!("Invalid square {}", long_algebraic),
-    };
-    let rank = chars.next().and_then(|c| c.to_digit(10)).unwrap() - 1;
-    u8::try_from(rank * 8 + file).unwrap()
+/// Reads a square like "e4" from algebraic notation.
+/// Returns `None` if the text doesn't name a square on the board.
+pub fn square_from_algebraic(algebraic: &str) -> Option<Square> {
+    let mut chars = algebraic.chars();
+    let file_char = chars.next()?;
+    let file = FILES.iter().position(|&f| f == file_char)?;
+    let rank = chars
+        .next()
+        .and_then(|c| c.to_digit(10))
+        .filter(|r| (1..=8).contains(r))?;
+    if chars.next().is_some() {
+        return None;
+    }
+    u8::try_from((rank - 1) as usize * 8 + file).ok()
 }
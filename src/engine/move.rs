@@ -1,47 +1,211 @@
+use std::error::Error;
 use std::fmt::Display;
 
-use super::{Square, piece::Piece, square_from_algebraic, square_to_algebraic};
+use super::{Square, board::Board, piece::Piece, square_from_algebraic, square_to_algebraic};
 
-/// A move between two squares.
+/// What a move does besides moving a piece, which is needed to play it without looking at the board.
+/// The discriminants are the flags stored in the top four bits of a [Move].
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
-pub struct Move {
-    pub from: Square,
-    pub to: Square,
-    pub promote_to: Option<Piece>,
+pub enum MoveKind {
+    Quiet = 0,
+    /// A pawn moving two squares forward from its starting rank.
+    DoublePush = 1,
+    /// Castling towards the h-file.
+    KingCastle = 2,
+    /// Castling towards the a-file.
+    QueenCastle = 3,
+    Capture = 4,
+    EnPessant = 5,
+    KnightPromotion = 8,
+    BishopPromotion = 9,
+    RookPromotion = 10,
+    QueenPromotion = 11,
+    KnightPromotionCapture = 12,
+    BishopPromotionCapture = 13,
+    RookPromotionCapture = 14,
+    QueenPromotionCapture = 15,
 }
 
-#[allow(dead_code)]
+impl MoveKind {
+    /// The kinds of promotion, in the order of the promotion flags.
+    const PROMOTIONS: [MoveKind; 8] = {
+        use MoveKind::*;
+        [
+            KnightPromotion,
+            BishopPromotion,
+            RookPromotion,
+            QueenPromotion,
+            KnightPromotionCapture,
+            BishopPromotionCapture,
+            RookPromotionCapture,
+            QueenPromotionCapture,
+        ]
+    };
+
+    fn from_flags(flags: u16) -> Self {
+        use MoveKind::*;
+        match flags {
+            0 => Quiet,
+            1 => DoublePush,
+            2 => KingCastle,
+            3 => QueenCastle,
+            4 => Capture,
+            5 => EnPessant,
+            8..=15 => Self::PROMOTIONS[flags as usize - 8],
+            _ => unreachable!("move flags 6 and 7 are never used"),
+        }
+    }
+
+    /// The promotion of a pawn to a piece, which is white when promoting on the eighth rank.
+    /// Returns `None` if the piece isn't one a pawn can promote to.
+    pub fn promotion(piece: Piece, capture: bool) -> Option<Self> {
+        use Piece::*;
+        let offset = match piece {
+            KnightWhite | KnightBlack => 0,
+            BishopWhite | BishopBlack => 1,
+            RookWhite | RookBlack => 2,
+            QueenWhite | QueenBlack => 3,
+            PawnWhite | PawnBlack | KingWhite | KingBlack => return None,
+        };
+        Some(Self::PROMOTIONS[offset + 4 * capture as usize])
+    }
+}
+
+/// A move between two squares, along with its [MoveKind], packed into 16 bits.
+/// The lowest six bits hold the square moved from, the next six the square moved to, and the top four the kind.
+///
+/// Castling is stored as the king moving two squares towards the rook,
+/// or as the king capturing its own rook in Chess960, which is also how it is written in UCI.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Default)]
+pub struct Move(u16);
+
+/// Reasons a move could not be read by [Move::from_uci].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum MoveError {
+    /// The text is not a move in the long algebraic notation UCI uses, like 'e2e4' or 'e7e8q'.
+    Invalid(String),
+    /// The move is not legal in the position.
+    Illegal(String),
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::Invalid(text) => write!(f, "'{text}' is not a move in UCI notation"),
+            MoveError::Illegal(text) => write!(f, "'{text}' is not a legal move"),
+        }
+    }
+}
+
+impl Error for MoveError {}
+
 impl Move {
-    pub fn new(from: Square, to: Square) -> Self {
-        Self {
-            from,
-            to,
-            promote_to: None,
+    /// A placeholder for no move, for tables that store moves without wrapping them in an [Option].
+    /// It moves from a1 to a1, which is never a legal move.
+    pub const NULL: Move = Move(0);
+
+    pub fn new(from: Square, to: Square, kind: MoveKind) -> Self {
+        Self(from as u16 & 0x3f | (to as u16 & 0x3f) << 6 | (kind as u16) << 12)
+    }
+
+    /// Read a move from the 16 bits it is stored in by [Move::bits].
+    pub fn from_bits(bits: u16) -> Self {
+        // Flags 6 and 7 are unused, so read them as quiet moves rather than producing a move no one can play.
+        match bits >> 12 {
+            6 | 7 => Self(bits & 0x0fff),
+            _ => Self(bits),
         }
     }
 
-    /// Read a move from long algebraic notation.
-    /// Will panic if the move is not valid (has rank or file outside normal chess board).
-    /// TODO: Read promotions
-    /// TODO: is_white parameter
-    pub fn from_str(is_white: bool, long_algebraic: &str) -> Move {
-        assert!(long_algebraic.len() >= 4);
-        let from = square_from_algebraic(&long_algebraic[0..2]);
-        let to = square_from_algebraic(&long_algebraic[2..4]);
-        let promote_to = long_algebraic.chars().nth(4).and_then(|c| {
-            let cm = if is_white {
-                c.to_ascii_uppercase()
-            } else {
-                c.to_ascii_lowercase()
-            };
-            Piece::from_char(&cm)
-        });
-
-        Move {
-            from,
-            to,
-            promote_to,
+    /// The 16 bits the move is stored in.
+    pub fn bits(&self) -> u16 {
+        self.0
+    }
+
+    pub fn from(&self) -> Square {
+        (self.0 & 0x3f) as Square
+    }
+
+    pub fn to(&self) -> Square {
+        (self.0 >> 6 & 0x3f) as Square
+    }
+
+    pub fn kind(&self) -> MoveKind {
+        MoveKind::from_flags(self.0 >> 12)
+    }
+
+    /// Does the move capture a piece? This includes en pessant.
+    pub fn is_capture(&self) -> bool {
+        self.0 >> 12 & 0b0100 != 0
+    }
+
+    pub fn is_en_pessant(&self) -> bool {
+        self.kind() == MoveKind::EnPessant
+    }
+
+    pub fn is_castling(&self) -> bool {
+        matches!(self.kind(), MoveKind::KingCastle | MoveKind::QueenCastle)
+    }
+
+    pub fn is_promotion(&self) -> bool {
+        self.0 >> 12 & 0b1000 != 0
+    }
+
+    /// Is the move a capture or a promotion? These are the moves generated by [Board::generate_captures].
+    pub fn is_tactical(&self) -> bool {
+        self.is_capture() || self.is_promotion()
+    }
+
+    /// The piece a pawn promotes to. It is white when promoting on the eighth rank and black on the first.
+    pub fn promote_to(&self) -> Option<Piece> {
+        use Piece::*;
+        if !self.is_promotion() {
+            return None;
         }
+        let white = self.to() / 8 == 7;
+        let pieces = match white {
+            true => [KnightWhite, BishopWhite, RookWhite, QueenWhite],
+            false => [KnightBlack, BishopBlack, RookBlack, QueenBlack],
+        };
+        Some(pieces[(self.0 >> 12 & 0b11) as usize])
+    }
+
+    /// Read a move from the long algebraic notation used by UCI, like 'e2e4' or 'e7e8q',
+    /// working out its kind from the position it is played in.
+    /// Castling is read as in [Board::castling], and the move has to be legal.
+    pub fn from_uci(board: &Board, long_algebraic: &str) -> Result<Move, MoveError> {
+        let invalid = || MoveError::Invalid(long_algebraic.to_owned());
+        let (from, to, promotion) = match long_algebraic.len() {
+            4 | 5 if long_algebraic.is_ascii() => (
+                &long_algebraic[0..2],
+                &long_algebraic[2..4],
+                &long_algebraic[4..],
+            ),
+            _ => return Err(invalid()),
+        };
+        let from = square_from_algebraic(from).ok_or_else(invalid)?;
+        let to = square_from_algebraic(to).ok_or_else(invalid)?;
+        let promote_to = match promotion.chars().next() {
+            Some(c) => Some(Piece::from_char(&c).ok_or_else(invalid)?),
+            None => None,
+        };
+
+        board
+            .generate_square_moves(from)
+            .iter()
+            .copied()
+            .find(|m| {
+                m.to() == to
+                    && m.promote_to().map(|p| p.to_char().to_ascii_lowercase())
+                        == promote_to.map(|p| p.to_char().to_ascii_lowercase())
+            })
+            .ok_or_else(|| MoveError::Illegal(long_algebraic.to_owned()))
+    }
+}
+
+impl std::fmt::Debug for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self} ({:?})", self.kind())
     }
 }
 
@@ -50,9 +214,9 @@ impl Display for Move {
         write!(
             f,
             "{}{}{}",
-            square_to_algebraic(self.from),
-            square_to_algebraic(self.to),
-            self.promote_to.map_or("".to_owned(), |p| p
+            square_to_algebraic(self.from()),
+            square_to_algebraic(self.to()),
+            self.promote_to().map_or("".to_owned(), |p| p
                 .to_char()
                 // UCI move format always has piece in lower case.
                 .to_ascii_lowercase()
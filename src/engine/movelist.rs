@@ -0,0 +1,177 @@
+//! A list of moves stored inline, for generating moves without allocating.
+
+use super::Move;
+use std::ops::{Deref, DerefMut};
+
+/// The most legal moves any chess position has is 218, so a list of this many moves never overflows.
+pub const MAX_MOVES: usize = 256;
+
+/// A fixed capacity list of moves that lives on the stack.
+/// Dereferences to a slice of its moves, so it can be indexed, sorted and iterated like a [Vec].
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    pub fn new() -> Self {
+        Self {
+            moves: [Move::NULL; MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    /// Add a move to the end of the list.
+    /// Panics if the list is full, which can't happen for the moves of a single position.
+    pub fn push(&mut self, r#move: Move) {
+        self.moves[self.len] = r#move;
+        self.len += 1;
+    }
+
+    /// Remove and return the last move of the list.
+    pub fn pop(&mut self) -> Option<Move> {
+        self.len = self.len.checked_sub(1)?;
+        Some(self.moves[self.len])
+    }
+
+    /// Remove the move at an index, replacing it with the last move of the list.
+    pub fn swap_remove(&mut self, index: usize) -> Move {
+        let r#move = self.moves[index];
+        self.len -= 1;
+        self.moves[index] = self.moves[self.len];
+        r#move
+    }
+
+    /// Keep only the moves for which `keep` is true, in their original order.
+    pub fn retain(&mut self, mut keep: impl FnMut(&Move) -> bool) {
+        let mut kept = 0;
+        for i in 0..self.len {
+            if keep(&self.moves[i]) {
+                self.moves[kept] = self.moves[i];
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl std::fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for MoveList {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for MoveList {}
+
+impl PartialEq<Vec<Move>> for MoveList {
+    fn eq(&self, other: &Vec<Move>) -> bool {
+        **self == **other
+    }
+}
+
+impl PartialEq<MoveList> for Vec<Move> {
+    fn eq(&self, other: &MoveList) -> bool {
+        **self == **other
+    }
+}
+
+impl Extend<Move> for MoveList {
+    fn extend<T: IntoIterator<Item = Move>>(&mut self, moves: T) {
+        for r#move in moves {
+            self.push(r#move);
+        }
+    }
+}
+
+impl FromIterator<Move> for MoveList {
+    fn from_iter<T: IntoIterator<Item = Move>>(moves: T) -> Self {
+        let mut list = Self::new();
+        list.extend(moves);
+        list
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for MoveList {
+    type Item = Move;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter {
+            list: self,
+            next: 0,
+        }
+    }
+}
+
+/// Iterator over the moves of a [MoveList], taking them out of the list.
+#[derive(Clone, Debug)]
+pub struct IntoIter {
+    list: MoveList,
+    next: usize,
+}
+
+impl Iterator for IntoIter {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        let r#move = self.list.get(self.next).copied()?;
+        self.next += 1;
+        Some(r#move)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.list.len() - self.next;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Move> {
+        if self.next == self.list.len() {
+            return None;
+        }
+        self.list.pop()
+    }
+}
@@ -0,0 +1,230 @@
+//! Hands out the legal moves of a position one at a time, most promising first,
+//! generating each kind of move only once the moves before it have been tried.
+//! A search that cuts off after the first few moves never pays for generating the rest.
+
+use super::board::Board;
+use super::r#move::{Move, MoveKind};
+use super::movelist::{MAX_MOVES, MoveList};
+use super::ordering::MoveOrdering;
+
+/// The order moves are handed out in by a [MovePicker].
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+enum Stage {
+    HashMove,
+    GenerateCaptures,
+    GoodCaptures,
+    Killers,
+    Countermove,
+    GenerateQuiets,
+    Quiets,
+    BadCaptures,
+    Done,
+}
+
+/// Yields every legal move of a position exactly once, in stages:
+/// 1. The hash move, the best move found for the position earlier.
+/// 2. Captures and promotions that don't lose material by [Board::see], most valuable victim first.
+/// 3. Killer moves, quiet moves that caused a cutoff at the same depth in a sibling position.
+/// 4. The countermove, the quiet move that last refuted the move played before it.
+/// 5. The remaining quiet moves, by their history score when picked with [MovePicker::pick].
+/// 6. Captures that lose material.
+///
+/// The hash move, killers and countermove come from other positions and are skipped if they aren't legal here.
+/// For quiescence search, [MovePicker::captures] only yields the captures of the second stage.
+#[derive(Clone, Debug)]
+pub struct MovePicker<'a> {
+    board: &'a Board,
+    hash_move: Option<Move>,
+    killers: [Option<Move>; 2],
+    countermove: Option<Move>,
+    stage: Stage,
+    /// Moves of the current stage that are yet to be handed out.
+    moves: MoveList,
+    /// The score of each move in `moves`, at the same index.
+    scores: [i32; MAX_MOVES],
+    bad_captures: MoveList,
+    /// Stop after the captures that don't lose material.
+    captures_only: bool,
+}
+
+impl<'a> MovePicker<'a> {
+    pub fn new(board: &'a Board, hash_move: Option<Move>, killers: [Option<Move>; 2]) -> Self {
+        Self {
+            board,
+            hash_move,
+            killers,
+            countermove: None,
+            stage: Stage::HashMove,
+            moves: MoveList::new(),
+            scores: [0; MAX_MOVES],
+            bad_captures: MoveList::new(),
+            captures_only: false,
+        }
+    }
+
+    /// Yields only the captures and promotions that don't lose material, most valuable victim first.
+    /// Losing captures are left out, as they are rarely worth searching in a quiescence search.
+    pub fn captures(board: &'a Board) -> Self {
+        Self {
+            stage: Stage::GenerateCaptures,
+            captures_only: true,
+            ..Self::new(board, None, [None; 2])
+        }
+    }
+
+    /// Try the move after the killers, if it is a legal quiet move.
+    pub fn with_countermove(mut self, countermove: Option<Move>) -> Self {
+        self.countermove = countermove;
+        self
+    }
+
+    /// Has the move already been handed out in an earlier stage?
+    /// Handed out killers and countermoves are kept in `moves` until the quiet moves are generated.
+    fn was_picked(&self, r#move: Move) -> bool {
+        self.hash_move == Some(r#move) || self.moves.contains(&r#move)
+    }
+
+    /// Can a killer or countermove be played here without handing out a move twice?
+    fn is_playable_quiet(&self, r#move: Move) -> bool {
+        !r#move.is_tactical() && !self.was_picked(r#move) && self.board.is_legal(r#move)
+    }
+
+    /// The value of the piece a move captures, if any.
+    fn victim_value(&self, r#move: Move) -> i32 {
+        match r#move.kind() {
+            // En pessant captures a pawn from an empty square.
+            MoveKind::EnPessant => 100,
+            _ if r#move.is_capture() => self.board.at(r#move.to()).map_or(0, |p| p.value()),
+            _ => 0,
+        }
+    }
+
+    /// Does a capture or promotion at least break even? Losing captures wait until after the quiet moves.
+    fn is_good_capture(&self, r#move: Move) -> bool {
+        let attacker = self
+            .board
+            .at(r#move.from())
+            .expect("move to target a piece");
+        // Taking a piece worth at least as much as the attacker can't lose material, so skip the exchange evaluation.
+        self.victim_value(r#move) >= attacker.value() || self.board.see(r#move) >= 0
+    }
+
+    /// Score a capture or promotion by the most valuable victim, least valuable attacker rule.
+    /// A promotion counts as capturing the difference between the new piece and the pawn.
+    fn score_capture(&self, r#move: Move) -> i32 {
+        let attacker = self
+            .board
+            .at(r#move.from())
+            .expect("move to target a piece");
+        let victim = self.victim_value(r#move);
+        let promotion = r#move.promote_to().map_or(0, |p| p.value() - 100);
+        (victim + promotion) * 16 - attacker.value() / 100
+    }
+
+    /// Remove and return the highest scoring move of the current stage.
+    fn take_best(&mut self) -> Option<Move> {
+        let best = (0..self.moves.len()).max_by_key(|&i| self.scores[i])?;
+        self.scores[best] = self.scores[self.moves.len() - 1];
+        Some(self.moves.swap_remove(best))
+    }
+}
+
+impl MovePicker<'_> {
+    /// The next move, with quiet moves ordered by their history score in `ordering`.
+    pub fn pick(&mut self, ordering: &MoveOrdering) -> Option<Move> {
+        self.advance(Some(ordering))
+    }
+
+    fn advance(&mut self, ordering: Option<&MoveOrdering>) -> Option<Move> {
+        loop {
+            match self.stage {
+                Stage::HashMove => {
+                    self.stage = Stage::GenerateCaptures;
+                    match self.hash_move {
+                        Some(m) if self.board.is_legal(m) => return Some(m),
+                        _ => self.hash_move = None,
+                    }
+                }
+                Stage::GenerateCaptures => {
+                    self.moves = self.board.generate_captures();
+                    self.moves.retain(|&m| self.hash_move != Some(m));
+                    for i in 0..self.moves.len() {
+                        self.scores[i] = self.score_capture(self.moves[i]);
+                    }
+                    self.stage = Stage::GoodCaptures;
+                }
+                Stage::GoodCaptures => {
+                    let Some(m) = self.take_best() else {
+                        self.stage = match self.captures_only {
+                            true => Stage::Done,
+                            false => Stage::Killers,
+                        };
+                        continue;
+                    };
+                    if !self.is_good_capture(m) {
+                        self.bad_captures.push(m);
+                        continue;
+                    }
+                    return Some(m);
+                }
+                Stage::Killers => {
+                    let killer = self
+                        .killers
+                        .iter()
+                        .flatten()
+                        .copied()
+                        .find(|&m| self.is_playable_quiet(m));
+                    match killer {
+                        // Remember handed out killers in the otherwise empty list of moves.
+                        Some(m) => {
+                            self.moves.push(m);
+                            return Some(m);
+                        }
+                        None => self.stage = Stage::Countermove,
+                    }
+                }
+                Stage::Countermove => {
+                    self.stage = Stage::GenerateQuiets;
+                    if let Some(m) = self.countermove.filter(|&m| self.is_playable_quiet(m)) {
+                        self.moves.push(m);
+                        return Some(m);
+                    }
+                }
+                Stage::GenerateQuiets => {
+                    let mut quiets = self.board.generate_quiets();
+                    quiets.retain(|&m| !self.was_picked(m));
+                    for (i, &m) in quiets.iter().enumerate() {
+                        self.scores[i] = match ordering {
+                            Some(ordering) => ordering.history(self.board, m),
+                            // Keep the generated order.
+                            None => -(i as i32),
+                        };
+                    }
+                    self.moves = quiets;
+                    self.stage = Stage::Quiets;
+                }
+                Stage::Quiets => match self.take_best() {
+                    Some(m) => return Some(m),
+                    None => {
+                        self.bad_captures.reverse();
+                        self.stage = Stage::BadCaptures;
+                    }
+                },
+                Stage::BadCaptures => match self.bad_captures.pop() {
+                    Some(m) => return Some(m),
+                    None => self.stage = Stage::Done,
+                },
+                Stage::Done => return None,
+            }
+        }
+    }
+}
+
+/// Hands out the moves without history scores, so quiet moves come in the order they are generated.
+impl Iterator for MovePicker<'_> {
+    type Item = Move;
+
+    fn next(&mut self) -> Option<Move> {
+        self.advance(None)
+    }
+}
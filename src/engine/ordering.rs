@@ -0,0 +1,135 @@
+//! Tables the search fills in as it goes, remembering which quiet moves caused cutoffs
+//! so that the [MovePicker](super::movepick::MovePicker) tries similar moves early elsewhere in the tree.
+
+use super::Move;
+use super::board::Board;
+use super::search::MAX_PLY;
+use std::fmt::Display;
+
+/// History scores stay between plus and minus this.
+pub const MAX_HISTORY: i32 = 16_384;
+
+/// The largest change to a history score from a single cutoff.
+const MAX_BONUS: i32 = 1_200;
+
+/// Quiet move ordering heuristics:
+/// - Killer moves: the last two quiet moves that caused a cutoff at each ply.
+/// - Countermoves: the quiet move that last refuted each move, by the piece that moved and where it went.
+/// - Butterfly history: a score for each side moving from one square to another,
+///   raised when the move causes a cutoff and lowered when it is tried without one.
+#[derive(Clone, Debug)]
+pub struct MoveOrdering {
+    killers: [[Option<Move>; 2]; MAX_PLY],
+    countermoves: Box<[[Option<Move>; 64]; 14]>,
+    butterfly: Box<[[[i32; 64]; 64]; 2]>,
+}
+
+impl Default for MoveOrdering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MoveOrdering {
+    pub fn new() -> Self {
+        Self {
+            killers: [[None; 2]; MAX_PLY],
+            countermoves: Box::new([[None; 64]; 14]),
+            butterfly: Box::new([[[0; 64]; 64]; 2]),
+        }
+    }
+
+    /// Forget everything, as when starting a new game.
+    pub fn clear(&mut self) {
+        *self = Self::new();
+    }
+
+    /// Prepare for a new search. Killers are specific to the position searched and are forgotten,
+    /// while history scores are halved so that what was learned in this search weighs more.
+    pub fn new_search(&mut self) {
+        self.killers = [[None; 2]; MAX_PLY];
+        for score in self.butterfly.iter_mut().flatten().flatten() {
+            *score /= 2;
+        }
+    }
+
+    pub fn killers(&self, ply: usize) -> [Option<Move>; 2] {
+        self.killers[ply]
+    }
+
+    /// The move that last refuted `previous`, the move that led to the position on the board.
+    pub fn countermove(&self, board: &Board, previous: Option<Move>) -> Option<Move> {
+        let previous = previous?;
+        let piece = board.at(previous.to())?;
+        self.countermoves[piece as usize][previous.to() as usize]
+    }
+
+    /// The butterfly history score of a quiet move by the side to move.
+    pub fn history(&self, board: &Board, r#move: Move) -> i32 {
+        self.butterfly[board.white_to_move as usize][r#move.from() as usize][r#move.to() as usize]
+    }
+
+    /// Learn from a quiet move causing a cutoff `ply` plies from the root with `depth` plies left,
+    /// after the quiet moves in `tried` failed to.
+    pub fn update(
+        &mut self,
+        board: &Board,
+        ply: usize,
+        previous: Option<Move>,
+        cutoff: Move,
+        tried: &[Move],
+        depth: usize,
+    ) {
+        if self.killers[ply][0] != Some(cutoff) {
+            self.killers[ply] = [Some(cutoff), self.killers[ply][0]];
+        }
+        if let Some(previous) = previous
+            && let Some(piece) = board.at(previous.to())
+        {
+            self.countermoves[piece as usize][previous.to() as usize] = Some(cutoff);
+        }
+
+        let bonus = (depth * depth).min(MAX_BONUS as usize) as i32;
+        let side = &mut self.butterfly[board.white_to_move as usize];
+        apply_gravity(
+            &mut side[cutoff.from() as usize][cutoff.to() as usize],
+            bonus,
+        );
+        for m in tried {
+            apply_gravity(&mut side[m.from() as usize][m.to() as usize], -bonus);
+        }
+    }
+}
+
+/// Move a history score by `bonus`, by less the closer it already is to [MAX_HISTORY] in that direction,
+/// so that scores never leave the range and moves that keep causing cutoffs don't drown out everything else.
+fn apply_gravity(score: &mut i32, bonus: i32) {
+    *score += bonus - *score * bonus.abs() / MAX_HISTORY;
+}
+
+/// How often the search cut off, and how often the first move tried was the one that did.
+/// Good move ordering makes the first move cause most cutoffs.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub struct CutoffStats {
+    pub cutoffs: u64,
+    pub first_move_cutoffs: u64,
+}
+
+impl CutoffStats {
+    /// The share of cutoffs caused by the first move, from 0 to 1.
+    pub fn first_move_rate(&self) -> f64 {
+        self.first_move_cutoffs as f64 / self.cutoffs.max(1) as f64
+    }
+}
+
+impl Display for CutoffStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "cutoffs {} first move cutoffs {} ({:.1}%)",
+            self.cutoffs,
+            self.first_move_cutoffs,
+            self.first_move_rate() * 100.0
+        )
+    }
+}
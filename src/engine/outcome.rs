@@ -0,0 +1,125 @@
+use super::bitboard::Bitboard;
+use super::board::Board;
+use super::piece::Piece;
+use std::fmt::Display;
+
+/// The ways a game of chess can end.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GameOutcome {
+    /// The side to move is in check and has no legal moves, so the other side wins.
+    Checkmate { white_wins: bool },
+    /// The side to move is not in check, but has no legal moves. The game is drawn.
+    Stalemate,
+    /// Neither side has the material to ever checkmate. The game is drawn.
+    InsufficientMaterial,
+    /// The same position has occured five times. The game is drawn automatically.
+    FivefoldRepetition,
+    /// 75 moves have been played by each side without a capture or a pawn move.
+    /// The game is drawn automatically.
+    SeventyFiveMoveRule,
+    /// The same position has occured three times. Either player may claim a draw.
+    ThreefoldRepetition,
+    /// 50 moves have been played by each side without a capture or a pawn move.
+    /// Either player may claim a draw.
+    FiftyMoveRule,
+}
+
+impl GameOutcome {
+    /// Is the game over without either player having to claim a draw?
+    pub fn is_automatic(&self) -> bool {
+        !matches!(
+            self,
+            GameOutcome::ThreefoldRepetition | GameOutcome::FiftyMoveRule
+        )
+    }
+
+    /// Did white win the game? `None` for draws.
+    pub fn winner(&self) -> Option<bool> {
+        match self {
+            GameOutcome::Checkmate { white_wins } => Some(*white_wins),
+            _ => None,
+        }
+    }
+}
+
+impl Display for GameOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameOutcome::Checkmate { white_wins: true } => write!(f, "white wins by checkmate"),
+            GameOutcome::Checkmate { white_wins: false } => write!(f, "black wins by checkmate"),
+            GameOutcome::Stalemate => write!(f, "draw by stalemate"),
+            GameOutcome::InsufficientMaterial => write!(f, "draw by insufficient material"),
+            GameOutcome::FivefoldRepetition => write!(f, "draw by fivefold repetition"),
+            GameOutcome::SeventyFiveMoveRule => write!(f, "draw by the 75 move rule"),
+            GameOutcome::ThreefoldRepetition => write!(f, "draw by threefold repetition"),
+            GameOutcome::FiftyMoveRule => write!(f, "draw by the 50 move rule"),
+        }
+    }
+}
+
+impl Board {
+    /// Has the game ended in this position?
+    /// `history` holds the [Board::hash] of every earlier position in the game, in the order they were played,
+    /// and is used to detect repetitions.
+    /// Automatic endings are preferred over draws that have to be claimed,
+    /// and a checkmate delivered on the last move stands even if a draw could otherwise be claimed.
+    pub fn outcome(&self, history: &[u64]) -> Option<GameOutcome> {
+        if self.generate_moves().is_empty() {
+            return match self.is_in_check() {
+                true => Some(GameOutcome::Checkmate {
+                    white_wins: !self.white_to_move,
+                }),
+                false => Some(GameOutcome::Stalemate),
+            };
+        }
+
+        if self.is_insufficient_material() {
+            return Some(GameOutcome::InsufficientMaterial);
+        }
+
+        // Positions before the last capture or pawn move can't be repeated.
+        let repetitions = 1 + history
+            .iter()
+            .rev()
+            .take(self.half_move_clock() as usize)
+            .filter(|&&hash| hash == self.hash())
+            .count();
+        if repetitions >= 5 {
+            return Some(GameOutcome::FivefoldRepetition);
+        }
+        if self.half_move_clock() >= 150 {
+            return Some(GameOutcome::SeventyFiveMoveRule);
+        }
+        if repetitions >= 3 {
+            return Some(GameOutcome::ThreefoldRepetition);
+        }
+        if self.half_move_clock() >= 100 {
+            return Some(GameOutcome::FiftyMoveRule);
+        }
+
+        None
+    }
+
+    /// Is there too little material left for either side to checkmate?
+    /// That is the case with only kings and a single minor piece,
+    /// or with only kings and bishops that all stand on squares of the same color.
+    pub fn is_insufficient_material(&self) -> bool {
+        use Piece::*;
+
+        let heavy_or_pawns = [
+            PawnWhite, PawnBlack, RookWhite, RookBlack, QueenWhite, QueenBlack,
+        ];
+        if heavy_or_pawns.iter().any(|&p| !self.pieces(p).is_empty()) {
+            return false;
+        }
+
+        let knights = self.pieces(KnightWhite) | self.pieces(KnightBlack);
+        let bishops = self.pieces(BishopWhite) | self.pieces(BishopBlack);
+        if (knights | bishops).count() <= 1 {
+            return true;
+        }
+
+        let light_bishops = bishops & Bitboard::LIGHT_SQUARES;
+        knights.is_empty() && (light_bishops.is_empty() || light_bishops == bishops)
+    }
+}
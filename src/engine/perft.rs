@@ -0,0 +1,293 @@
+//! Perft counts the positions reachable in a number of moves, which is the standard way of
+//! validating move generation against published tables.
+//! [Perft] counts the positions in parallel and can cache counts of positions reached by different move orders,
+//! [Perft::divide] splits the count by the first move for narrowing down mismatches,
+//! and [perft_stats] counts the kinds of moves leading to the positions.
+
+use super::Move;
+use super::board::Board;
+use super::piece::Piece;
+use std::fmt::Display;
+use std::ops::AddAssign;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+/// Count the positions reachable in `depth` moves on a single thread without a cache.
+pub fn perft(board: &Board, depth: usize) -> u64 {
+    Perft::new().count(board, depth)
+}
+
+/// Counts positions reachable in a number of moves.
+/// Moves from the root are shared out between threads, and counts of positions below them
+/// may be cached by their [Board::hash] so that positions reached by different move orders are only counted once.
+#[derive(Debug)]
+pub struct Perft {
+    threads: usize,
+    cache: Option<PerftCache>,
+}
+
+impl Default for Perft {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Perft {
+    /// Count on a single thread without a cache.
+    pub fn new() -> Self {
+        Self {
+            threads: 1,
+            cache: None,
+        }
+    }
+
+    /// Count on the given number of threads, with at least one.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Cache counts in a table of about the given number of megabytes.
+    /// A size of 0 disables the cache.
+    pub fn cache_mb(mut self, megabytes: usize) -> Self {
+        self.cache = (megabytes > 0).then(|| PerftCache::new(megabytes));
+        self
+    }
+
+    /// Count the positions reachable in `depth` moves.
+    pub fn count(&self, board: &Board, depth: usize) -> u64 {
+        if depth <= 1 {
+            return self.count_from(board, depth);
+        }
+        self.divide(board, depth)
+            .iter()
+            .map(|(_, nodes)| nodes)
+            .sum()
+    }
+
+    /// Count the positions reachable in `depth` moves for each legal move in the position,
+    /// in the order the moves are generated.
+    pub fn divide(&self, board: &Board, depth: usize) -> Vec<(Move, u64)> {
+        let moves = board.generate_moves();
+        let counts = split(board, &moves, self.threads, |child| {
+            self.count_from(child, depth.saturating_sub(1))
+        });
+        moves.into_iter().zip(counts).collect()
+    }
+
+    fn count_from(&self, board: &Board, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let moves = board.generate_moves();
+        // Bulk counting: the last moves don't need to be played to be counted.
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        if let Some(nodes) = self.cache.as_ref().and_then(|c| c.get(board.hash(), depth)) {
+            return nodes;
+        }
+
+        let nodes = moves
+            .into_iter()
+            .map(|m| {
+                let mut child = *board;
+                child.make(m);
+                self.count_from(&child, depth - 1)
+            })
+            .sum();
+        if let Some(cache) = &self.cache {
+            cache.insert(board.hash(), depth, nodes);
+        }
+        nodes
+    }
+}
+
+/// A table of counts keyed by position hash and depth, shared between threads without locking.
+/// Each entry stores its count next to the hash XOR'ed with the count,
+/// so an entry torn by two threads writing at once fails the hash check instead of giving a wrong count.
+#[derive(Debug)]
+struct PerftCache {
+    entries: Vec<[AtomicU64; 2]>,
+}
+
+impl PerftCache {
+    fn new(megabytes: usize) -> Self {
+        let bytes = megabytes * 1024 * 1024;
+        let len = (bytes / size_of::<[AtomicU64; 2]>()).max(1);
+        // Round down to a power of two, so the entry can be picked by masking the hash.
+        let len = match len.is_power_of_two() {
+            true => len,
+            false => len.next_power_of_two() / 2,
+        };
+        Self {
+            entries: (0..len)
+                .map(|_| [AtomicU64::new(0), AtomicU64::new(0)])
+                .collect(),
+        }
+    }
+
+    fn entry(&self, hash: u64) -> &[AtomicU64; 2] {
+        &self.entries[hash as usize & (self.entries.len() - 1)]
+    }
+
+    /// The count is kept in the top 56 bits of the data and the depth in the lowest 8.
+    fn get(&self, hash: u64, depth: usize) -> Option<u64> {
+        let [check, data] = self.entry(hash);
+        let data = data.load(Ordering::Relaxed);
+        let check = check.load(Ordering::Relaxed);
+        (check ^ data == hash && (data & 0xff) as usize == depth).then_some(data >> 8)
+    }
+
+    fn insert(&self, hash: u64, depth: usize, nodes: u64) {
+        let [check, data] = self.entry(hash);
+        let value = nodes << 8 | depth as u64 & 0xff;
+        check.store(hash ^ value, Ordering::Relaxed);
+        data.store(value, Ordering::Relaxed);
+    }
+}
+
+/// Play each move on a copy of the board and run `count` on the resulting positions,
+/// with the moves handed out to the threads one at a time. The results are in the order of the moves.
+fn split<T: Send>(
+    board: &Board,
+    moves: &[Move],
+    threads: usize,
+    count: impl Fn(&Board) -> T + Sync,
+) -> Vec<T> {
+    let next = AtomicUsize::new(0);
+    let work = || {
+        let mut results = Vec::new();
+        loop {
+            let i = next.fetch_add(1, Ordering::Relaxed);
+            let Some(&m) = moves.get(i) else {
+                return results;
+            };
+            let mut child = *board;
+            child.make(m);
+            results.push((i, count(&child)));
+        }
+    };
+
+    let mut results: Vec<(usize, T)> = if threads <= 1 {
+        work()
+    } else {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads).map(|_| scope.spawn(work)).collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().expect("perft thread not to panic"))
+                .collect()
+        })
+    };
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Counts of the positions reachable in a number of moves, split by the kind of the last move,
+/// as listed in the published perft tables.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub struct PerftStats {
+    pub nodes: u64,
+    /// Moves capturing a piece, including en pessant.
+    pub captures: u64,
+    pub en_pessants: u64,
+    pub castles: u64,
+    pub promotions: u64,
+    /// Moves giving check, including discovered and double checks.
+    pub checks: u64,
+    /// Moves giving check only with pieces other than the one that moved.
+    pub discovered_checks: u64,
+    /// Moves giving check with two pieces at once.
+    pub double_checks: u64,
+    pub checkmates: u64,
+}
+
+impl AddAssign for PerftStats {
+    fn add_assign(&mut self, other: Self) {
+        self.nodes += other.nodes;
+        self.captures += other.captures;
+        self.en_pessants += other.en_pessants;
+        self.castles += other.castles;
+        self.promotions += other.promotions;
+        self.checks += other.checks;
+        self.discovered_checks += other.discovered_checks;
+        self.double_checks += other.double_checks;
+        self.checkmates += other.checkmates;
+    }
+}
+
+impl Display for PerftStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "nodes {} captures {} e.p. {} castles {} promotions {} checks {} discovered checks {} double checks {} checkmates {}",
+            self.nodes,
+            self.captures,
+            self.en_pessants,
+            self.castles,
+            self.promotions,
+            self.checks,
+            self.discovered_checks,
+            self.double_checks,
+            self.checkmates
+        )
+    }
+}
+
+/// Count the positions reachable in `depth` moves along with the kinds of moves leading to them,
+/// sharing the moves from the root between the given number of threads.
+/// Every last move has to be played to be classified, so this is a lot slower than [Perft::count].
+pub fn perft_stats(board: &Board, depth: usize, threads: usize) -> PerftStats {
+    if depth == 0 {
+        return PerftStats {
+            nodes: 1,
+            ..Default::default()
+        };
+    }
+    let moves = board.generate_moves();
+    let mut stats = PerftStats::default();
+    if depth == 1 {
+        for m in moves {
+            stats += classify(board, m);
+        }
+        return stats;
+    }
+    for child in split(board, &moves, threads, |child| {
+        perft_stats(child, depth - 1, 1)
+    }) {
+        stats += child;
+    }
+    stats
+}
+
+/// The stats of a single move played in a position.
+fn classify(board: &Board, m: Move) -> PerftStats {
+    let castling = board.castling(m);
+    let mut after = *board;
+    after.make(m);
+    let king = if after.white_to_move {
+        Piece::KingWhite
+    } else {
+        Piece::KingBlack
+    };
+    let checkers = after
+        .pieces(king)
+        .first()
+        .map(|king| after.attackers_of(king, !after.white_to_move))
+        .unwrap_or_default();
+    // The piece giving a direct check stands where the move ended, which is the rook's square when castling.
+    let landed = castling.map_or(m.to(), |[_, (_, rook_to)]| rook_to);
+    let is_check = !checkers.is_empty();
+
+    PerftStats {
+        nodes: 1,
+        captures: m.is_capture() as u64,
+        en_pessants: m.is_en_pessant() as u64,
+        castles: m.is_castling() as u64,
+        promotions: m.is_promotion() as u64,
+        checks: is_check as u64,
+        discovered_checks: (is_check && !checkers.contains(landed)) as u64,
+        double_checks: (checkers.count() > 1) as u64,
+        checkmates: (is_check && after.generate_moves().is_empty()) as u64,
+    }
+}
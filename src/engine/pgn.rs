@@ -0,0 +1,552 @@
+//! Portable Game Notation (PGN), the common format for storing chess games.
+//! A game is a list of tag pairs like `[White "Kasparov"]`,
+//! followed by the moves in SAN along with comments, annotations, variations and the result.
+
+use super::Move;
+use super::board::{Board, FenError, PositionError};
+use super::game::{Game, STANDARD_FEN};
+use super::outcome::GameOutcome;
+use super::san::SanError;
+use std::error::Error;
+use std::fmt::Display;
+
+/// The tags every exported game has, in the order they are written.
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+/// The longest line written when exporting movetext.
+const MAX_LINE_LENGTH: usize = 80;
+
+/// The result of a game, as written at the end of its movetext.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GameResult {
+    WhiteWins,
+    BlackWins,
+    Draw,
+    /// The game is still going, or the result is unknown.
+    Unknown,
+}
+
+impl GameResult {
+    /// Read a result like "1-0" or "*".
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(result: &str) -> Option<Self> {
+        match result {
+            "1-0" => Some(GameResult::WhiteWins),
+            "0-1" => Some(GameResult::BlackWins),
+            "1/2-1/2" => Some(GameResult::Draw),
+            "*" => Some(GameResult::Unknown),
+            _ => None,
+        }
+    }
+
+    /// The result of a game that ended with an outcome, or is still going.
+    pub fn from_outcome(outcome: Option<GameOutcome>) -> Self {
+        match outcome.map(|o| o.winner()) {
+            Some(Some(true)) => GameResult::WhiteWins,
+            Some(Some(false)) => GameResult::BlackWins,
+            Some(None) => GameResult::Draw,
+            None => GameResult::Unknown,
+        }
+    }
+}
+
+impl Display for GameResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GameResult::WhiteWins => write!(f, "1-0"),
+            GameResult::BlackWins => write!(f, "0-1"),
+            GameResult::Draw => write!(f, "1/2-1/2"),
+            GameResult::Unknown => write!(f, "*"),
+        }
+    }
+}
+
+/// A move in a PGN game, along with what was written about it.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct PgnMove {
+    pub r#move: Move,
+    /// Numeric Annotation Glyphs like `$1`. Suffixes like "!" and "?!" are read as their NAGs.
+    pub nags: Vec<u8>,
+    /// Comments written after the move.
+    pub comments: Vec<String>,
+    /// Alternatives to this move, each played from the position before it.
+    pub variations: Vec<Line>,
+}
+
+/// A sequence of moves, like the main line of a game or a variation of it.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct Line {
+    /// Comments written before the first move.
+    pub comments: Vec<String>,
+    pub moves: Vec<PgnMove>,
+}
+
+/// A game read from or written as PGN.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Pgn {
+    /// Tag pairs, in the order they were given.
+    pub tags: Vec<(String, String)>,
+    /// The position the game starts from, which is given by the FEN tag for non-standard starts.
+    pub start: Board,
+    pub line: Line,
+    pub result: GameResult,
+}
+
+/// Reasons PGN text could not be read.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum PgnError {
+    /// A tag pair was not written like `[Name "value"]`.
+    Tag(String),
+    /// The FEN tag could not be read.
+    Fen(FenError),
+    /// The FEN tag holds a position that could not occur in a game.
+    Position(PositionError),
+    /// A move could not be read or is not legal. Holds the number of the half move, counting from 1.
+    Move { ply: usize, error: SanError },
+    /// The movetext contains something that is not allowed where it is,
+    /// like a variation before any move or an unmatched parenthesis.
+    Movetext(String),
+    /// The text ended in the middle of a comment, tag or variation.
+    UnexpectedEnd,
+}
+
+impl Display for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PgnError::Tag(tag) => write!(f, "invalid tag pair '{tag}'"),
+            PgnError::Fen(e) => write!(f, "invalid FEN tag: {e}"),
+            PgnError::Position(e) => write!(f, "invalid FEN tag position: {e}"),
+            PgnError::Move { ply, error } => write!(f, "invalid move at ply {ply}: {error}"),
+            PgnError::Movetext(text) => write!(f, "unexpected '{text}' in movetext"),
+            PgnError::UnexpectedEnd => write!(f, "unexpected end of PGN"),
+        }
+    }
+}
+
+impl Error for PgnError {}
+
+/// The pieces PGN text is made of.
+#[derive(PartialEq, Eq, Clone, Debug)]
+enum Token {
+    Tag(String, String),
+    Comment(String),
+    Nag(u8),
+    San(String),
+    Result(GameResult),
+    OpenVariation,
+    CloseVariation,
+}
+
+/// Split PGN text into tokens, skipping move numbers and escaped lines.
+fn tokenize(text: &str) -> Result<Vec<Token>, PgnError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(c) = chars.next() {
+        let at_line_start = line_start;
+        line_start = c == '\n';
+        match c {
+            // Lines starting with '%' are escaped, and ';' comments out the rest of a line.
+            '%' if at_line_start => {
+                chars.by_ref().find(|&c| c == '\n');
+                line_start = true;
+            }
+            ';' => {
+                let comment: String = chars.by_ref().take_while(|&c| c != '\n').collect();
+                tokens.push(Token::Comment(comment.trim().to_owned()));
+                line_start = true;
+            }
+            '{' => {
+                let mut comment = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => comment.push(c),
+                        None => return Err(PgnError::UnexpectedEnd),
+                    }
+                }
+                tokens.push(Token::Comment(comment.trim().to_owned()));
+            }
+            '[' => {
+                let mut tag = String::new();
+                let mut in_string = false;
+                loop {
+                    match chars.next() {
+                        Some(']') if !in_string => break,
+                        Some('\\') if in_string => tag.extend(['\\', chars.next().unwrap_or('\\')]),
+                        Some(c) => {
+                            in_string ^= c == '"';
+                            tag.push(c);
+                        }
+                        None => return Err(PgnError::UnexpectedEnd),
+                    }
+                }
+                tokens.push(parse_tag(&tag)?);
+            }
+            '(' => tokens.push(Token::OpenVariation),
+            ')' => tokens.push(Token::CloseVariation),
+            '$' => {
+                let mut nag = String::new();
+                while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                    nag.push(c);
+                }
+                let nag = nag
+                    .parse()
+                    .map_err(|_| PgnError::Movetext(format!("${nag}")))?;
+                tokens.push(Token::Nag(nag));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut symbol = String::from(c);
+                while let Some(c) =
+                    chars.next_if(|&c| !c.is_whitespace() && !"[]{}();$".contains(c))
+                {
+                    symbol.push(c);
+                }
+                tokens.extend(parse_symbol(&symbol)?);
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Read a tag pair like `Event "Casual game"`, without the square brackets.
+fn parse_tag(tag: &str) -> Result<Token, PgnError> {
+    let invalid = || PgnError::Tag(tag.to_owned());
+    let (name, value) = tag
+        .trim()
+        .split_once(char::is_whitespace)
+        .ok_or_else(invalid)?;
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .ok_or_else(invalid)?;
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(invalid());
+    }
+
+    // Quotes and backslashes in the value are escaped by a backslash.
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        unescaped.push(if c == '\\' {
+            chars.next().ok_or_else(invalid)?
+        } else {
+            c
+        });
+    }
+    Ok(Token::Tag(name.to_owned(), unescaped))
+}
+
+/// Read a symbol from the movetext, which is a result, a move number or a move in SAN.
+/// A move may be preceded by its move number, like "12.e4", and followed by annotations like "!?".
+fn parse_symbol(symbol: &str) -> Result<Vec<Token>, PgnError> {
+    if let Some(result) = GameResult::from_str(symbol) {
+        return Ok(vec![Token::Result(result)]);
+    }
+
+    let san = symbol.trim_start_matches(|c: char| c.is_ascii_digit());
+    let san = match san.strip_prefix('.') {
+        Some(after_number) => after_number.trim_start_matches('.'),
+        // Without a dot the digits were part of the move, like in "0-0", unless they were all there was.
+        None if san.is_empty() => "",
+        None => symbol,
+    };
+    if san.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let annotation_start = san.trim_end_matches(['!', '?']).len();
+    let (san, annotation) = san.split_at(annotation_start);
+    let nag = match annotation {
+        "" => None,
+        "!" => Some(1),
+        "?" => Some(2),
+        "!!" => Some(3),
+        "??" => Some(4),
+        "!?" => Some(5),
+        "?!" => Some(6),
+        _ => return Err(PgnError::Movetext(symbol.to_owned())),
+    };
+    if san.is_empty() {
+        return Err(PgnError::Movetext(symbol.to_owned()));
+    }
+
+    let mut tokens = vec![Token::San(san.to_owned())];
+    tokens.extend(nag.map(Token::Nag));
+    Ok(tokens)
+}
+
+/// Read a line of moves played from `board`, up to the end of the variation or game.
+/// `ply` is the number of half moves played before the line.
+fn parse_line(
+    tokens: &[Token],
+    position: &mut usize,
+    mut board: Board,
+    ply: usize,
+    is_variation: bool,
+) -> Result<Line, PgnError> {
+    let mut line = Line::default();
+    // The position before the last move, which is where its variations are played from.
+    let mut before_last_move = board;
+
+    while let Some(token) = tokens.get(*position) {
+        *position += 1;
+        match token {
+            Token::San(san) => {
+                let r#move = Move::from_san(&board, san).map_err(|error| PgnError::Move {
+                    ply: ply + line.moves.len() + 1,
+                    error,
+                })?;
+                before_last_move = board;
+                board.apply(r#move);
+                line.moves.push(PgnMove {
+                    r#move,
+                    nags: Vec::new(),
+                    comments: Vec::new(),
+                    variations: Vec::new(),
+                });
+            }
+            Token::Nag(nag) => match line.moves.last_mut() {
+                Some(last) => last.nags.push(*nag),
+                None => return Err(PgnError::Movetext(format!("${nag}"))),
+            },
+            Token::Comment(comment) => match line.moves.last_mut() {
+                Some(last) => last.comments.push(comment.clone()),
+                None => line.comments.push(comment.clone()),
+            },
+            Token::OpenVariation => {
+                let variation_ply = ply + line.moves.len().saturating_sub(1);
+                let Some(last) = line.moves.last_mut() else {
+                    return Err(PgnError::Movetext("(".to_owned()));
+                };
+                let variation =
+                    parse_line(tokens, position, before_last_move, variation_ply, true)?;
+                last.variations.push(variation);
+            }
+            Token::CloseVariation if is_variation => return Ok(line),
+            Token::CloseVariation => return Err(PgnError::Movetext(")".to_owned())),
+            // The result or the tags of the next game end the main line.
+            Token::Result(_) | Token::Tag(..) if !is_variation => {
+                *position -= 1;
+                return Ok(line);
+            }
+            Token::Result(result) => return Err(PgnError::Movetext(result.to_string())),
+            Token::Tag(..) => return Err(PgnError::UnexpectedEnd),
+        }
+    }
+
+    match is_variation {
+        true => Err(PgnError::UnexpectedEnd),
+        false => Ok(line),
+    }
+}
+
+impl Pgn {
+    /// Read the first game from PGN text.
+    pub fn parse(text: &str) -> Result<Self, PgnError> {
+        let tokens = tokenize(text)?;
+        Self::parse_game(&tokens, &mut 0)
+    }
+
+    /// Read every game from PGN text, like a database or the games of a tournament.
+    pub fn parse_all(text: &str) -> Result<Vec<Self>, PgnError> {
+        let tokens = tokenize(text)?;
+        let mut position = 0;
+        let mut games = Vec::new();
+        while position < tokens.len() {
+            games.push(Self::parse_game(&tokens, &mut position)?);
+        }
+        Ok(games)
+    }
+
+    /// Read a game starting at `position` in the tokens, leaving `position` after the game.
+    fn parse_game(tokens: &[Token], position: &mut usize) -> Result<Self, PgnError> {
+        let mut tags = Vec::new();
+        while let Some(Token::Tag(name, value)) = tokens.get(*position) {
+            tags.push((name.clone(), value.clone()));
+            *position += 1;
+        }
+
+        let fen = tags
+            .iter()
+            .find(|(name, _)| name == "FEN")
+            .map(|(_, fen)| fen.as_str());
+        let start = Board::from_fen(fen.unwrap_or(STANDARD_FEN)).map_err(PgnError::Fen)?;
+        start.validate().map_err(PgnError::Position)?;
+        let ply =
+            (start.full_move_counter().max(1) as usize - 1) * 2 + !start.white_to_move as usize;
+        let line = parse_line(tokens, position, start, ply, false)?;
+
+        // The result ends the movetext, but may be left out at the end of the text.
+        let result = match tokens.get(*position) {
+            Some(Token::Result(result)) => {
+                *position += 1;
+                *result
+            }
+            _ => tags
+                .iter()
+                .find(|(name, _)| name == "Result")
+                .and_then(|(_, result)| GameResult::from_str(result))
+                .unwrap_or(GameResult::Unknown),
+        };
+
+        Ok(Self {
+            tags,
+            start,
+            line,
+            result,
+        })
+    }
+
+    /// Create a record of a game, with the moves that have been played so far.
+    /// The Seven Tag Roster is filled in with unknown values, except for the result.
+    pub fn from_game(game: &Game) -> Self {
+        let moves = game
+            .moves()
+            .iter()
+            .map(|played| PgnMove {
+                r#move: played.r#move,
+                nags: Vec::new(),
+                comments: Vec::new(),
+                variations: Vec::new(),
+            })
+            .collect();
+        let result = GameResult::from_outcome(game.outcome());
+        Self {
+            tags: vec![("Result".to_owned(), result.to_string())],
+            start: *game.start(),
+            line: Line {
+                comments: Vec::new(),
+                moves,
+            },
+            result,
+        }
+    }
+
+    /// The game following the main line.
+    pub fn game(&self) -> Game {
+        let mut game = Game::new(self.start);
+        for m in &self.line.moves {
+            game.push(m.r#move);
+        }
+        game
+    }
+
+    /// Look up the value of a tag.
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Set the value of a tag, replacing any earlier value.
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value.to_owned(),
+            None => self.tags.push((name.to_owned(), value.to_owned())),
+        }
+    }
+}
+
+/// Write the movetext of a line played from `board` as a list of words.
+fn write_line(board: Board, line: &Line, words: &mut Vec<String>) {
+    let mut board = board;
+    for comment in &line.comments {
+        words.push(format!("{{{}}}", comment.replace('}', "")));
+    }
+
+    // Black moves are numbered when they don't follow right after white's move.
+    let mut needs_number = true;
+    for m in &line.moves {
+        let number = board.full_move_counter();
+        if board.white_to_move {
+            words.push(format!("{number}."));
+        } else if needs_number {
+            words.push(format!("{number}..."));
+        }
+        words.push(m.r#move.to_san(&board));
+        needs_number = false;
+
+        words.extend(m.nags.iter().map(|nag| format!("${nag}")));
+        for comment in &m.comments {
+            words.push(format!("{{{}}}", comment.replace('}', "")));
+            needs_number = true;
+        }
+        for variation in &m.variations {
+            let start = words.len();
+            write_line(board, variation, words);
+            match words.get_mut(start..) {
+                Some([first, .., last]) => {
+                    first.insert(0, '(');
+                    last.push(')');
+                }
+                Some([only]) => *only = format!("({only})"),
+                _ => words.push("()".to_owned()),
+            }
+            needs_number = true;
+        }
+
+        board.apply(m.r#move);
+    }
+}
+
+/// Writes the game in PGN export format.
+/// The Seven Tag Roster comes first, followed by the `SetUp` and `FEN` tags for games
+/// that don't start from the standard position, and then any other tags.
+impl Display for Pgn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let result = self.result.to_string();
+        let fen = self.start.to_fen();
+        let mut tags: Vec<(&str, &str)> = SEVEN_TAG_ROSTER
+            .iter()
+            .map(|&(name, default)| match name {
+                "Result" => (name, result.as_str()),
+                _ => (name, self.tag(name).unwrap_or(default)),
+            })
+            .collect();
+        if fen != STANDARD_FEN {
+            tags.extend([("SetUp", "1"), ("FEN", fen.as_str())]);
+        }
+        for (name, value) in &self.tags {
+            if !tags.iter().any(|(n, _)| n == name) {
+                tags.push((name, value));
+            }
+        }
+        for (name, value) in tags {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(f, "[{name} \"{value}\"]")?;
+        }
+        writeln!(f)?;
+
+        let mut words = Vec::new();
+        write_line(self.start, &self.line, &mut words);
+        words.push(result);
+
+        let mut line_length = 0;
+        for word in words {
+            if line_length > 0 && line_length + 1 + word.len() > MAX_LINE_LENGTH {
+                writeln!(f)?;
+                line_length = 0;
+            } else if line_length > 0 {
+                write!(f, " ")?;
+                line_length += 1;
+            }
+            write!(f, "{word}")?;
+            line_length += word.len();
+        }
+        writeln!(f)
+    }
+}
@@ -64,8 +64,23 @@ impl Piece {
         *self as u8 != 0 && *self as u8 >> 3 < 1
     }
 
+    /// The usual material value of this piece in centipawns, regardless of color.
+    /// The king can't be traded, so it is given no value.
+    pub fn value(&self) -> i32 {
+        use Piece::*;
+        match self {
+            PawnWhite | PawnBlack => 100,
+            KnightWhite | KnightBlack => 320,
+            BishopWhite | BishopBlack => 330,
+            RookWhite | RookBlack => 500,
+            QueenWhite | QueenBlack => 900,
+            KingWhite | KingBlack => 0,
+        }
+    }
+
     /// Check if another piece is of the same kind.
     /// For instance, are both rooks?
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_same_kind(&self, other: &Piece) -> bool {
         use Piece::*;
         match (self, other) {
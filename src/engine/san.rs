@@ -0,0 +1,202 @@
+//! Standard Algebraic Notation (SAN), the notation used by humans and PGN files.
+//! A move like `Nbd7` names the moving piece and its target square,
+//! along with just enough of the origin square to tell it apart from other moves.
+
+use super::board::Board;
+use super::r#move::{Move, MoveKind};
+use super::piece::Piece;
+use super::{FILES, Square, square_from_algebraic, square_to_algebraic};
+use std::error::Error;
+use std::fmt::Display;
+
+/// Reasons a SAN move could not be read by [Move::from_san].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum SanError {
+    /// The text is not a move in SAN.
+    Invalid(String),
+    /// No legal move in the position matches the text.
+    Illegal(String),
+    /// More than one legal move in the position matches the text.
+    Ambiguous(String),
+}
+
+impl Display for SanError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SanError::Invalid(san) => write!(f, "'{san}' is not a move in SAN"),
+            SanError::Illegal(san) => write!(f, "'{san}' is not a legal move"),
+            SanError::Ambiguous(san) => write!(f, "'{san}' matches more than one legal move"),
+        }
+    }
+}
+
+impl Error for SanError {}
+
+/// The letter of a piece in SAN, which is always uppercase.
+fn piece_letter(piece: Piece) -> char {
+    piece.to_char().to_ascii_uppercase()
+}
+
+impl Move {
+    /// Write this move in SAN, for the position it is played in.
+    /// The move is assummed to be legal.
+    pub fn to_san(&self, board: &Board) -> String {
+        let piece = board.at(self.from()).expect("move to target a piece");
+        let mut san = String::new();
+
+        if self.kind() == MoveKind::KingCastle {
+            san.push_str("O-O");
+        } else if self.kind() == MoveKind::QueenCastle {
+            san.push_str("O-O-O");
+        } else if matches!(piece, Piece::PawnWhite | Piece::PawnBlack) {
+            // Pawns are named by their file when capturing, which includes en pessant.
+            if self.is_capture() {
+                san.push(FILES[(self.from() % 8) as usize]);
+                san.push('x');
+            }
+            san.push_str(&square_to_algebraic(self.to()));
+            if let Some(promote_to) = self.promote_to() {
+                san.push('=');
+                san.push(piece_letter(promote_to));
+            }
+        } else {
+            san.push(piece_letter(piece));
+
+            // Tell the move apart from moves by other pieces of the same kind to the same square,
+            // preferring the file, then the rank, and using both only when neither is enough.
+            let others: Vec<Square> = board
+                .generate_moves()
+                .into_iter()
+                .filter(|m| {
+                    m.to() == self.to()
+                        && m.from() != self.from()
+                        && board.at(m.from()) == Some(piece)
+                })
+                .filter(|m| !m.is_castling())
+                .map(|m| m.from())
+                .collect();
+            if !others.is_empty() {
+                let same_file = others.iter().any(|s| s % 8 == self.from() % 8);
+                let same_rank = others.iter().any(|s| s / 8 == self.from() / 8);
+                let from = square_to_algebraic(self.from());
+                match (same_file, same_rank) {
+                    (false, _) => san.push_str(&from[..1]),
+                    (true, false) => san.push_str(&from[1..]),
+                    (true, true) => san.push_str(&from),
+                }
+            }
+
+            if self.is_capture() {
+                san.push('x');
+            }
+            san.push_str(&square_to_algebraic(self.to()));
+        }
+
+        // Mark checks and checkmates.
+        let mut after = *board;
+        after.make(*self);
+        if after.is_in_check() {
+            san.push(if after.generate_moves().is_empty() {
+                '#'
+            } else {
+                '+'
+            });
+        }
+
+        san
+    }
+
+    /// Read a move in SAN, resolving it against the legal moves of the position it is played in.
+    /// Check and annotation marks like '+', '#', '!' and '?' are ignored,
+    /// as is a trailing "e.p." for en pessant captures.
+    /// Castling may be written with the letter O or the digit 0.
+    pub fn from_san(board: &Board, san: &str) -> Result<Move, SanError> {
+        let invalid = || SanError::Invalid(san.to_owned());
+        let text = san
+            .trim()
+            .trim_end_matches("e.p.")
+            .trim_end()
+            .trim_end_matches(['+', '#', '!', '?']);
+
+        let legal_moves = board.generate_moves();
+        let candidates: Vec<Move> = match text {
+            "O-O" | "0-0" | "O-O-O" | "0-0-0" => {
+                let kind = match text.len() {
+                    3 => MoveKind::KingCastle,
+                    _ => MoveKind::QueenCastle,
+                };
+                legal_moves
+                    .into_iter()
+                    .filter(|m| m.kind() == kind)
+                    .collect()
+            }
+            _ => {
+                // Split off the piece letter in front and the promotion behind,
+                // leaving the target square at the end and anything used to disambiguate before it.
+                let mut chars: Vec<char> = text.chars().collect();
+                let letter = match chars.first() {
+                    Some(c @ ('N' | 'B' | 'R' | 'Q' | 'K')) => {
+                        let c = *c;
+                        chars.remove(0);
+                        c
+                    }
+                    _ => 'P',
+                };
+                let promote_to = match chars.as_slice() {
+                    [.., '=' | '1'..='8', p @ ('N' | 'B' | 'R' | 'Q')] if letter == 'P' => Some(*p),
+                    _ => None,
+                };
+                if promote_to.is_some() {
+                    chars.pop();
+                    if chars.last() == Some(&'=') {
+                        chars.pop();
+                    }
+                }
+                if chars.len() < 2 {
+                    return Err(invalid());
+                }
+                let target: String = chars.split_off(chars.len() - 2).into_iter().collect();
+                let to = square_from_algebraic(&target).ok_or_else(invalid)?;
+                if chars.last() == Some(&'x') {
+                    chars.pop();
+                }
+                let (mut from_file, mut from_rank) = (None, None);
+                for c in chars {
+                    match c {
+                        'a'..='h' if from_file.is_none() => from_file = Some(c as u8 - b'a'),
+                        '1'..='8' if from_rank.is_none() => from_rank = Some(c as u8 - b'1'),
+                        _ => return Err(invalid()),
+                    }
+                }
+
+                // Every legal move is by the side to move, so the letters name its pieces.
+                let side_piece = |letter: char| {
+                    let letter = match board.white_to_move {
+                        true => letter,
+                        false => letter.to_ascii_lowercase(),
+                    };
+                    Piece::from_char(&letter).expect("SAN letters to name pieces")
+                };
+                let piece = side_piece(letter);
+                let promote_to = promote_to.map(side_piece);
+                legal_moves
+                    .into_iter()
+                    .filter(|&m| {
+                        board.at(m.from()) == Some(piece)
+                            && m.to() == to
+                            && !m.is_castling()
+                            && m.promote_to() == promote_to
+                            && from_file.is_none_or(|f| m.from() % 8 == f)
+                            && from_rank.is_none_or(|r| m.from() / 8 == r)
+                    })
+                    .collect()
+            }
+        };
+
+        match candidates.as_slice() {
+            [m] => Ok(*m),
+            [] => Err(SanError::Illegal(san.to_owned())),
+            _ => Err(SanError::Ambiguous(san.to_owned())),
+        }
+    }
+}
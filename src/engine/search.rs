@@ -0,0 +1,788 @@
+//! Finds the best move in a position with a negamax alpha-beta search.
+//! The search deepens one ply at a time until it runs out of time or depth,
+//! so there is always a move from the last completed iteration to play.
+//! Results are kept in a [TranspositionTable], so that the best moves of each iteration are searched first in the next.
+
+use super::Move;
+use super::board::Board;
+use super::eval::Evaluator;
+use super::eval::params::Params;
+use super::movelist::MoveList;
+use super::movepick::MovePicker;
+use super::ordering::{CutoffStats, MoveOrdering};
+use super::piece::Piece;
+use super::transposition::{Bound, TranspositionTable};
+use std::fmt::Display;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// The deepest the search goes, counted in plies from the root.
+pub const MAX_PLY: usize = 128;
+
+/// The size of the transposition table in megabytes unless set otherwise.
+pub const DEFAULT_HASH_MB: usize = 16;
+
+/// The score of checkmating on the next move. Mates further away score one less for each extra ply.
+pub const MATE: i32 = 30_000;
+
+/// A score outside every possible score, for opening the search window.
+pub const INFINITY: i32 = 32_000;
+
+const DRAW: i32 = 0;
+
+/// Time kept back from every move for the messages between the engine and the GUI.
+const MOVE_OVERHEAD: Duration = Duration::from_millis(30);
+
+/// The moves left until the next time control when the GUI doesn't say.
+const DEFAULT_MOVES_TO_GO: u32 = 30;
+
+/// How the search checks the clock and the stop flag, every this many nodes.
+const CHECK_INTERVAL: u64 = 1024;
+
+/// How much a capture may gain beyond the value of the captured piece, through the position improving.
+/// Captures that can't raise alpha even with this much extra are pruned in quiescence search.
+const DELTA_MARGIN: i32 = 200;
+
+/// The half width of the window around the score of the last iteration.
+const ASPIRATION_WINDOW: i32 = 25;
+/// Shallow iterations are fast and their scores swing a lot, so they are searched with a full window.
+const ASPIRATION_MIN_DEPTH: usize = 4;
+
+/// Null move pruning searches the position after passing this many plies less deep, and more at high depths.
+const NULL_MOVE_REDUCTION: usize = 3;
+const NULL_MOVE_MIN_DEPTH: usize = 3;
+
+/// How far above beta the static evaluation has to be for each ply left, to prune with reverse futility.
+const REVERSE_FUTILITY_MARGIN: i32 = 80;
+const REVERSE_FUTILITY_DEPTH: usize = 6;
+
+/// How far below alpha the static evaluation has to be for each ply left, to skip quiet moves.
+const FUTILITY_MARGIN: i32 = 100;
+const FUTILITY_DEPTH: usize = 3;
+
+/// Late move reductions only apply to moves after this many, with at least this many plies left.
+const LMR_MIN_MOVES: usize = 3;
+const LMR_MIN_DEPTH: usize = 3;
+
+/// When to stop searching, as given by the UCI 'go' command.
+/// With no limits at all the search goes on until it is stopped.
+#[derive(PartialEq, Eq, Clone, Copy, Default, Debug)]
+pub struct Limits {
+    pub depth: Option<usize>,
+    pub nodes: Option<u64>,
+    /// Search for exactly this long.
+    pub movetime: Option<Duration>,
+    /// The time left on the clock of the side to move.
+    pub time: Option<Duration>,
+    /// The time added to the clock of the side to move after each move.
+    pub increment: Duration,
+    pub moves_to_go: Option<u32>,
+    /// Search until stopped, even if a mate is found.
+    pub infinite: bool,
+}
+
+impl Limits {
+    /// The time to spend on the move, as the time after which no new iteration is started
+    /// and the time at which the search is stopped.
+    fn time_budget(&self) -> Option<(Duration, Duration)> {
+        if self.infinite {
+            return None;
+        }
+        if let Some(movetime) = self.movetime {
+            let hard = movetime
+                .saturating_sub(MOVE_OVERHEAD)
+                .max(Duration::from_millis(1));
+            return Some((hard, hard));
+        }
+        let time = self.time?;
+        let usable = time.saturating_sub(MOVE_OVERHEAD);
+        let moves_to_go = self.moves_to_go.unwrap_or(DEFAULT_MOVES_TO_GO).max(1);
+        let target = (time / moves_to_go + self.increment * 3 / 4).min(usable);
+        // An iteration usually takes longer than all the ones before it, so don't start one past half the target.
+        // The search may overrun the target to finish an iteration, but never the time on the clock.
+        Some((target / 2, (target * 2).min(usable)))
+    }
+}
+
+/// Parts of the search that can be switched off or tuned, so that what each is worth can be measured.
+/// Everything is switched on by default.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct SearchOptions {
+    /// Principal variation search: search moves after the first with a null window, expecting them to fail low.
+    pub pvs: bool,
+    /// Null move pruning: cut off when passing the turn still leaves the position above beta.
+    pub null_move: bool,
+    /// Late move reductions: search quiet moves late in the move order less deep.
+    pub late_move_reductions: bool,
+    /// Futility pruning: skip quiet moves near the leaves when the position is far below alpha.
+    pub futility: bool,
+    /// Reverse futility pruning: cut off near the leaves when the position is far above beta.
+    pub reverse_futility: bool,
+    /// Check extensions: search one ply deeper when in check.
+    pub check_extensions: bool,
+    /// Aspiration windows: search each iteration with a narrow window around the last score.
+    pub aspiration_windows: bool,
+    /// Late moves are reduced by `base + ln(depth) * ln(move number) / divisor` plies,
+    /// with the base and divisor given in hundredths.
+    pub lmr_base: u32,
+    pub lmr_divisor: u32,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            pvs: true,
+            null_move: true,
+            late_move_reductions: true,
+            futility: true,
+            reverse_futility: true,
+            check_extensions: true,
+            aspiration_windows: true,
+            lmr_base: 75,
+            lmr_divisor: 225,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// The switches by their UCI option names, for listing and setting them.
+    pub fn switches(&mut self) -> [(&'static str, &mut bool); 7] {
+        [
+            ("PVS", &mut self.pvs),
+            ("NullMove", &mut self.null_move),
+            ("LMR", &mut self.late_move_reductions),
+            ("Futility", &mut self.futility),
+            ("ReverseFutility", &mut self.reverse_futility),
+            ("CheckExtensions", &mut self.check_extensions),
+            ("AspirationWindows", &mut self.aspiration_windows),
+        ]
+    }
+
+    /// The reduction in plies of the `moves`th move searched with `depth` plies left, for each depth and move below 64.
+    fn reductions(&self) -> Box<[[u8; 64]; 64]> {
+        let base = self.lmr_base as f64 / 100.0;
+        let divisor = self.lmr_divisor.max(1) as f64 / 100.0;
+        let mut table = Box::new([[0; 64]; 64]);
+        for (depth, row) in table.iter_mut().enumerate().skip(1) {
+            for (moves, reduction) in row.iter_mut().enumerate().skip(1) {
+                let plies = base + (depth as f64).ln() * (moves as f64).ln() / divisor;
+                *reduction = plies.max(0.0) as u8;
+            }
+        }
+        table
+    }
+}
+
+/// A score as reported over UCI, either in centipawns or as moves until mate.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Score {
+    Centipawns(i32),
+    /// Moves until the side to move mates, or is mated if negative.
+    Mate(i32),
+}
+
+impl Score {
+    /// Read a score returned by the search, where mates are counted in plies from [MATE].
+    pub fn from_value(value: i32) -> Self {
+        if !is_mate(value) {
+            Score::Centipawns(value)
+        } else if value > 0 {
+            Score::Mate((MATE - value + 1) / 2)
+        } else {
+            Score::Mate(-(MATE + value) / 2)
+        }
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Score::Centipawns(cp) => write!(f, "cp {cp}"),
+            Score::Mate(moves) => write!(f, "mate {moves}"),
+        }
+    }
+}
+
+/// Is the score a forced mate for either side?
+pub fn is_mate(value: i32) -> bool {
+    value.abs() >= MATE - MAX_PLY as i32
+}
+
+/// The result of a completed iteration of the search.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Report {
+    pub depth: usize,
+    /// The score for the side to move, with mates counted in plies from [MATE].
+    pub value: i32,
+    /// Nodes searched since the search started.
+    pub nodes: u64,
+    /// Time since the search started.
+    pub time: Duration,
+    /// The principal variation, the line the search expects to be played.
+    pub pv: Vec<Move>,
+    /// How full the transposition table is, in permille.
+    pub hashfull: u32,
+    /// How well moves were ordered, for tuning the search.
+    pub cutoffs: CutoffStats,
+}
+
+impl Report {
+    pub fn best_move(&self) -> Option<Move> {
+        self.pv.first().copied()
+    }
+
+    pub fn score(&self) -> Score {
+        Score::from_value(self.value)
+    }
+}
+
+/// Formats the report as the arguments of a UCI 'info' line.
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let millis = self.time.as_millis() as u64;
+        write!(
+            f,
+            "depth {} score {} nodes {} nps {} hashfull {} time {}",
+            self.depth,
+            self.score(),
+            self.nodes,
+            self.nodes * 1000 / millis.max(1),
+            self.hashfull,
+            millis
+        )?;
+        if !self.pv.is_empty() {
+            write!(f, " pv")?;
+            for m in &self.pv {
+                write!(f, " {m}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Searches positions for the best move.
+/// The searcher is kept between searches, so that what it learns about a game carries over to the next move.
+#[derive(Debug)]
+pub struct Searcher {
+    stop: Arc<AtomicBool>,
+    stopped: bool,
+    nodes: u64,
+    node_limit: Option<u64>,
+    start: Instant,
+    deadline: Option<Instant>,
+    /// Hashes of the positions leading up to the one being searched, for detecting repetitions.
+    keys: Vec<u64>,
+    /// The principal variation found from each ply, where the line from a ply starts at that index.
+    pv: Box<[[Move; MAX_PLY]; MAX_PLY]>,
+    pv_len: [usize; MAX_PLY],
+    /// The move played at each ply on the way to the position being searched.
+    played: [Option<Move>; MAX_PLY],
+    tt: TranspositionTable,
+    ordering: MoveOrdering,
+    evaluator: Evaluator,
+    cutoffs: CutoffStats,
+    options: SearchOptions,
+    /// Late move reductions by depth and move number, computed from the options.
+    reductions: Box<[[u8; 64]; 64]>,
+}
+
+impl Default for Searcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Searcher {
+    pub fn new() -> Self {
+        Self {
+            stop: Arc::new(AtomicBool::new(false)),
+            stopped: false,
+            nodes: 0,
+            node_limit: None,
+            start: Instant::now(),
+            deadline: None,
+            keys: Vec::new(),
+            pv: Box::new([[Move::NULL; MAX_PLY]; MAX_PLY]),
+            pv_len: [0; MAX_PLY],
+            played: [None; MAX_PLY],
+            tt: TranspositionTable::new(DEFAULT_HASH_MB),
+            ordering: MoveOrdering::new(),
+            evaluator: Evaluator::new(),
+            cutoffs: CutoffStats::default(),
+            options: SearchOptions::default(),
+            reductions: SearchOptions::default().reductions(),
+        }
+    }
+
+    pub fn set_options(&mut self, options: SearchOptions) {
+        self.options = options;
+        self.reductions = options.reductions();
+    }
+
+    /// Evaluate positions with other weights, like ones loaded from a tuned file.
+    /// Results of earlier searches were scored with the old weights and are forgotten.
+    pub fn set_eval_params(&mut self, params: Params) {
+        self.evaluator.set_params(params);
+        self.tt.clear();
+    }
+
+    /// Use a transposition table of about the given number of megabytes, forgetting what is in the current one.
+    pub fn set_hash_mb(&mut self, megabytes: usize) {
+        self.tt.resize(megabytes);
+    }
+
+    /// Forget the results of earlier searches, as when starting a new game.
+    pub fn clear(&mut self) {
+        self.tt.clear();
+        self.ordering.clear();
+    }
+
+    /// A flag that stops the search as soon as it is set, for stopping it from another thread.
+    /// The flag is left set after the search stops, so clear it before starting the next one.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.stop)
+    }
+
+    /// Search the position until the limits are reached, calling `report` after each completed iteration.
+    /// `history` holds the [Board::hash] of every earlier position in the game, as for [Board::outcome].
+    ///
+    /// Returns the report of the last completed iteration. If not even the first iteration completes,
+    /// the report has depth 0 and the first legal move. There is no move if the game is over.
+    pub fn search(
+        &mut self,
+        board: &Board,
+        history: &[u64],
+        limits: &Limits,
+        mut report: impl FnMut(&Report),
+    ) -> Report {
+        self.start = Instant::now();
+        self.stopped = false;
+        self.nodes = 0;
+        self.node_limit = limits.nodes;
+        let budget = limits.time_budget();
+        self.deadline = budget.map(|(_, hard)| self.start + hard);
+        self.keys = history.to_vec();
+        self.tt.new_search();
+        self.ordering.new_search();
+        self.cutoffs = CutoffStats::default();
+
+        let moves = board.generate_moves();
+        let mut best = Report {
+            depth: 0,
+            value: self.evaluator.evaluate(board),
+            nodes: 0,
+            time: Duration::ZERO,
+            pv: moves.first().copied().into_iter().collect(),
+            hashfull: 0,
+            cutoffs: CutoffStats::default(),
+        };
+
+        let max_depth = limits.depth.unwrap_or(MAX_PLY - 1).clamp(1, MAX_PLY - 1);
+        for depth in 1..=max_depth {
+            let value = self.aspiration_search(board, depth, best.value);
+            if self.stopped {
+                break;
+            }
+            best = Report {
+                depth,
+                value,
+                nodes: self.nodes,
+                time: self.start.elapsed(),
+                pv: self.principal_variation(board, depth),
+                hashfull: self.tt.hashfull(),
+                cutoffs: self.cutoffs,
+            };
+            report(&best);
+
+            // A mate within the searched depth is the shortest there is, so searching deeper won't change the move.
+            if value.abs() >= MATE - depth as i32 {
+                break;
+            }
+            if let Some((soft, _)) = budget
+                && (self.start.elapsed() >= soft || moves.len() == 1)
+            {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Search the root `depth` plies deep, expecting the score to be close to the score of the last iteration.
+    /// A narrow window around it cuts off more, and is widened until the score falls inside it.
+    fn aspiration_search(&mut self, board: &Board, depth: usize, previous: i32) -> i32 {
+        let mut delta = ASPIRATION_WINDOW;
+        let (mut alpha, mut beta) = match self.options.aspiration_windows
+            && depth >= ASPIRATION_MIN_DEPTH
+            && !is_mate(previous)
+        {
+            true => (previous - delta, previous + delta),
+            false => (-INFINITY, INFINITY),
+        };
+        loop {
+            let value = self.negamax(board, depth, 0, alpha, beta);
+            if self.stopped {
+                return value;
+            }
+            if value <= alpha {
+                alpha = (alpha - delta).max(-INFINITY);
+            } else if value >= beta {
+                beta = (beta + delta).min(INFINITY);
+            } else {
+                return value;
+            }
+            delta *= 2;
+        }
+    }
+
+    /// Should the search stop now? Once it has, every node returns straight away.
+    fn should_stop(&mut self) -> bool {
+        if self.stopped {
+            return true;
+        }
+        if self.node_limit.is_some_and(|limit| self.nodes >= limit) {
+            self.stopped = true;
+        } else if self.nodes.is_multiple_of(CHECK_INTERVAL) {
+            self.stopped = self.stop.load(Ordering::Relaxed)
+                || self.deadline.is_some_and(|d| Instant::now() >= d);
+        }
+        self.stopped
+    }
+
+    /// Has the position occurred before, since the last capture or pawn move?
+    /// A single repetition counts as a draw, since whatever made the side repeat will make it repeat again.
+    fn is_repetition(&self, board: &Board) -> bool {
+        self.keys
+            .iter()
+            .rev()
+            .take(board.half_move_clock() as usize)
+            .any(|&key| key == board.hash())
+    }
+
+    /// The score of the position for the side to move, searched `depth` plies deep.
+    /// Scores at or below `alpha` and at or above `beta` are bounds rather than exact scores.
+    fn negamax(
+        &mut self,
+        board: &Board,
+        mut depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> i32 {
+        self.pv_len[ply] = 0;
+        if self.should_stop() {
+            return 0;
+        }
+
+        if ply > 0 {
+            if board.half_move_clock() >= 100
+                || board.is_insufficient_material()
+                || self.is_repetition(board)
+            {
+                return DRAW;
+            }
+            // No line from here can score better than mating right away, or worse than being mated right away.
+            alpha = alpha.max(-MATE + ply as i32);
+            beta = beta.min(MATE - ply as i32 - 1);
+            if alpha >= beta {
+                return alpha;
+            }
+        }
+        let in_check = board.is_in_check();
+        // Look one ply further when in check, so that the search doesn't stop short of a mate or a forced loss.
+        if self.options.check_extensions && in_check {
+            depth += 1;
+        }
+        if depth == 0 || ply >= MAX_PLY - 1 {
+            return self.quiescence(board, ply, alpha, beta);
+        }
+        self.nodes += 1;
+
+        // Nodes searched with a null window only need to know if a move beats alpha,
+        // while nodes on the principal variation need exact scores.
+        let pv_node = beta - alpha > 1;
+        let entry = self.tt.probe(board.hash(), ply);
+        if !pv_node
+            && let Some(entry) = entry
+            && entry.depth >= depth
+            && entry.cuts_off(alpha, beta)
+        {
+            return entry.value;
+        }
+
+        let static_eval = match in_check {
+            true => -INFINITY,
+            false => self.evaluator.evaluate(board),
+        };
+        let previous = ply.checked_sub(1).and_then(|p| self.played[p]);
+        if !pv_node && !in_check {
+            // Reverse futility pruning: near the leaves, a position far enough above beta will stay above it.
+            if self.options.reverse_futility
+                && depth <= REVERSE_FUTILITY_DEPTH
+                && static_eval - REVERSE_FUTILITY_MARGIN * depth as i32 >= beta
+            {
+                return static_eval;
+            }
+
+            // Null move pruning: if passing the turn still leaves the position above beta after a reduced search,
+            // a real move would almost certainly do so too. Passing is never better than moving except in zugzwang,
+            // which happens with only pawns left, so don't pass then or twice in a row.
+            if self.options.null_move
+                && depth >= NULL_MOVE_MIN_DEPTH
+                && static_eval >= beta
+                && previous.is_some()
+                && has_pieces(board)
+            {
+                let reduction = (NULL_MOVE_REDUCTION + depth / 6).min(depth - 1);
+                let mut child = *board;
+                child.make_null();
+                self.played[ply] = None;
+                self.keys.push(board.hash());
+                let value = -self.negamax(&child, depth - 1 - reduction, ply + 1, -beta, -beta + 1);
+                self.keys.pop();
+                if self.stopped {
+                    return 0;
+                }
+                if value >= beta {
+                    // Mates found after passing aren't proven, as the side passed.
+                    return if is_mate(value) { beta } else { value };
+                }
+            }
+        }
+        // Futility pruning: near the leaves, quiet moves can't lift a position far enough below alpha above it.
+        let futile = self.options.futility
+            && !pv_node
+            && !in_check
+            && depth <= FUTILITY_DEPTH
+            && static_eval + FUTILITY_MARGIN * depth as i32 <= alpha;
+
+        let mut picker = MovePicker::new(
+            board,
+            entry.and_then(|e| e.r#move),
+            self.ordering.killers(ply),
+        )
+        .with_countermove(self.ordering.countermove(board, previous));
+
+        let original_alpha = alpha;
+        let mut best = -INFINITY;
+        let mut best_move = None;
+        let mut legal_moves = 0;
+        // Quiet moves that were searched without causing a cutoff, which lose history score if another move does.
+        let mut quiets_tried = MoveList::new();
+        self.keys.push(board.hash());
+        while let Some(m) = picker.pick(&self.ordering) {
+            legal_moves += 1;
+            let mut child = *board;
+            child.make(m);
+            let gives_check = child.is_in_check();
+            let quiet = !m.is_tactical() && !gives_check;
+            if futile && quiet && legal_moves > 1 {
+                // Count the move as searched, as its score can't be better than alpha.
+                best = best.max(static_eval);
+                continue;
+            }
+
+            // Late move reductions: with good move ordering, quiet moves late in the list rarely raise alpha,
+            // so search them less deep first and only search them fully if they do.
+            let reduction = match self.options.late_move_reductions
+                && quiet
+                && !in_check
+                && depth >= LMR_MIN_DEPTH
+                && legal_moves > LMR_MIN_MOVES
+            {
+                true => {
+                    let reduction = self.reductions[depth.min(63)][legal_moves.min(63)] as usize;
+                    // Reduce moves on the principal variation less.
+                    reduction.saturating_sub(pv_node as usize).min(depth - 2)
+                }
+                false => 0,
+            };
+
+            self.played[ply] = Some(m);
+            let new_depth = depth - 1;
+            let mut value = 0;
+            let mut full_search = true;
+            if legal_moves > 1 {
+                // Principal variation search: after the first move, only check whether a move beats alpha,
+                // and search it again with the full window if it does.
+                let null_beta = match self.options.pvs {
+                    true => alpha + 1,
+                    false => beta,
+                };
+                if reduction > 0 {
+                    value =
+                        -self.negamax(&child, new_depth - reduction, ply + 1, -null_beta, -alpha);
+                    full_search = value > alpha;
+                }
+                if full_search && self.options.pvs {
+                    value = -self.negamax(&child, new_depth, ply + 1, -null_beta, -alpha);
+                    full_search = value > alpha && value < beta;
+                }
+            }
+            if full_search {
+                value = -self.negamax(&child, new_depth, ply + 1, -beta, -alpha);
+            }
+            if self.stopped {
+                break;
+            }
+
+            if value > best {
+                best = value;
+                best_move = Some(m);
+                if value > alpha {
+                    alpha = value;
+                    self.update_pv(ply, m);
+                    if alpha >= beta {
+                        self.cutoffs.cutoffs += 1;
+                        self.cutoffs.first_move_cutoffs += (legal_moves == 1) as u64;
+                        if !m.is_tactical() {
+                            self.ordering
+                                .update(board, ply, previous, m, &quiets_tried, depth);
+                        }
+                        break;
+                    }
+                }
+            }
+            if !m.is_tactical() {
+                quiets_tried.push(m);
+            }
+        }
+        self.keys.pop();
+
+        if self.stopped {
+            return 0;
+        }
+        if legal_moves == 0 {
+            best = match in_check {
+                true => -MATE + ply as i32,
+                false => DRAW,
+            };
+        }
+
+        let bound = if best >= beta {
+            Bound::Lower
+        } else if best > original_alpha {
+            Bound::Exact
+        } else {
+            Bound::Upper
+        };
+        // When every move failed low, the best of them is no better a guess than any other.
+        let best_move = best_move.filter(|_| bound != Bound::Upper);
+        self.tt
+            .store(board.hash(), ply, depth, best, bound, best_move);
+        best
+    }
+
+    /// The principal variation of the root after searching `depth` plies.
+    /// Lines cut short by results from the transposition table are continued with the moves stored there.
+    fn principal_variation(&self, board: &Board, depth: usize) -> Vec<Move> {
+        let mut pv = self.pv[0][..self.pv_len[0]].to_vec();
+        let mut position = *board;
+        let mut seen = vec![position.hash()];
+        for &m in &pv {
+            position.make(m);
+            seen.push(position.hash());
+        }
+        while pv.len() < depth {
+            let Some(m) = self
+                .tt
+                .probe(position.hash(), pv.len())
+                .and_then(|e| e.r#move)
+                .filter(|&m| position.is_legal(m))
+            else {
+                break;
+            };
+            position.make(m);
+            // Stop at repetitions, where the stored moves could go around in circles.
+            if seen.contains(&position.hash()) {
+                break;
+            }
+            seen.push(position.hash());
+            pv.push(m);
+        }
+        pv
+    }
+
+    /// Search captures until the position is quiet, so that the evaluation isn't taken in the middle of an exchange.
+    /// The side to move may stand pat on the static evaluation instead of capturing, unless it is in check,
+    /// in which case every move is searched.
+    fn quiescence(&mut self, board: &Board, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.pv_len[ply] = 0;
+        if self.should_stop() {
+            return 0;
+        }
+        self.nodes += 1;
+        if ply >= MAX_PLY - 1 {
+            return self.evaluator.evaluate(board);
+        }
+
+        let in_check = board.is_in_check();
+        let stand_pat = self.evaluator.evaluate(board);
+        let mut best = match in_check {
+            true => -MATE + ply as i32,
+            false => stand_pat,
+        };
+        if best >= beta {
+            return best;
+        }
+        alpha = alpha.max(best);
+
+        let moves = match in_check {
+            true => MovePicker::new(board, None, [None; 2]),
+            false => MovePicker::captures(board),
+        };
+        for m in moves {
+            // Delta pruning: skip captures that can't raise alpha even if the piece is won for free.
+            if !in_check && stand_pat + captured_value(board, m) + DELTA_MARGIN <= alpha {
+                continue;
+            }
+            let mut child = *board;
+            child.make(m);
+            let value = -self.quiescence(&child, ply + 1, -beta, -alpha);
+            if self.stopped {
+                return 0;
+            }
+            if value > best {
+                best = value;
+                if value > alpha {
+                    alpha = value;
+                    self.update_pv(ply, m);
+                    if alpha >= beta {
+                        break;
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    /// Make the move followed by the principal variation of the next ply the principal variation of this ply.
+    fn update_pv(&mut self, ply: usize, r#move: Move) {
+        let child_len = self.pv_len[ply + 1];
+        let (head, tail) = self.pv.split_at_mut(ply + 1);
+        head[ply][ply] = r#move;
+        head[ply][ply + 1..ply + 1 + child_len]
+            .copy_from_slice(&tail[0][ply + 1..ply + 1 + child_len]);
+        self.pv_len[ply] = child_len + 1;
+    }
+}
+
+/// Does the side to move have pieces other than pawns and the king?
+/// Without them, zugzwang is common and passing can't be trusted to be worse than moving.
+fn has_pieces(board: &Board) -> bool {
+    let (pawn, king) = match board.white_to_move {
+        true => (Piece::PawnWhite, Piece::KingWhite),
+        false => (Piece::PawnBlack, Piece::KingBlack),
+    };
+    let pieces = board.side(board.white_to_move) & !(board.pieces(pawn) | board.pieces(king));
+    !pieces.is_empty()
+}
+
+/// The material a capture or promotion wins, if it isn't recaptured.
+fn captured_value(board: &Board, r#move: Move) -> i32 {
+    let victim = match r#move.is_en_pessant() {
+        true => 100,
+        false => board.at(r#move.to()).map_or(0, |p| p.value()),
+    };
+    victim + r#move.promote_to().map_or(0, |p| p.value() - 100)
+}
@@ -0,0 +1,129 @@
+//! Static exchange evaluation works out what a capture wins once every recapture on the square has been played,
+//! without playing any moves. It tells the search which captures are worth looking at.
+
+use super::Move;
+use super::Square;
+use super::bitboard::{
+    Bitboard, bishop_attacks, king_attacks, knight_attacks, pawn_attacks, rook_attacks,
+};
+use super::board::Board;
+use super::piece::Piece;
+
+/// A king can only take part in an exchange as the last piece to capture,
+/// so it is worth more than any exchange could win back.
+const KING_VALUE: i32 = 20_000;
+
+/// Pieces in the order they are sent into an exchange, least valuable first.
+const WHITE_ATTACKERS: [Piece; 6] = {
+    use Piece::*;
+    [
+        PawnWhite,
+        KnightWhite,
+        BishopWhite,
+        RookWhite,
+        QueenWhite,
+        KingWhite,
+    ]
+};
+const BLACK_ATTACKERS: [Piece; 6] = {
+    use Piece::*;
+    [
+        PawnBlack,
+        KnightBlack,
+        BishopBlack,
+        RookBlack,
+        QueenBlack,
+        KingBlack,
+    ]
+};
+
+fn exchange_value(piece: Piece) -> i32 {
+    match piece.value() {
+        0 => KING_VALUE,
+        value => value,
+    }
+}
+
+impl Board {
+    /// The material in centipawns the side to move wins with a move, when both sides go on capturing on the square
+    /// it moves to with their least valuable piece for as long as that pays off.
+    /// Quiet moves score 0, or less if the moved piece can be won.
+    ///
+    /// Sliding pieces behind the pieces that capture join the exchange as the squares in front of them are vacated.
+    /// Pins, checks and promotions during the exchange are not considered, and castling always scores 0.
+    pub fn see(&self, r#move: Move) -> i32 {
+        if r#move.is_castling() {
+            return 0;
+        }
+        let from = r#move.from();
+        let to = r#move.to();
+        let mover = self.at(from).expect("move to start on a piece");
+        let mut occupied = self.occupied() ^ Bitboard::from_square(from);
+
+        // What the side making each capture has won so far, if the exchange stopped after that capture.
+        let mut gains = [0; 33];
+        gains[0] = match r#move.is_en_pessant() {
+            true => {
+                occupied ^= Bitboard::from_square(from / 8 * 8 + to % 8);
+                100
+            }
+            false => self.at(to).map_or(0, exchange_value),
+        };
+        // The value of the piece on the square, which the next capture wins.
+        let mut on_square = exchange_value(mover);
+        if let Some(promoted) = r#move.promote_to() {
+            gains[0] += promoted.value() - 100;
+            on_square = promoted.value();
+        }
+
+        let mut white = !mover.is_white();
+        let mut captures = 0;
+        while let Some((piece, square)) = self.least_valuable_attacker(to, white, occupied) {
+            captures += 1;
+            gains[captures] = on_square - gains[captures - 1];
+            // Neither capturing nor standing pat can come out ahead of what the side already has.
+            if (-gains[captures - 1]).max(gains[captures]) < 0 {
+                break;
+            }
+            on_square = exchange_value(piece);
+            occupied ^= Bitboard::from_square(square);
+            white = !white;
+        }
+
+        // Going back from the last capture, each side only captures if it gains more than stopping.
+        while captures > 0 {
+            gains[captures - 1] = -(-gains[captures - 1]).max(gains[captures]);
+            captures -= 1;
+        }
+        gains[0]
+    }
+
+    /// The cheapest piece of the given color attacking a square, with the board occupied by `occupied`.
+    /// Pieces no longer in `occupied` have already been traded off.
+    fn least_valuable_attacker(
+        &self,
+        square: Square,
+        white: bool,
+        occupied: Bitboard,
+    ) -> Option<(Piece, Square)> {
+        use Piece::*;
+        let pieces = match white {
+            true => WHITE_ATTACKERS,
+            false => BLACK_ATTACKERS,
+        };
+        let diagonal = bishop_attacks(square, occupied);
+        let straight = rook_attacks(square, occupied);
+        pieces.into_iter().find_map(|piece| {
+            let reach = match piece {
+                PawnWhite | PawnBlack => pawn_attacks(square, !white),
+                KnightWhite | KnightBlack => knight_attacks(square),
+                BishopWhite | BishopBlack => diagonal,
+                RookWhite | RookBlack => straight,
+                QueenWhite | QueenBlack => diagonal | straight,
+                KingWhite | KingBlack => king_attacks(square),
+            };
+            let square = (reach & self.pieces(piece) & occupied).first()?;
+            Some((piece, square))
+        })
+    }
+}
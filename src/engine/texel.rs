@@ -0,0 +1,325 @@
+//! Texel tuning: fitting the weights of the evaluation to the results of real games.
+//!
+//! Every sample is a quiet position labeled with the result of the game it came from.
+//! The evaluation of a position is mapped to an expected result by a sigmoid,
+//! and the weights are moved towards the ones that predict the actual results with the smallest mean squared error.
+//!
+//! The evaluation adds up its weights times how often each of them counts in the position,
+//! so a position is reduced to those counts once when it is loaded, and never has to be evaluated again.
+
+use super::board::{Board, FenError, PositionError};
+use super::eval::params::{PARAM_COUNT, Params, taper};
+use super::eval::{Evaluation, Evaluator, MAX_PHASE};
+use std::error::Error;
+use std::fmt::Display;
+
+const ADAM_BETA1: f64 = 0.9;
+const ADAM_BETA2: f64 = 0.999;
+const ADAM_EPSILON: f64 = 1e-8;
+
+/// The expected result for white of a position evaluated at `score` centipawns for white,
+/// where `scaling` sets how sure a score makes the result.
+pub fn sigmoid(score: f64, scaling: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-scaling * score / 400.0))
+}
+
+/// A position reduced to what its evaluation depends on, along with the result of its game.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Sample {
+    /// Each parameter that counts in the position, with how many times it counts for white minus for black.
+    coefficients: Vec<(u16, i16)>,
+    phase: i32,
+    /// The result of the game for white, from 0 for a loss to 1 for a win.
+    pub result: f64,
+}
+
+impl Sample {
+    /// A sample from the [Evaluator::trace] of a position.
+    pub fn new(evaluation: &Evaluation, result: f64) -> Self {
+        Self {
+            coefficients: evaluation
+                .coefficients
+                .iter()
+                .enumerate()
+                .filter(|(_, count)| **count != 0)
+                .map(|(index, &count)| (index as u16, count as i16))
+                .collect(),
+            phase: evaluation.phase,
+            result,
+        }
+    }
+
+    /// The evaluation for white with the given middlegame and endgame weights for each parameter.
+    /// This is what [Evaluator::evaluate] returns for white, before rounding.
+    pub fn evaluate(&self, weights: &[[f64; 2]]) -> f64 {
+        let (mg, eg) = self
+            .coefficients
+            .iter()
+            .fold((0.0, 0.0), |(mg, eg), &(index, count)| {
+                let [weight_mg, weight_eg] = weights[index as usize];
+                (mg + weight_mg * count as f64, eg + weight_eg * count as f64)
+            });
+        (mg * self.phase as f64 + eg * (MAX_PHASE - self.phase) as f64) / MAX_PHASE as f64
+    }
+}
+
+/// Reasons samples could not be read by [parse_samples]. Lines are counted from 1.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum SampleError {
+    /// The position on this line could not be read.
+    Fen(usize, FenError),
+    /// The position on this line could not occur in a game.
+    Position(usize, PositionError),
+    /// The line did not end with a result or score that could be read.
+    Label(usize, String),
+}
+
+impl Display for SampleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SampleError::Fen(line, error) => write!(f, "line {line}: {error}"),
+            SampleError::Position(line, error) => write!(f, "line {line}: {error}"),
+            SampleError::Label(line, label) => {
+                write!(f, "line {line}: invalid result or score '{label}'")
+            }
+        }
+    }
+}
+
+impl Error for SampleError {}
+
+/// What the label at the end of each line of samples holds, see [parse_samples].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Labels {
+    /// The result of the game the position came from.
+    Results,
+    /// The score of the position in centipawns for white, like from searching it.
+    Scores,
+}
+
+/// Read samples, one per line, as a FEN followed by a label for white. With [Labels::Results] the label is either
+/// - the result of the game as '1-0', '0-1' or '1/2-1/2', or
+/// - the result as a number from 0 to 1, like '1', '0' or '0.5'.
+///
+/// With [Labels::Scores] the label is a score in centipawns as a whole number,
+/// which is turned into a result with a scaling of 1.
+///
+/// Quotes, brackets and semicolons around the label are ignored, as is a 'c9' before it,
+/// so EPD files with results like 'c9 "1-0";' can be read as they are. Empty lines are skipped.
+/// Positions that fail [Board::validate] are rejected,
+/// and they should be quiet, as the evaluation doesn't look at captures that are about to happen.
+pub fn parse_samples(text: &str, labels: Labels) -> Result<Vec<Sample>, SampleError> {
+    let evaluator = Evaluator::new();
+    let mut samples = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let (fen, label) = line.rsplit_once(char::is_whitespace).unwrap_or(("", line));
+        let fen = fen.trim_end();
+        let fen = fen.strip_suffix(" c9").unwrap_or(fen);
+        let result =
+            parse_label(label, labels).ok_or(SampleError::Label(number + 1, label.to_owned()))?;
+        let board = Board::from_fen(fen).map_err(|e| SampleError::Fen(number + 1, e))?;
+        board
+            .validate()
+            .map_err(|e| SampleError::Position(number + 1, e))?;
+        samples.push(Sample::new(&evaluator.trace(&board), result));
+    }
+    Ok(samples)
+}
+
+fn parse_label(label: &str, labels: Labels) -> Option<f64> {
+    let label = label.trim_matches(|c| matches!(c, '"' | '[' | ']' | ';'));
+    match labels {
+        Labels::Results => match label {
+            "1-0" => Some(1.0),
+            "0-1" => Some(0.0),
+            "1/2-1/2" => Some(0.5),
+            result => result
+                .parse::<f64>()
+                .ok()
+                .filter(|result| (0.0..=1.0).contains(result)),
+        },
+        Labels::Scores => label.parse::<i32>().ok().map(|cp| sigmoid(cp as f64, 1.0)),
+    }
+}
+
+/// Tunes evaluation parameters to a set of samples with the Adam flavour of gradient descent.
+#[derive(Clone, Copy, Debug)]
+pub struct Tuner<'a> {
+    samples: &'a [Sample],
+    scaling: f64,
+    learning_rate: f64,
+    threads: usize,
+}
+
+impl<'a> Tuner<'a> {
+    /// Tune to the given samples on a single thread, with a scaling of 1 and a learning rate of 1.
+    pub fn new(samples: &'a [Sample]) -> Self {
+        Self {
+            samples,
+            scaling: 1.0,
+            learning_rate: 1.0,
+            threads: 1,
+        }
+    }
+
+    /// Map evaluations to expected results with this scaling, see [sigmoid].
+    pub fn scaling(mut self, scaling: f64) -> Self {
+        self.scaling = scaling;
+        self
+    }
+
+    /// Move each parameter by about this many centipawns in each step.
+    pub fn learning_rate(mut self, learning_rate: f64) -> Self {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Go through the samples on the given number of threads, with at least one.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// The mean squared difference between the results of the samples and the results expected from evaluating them.
+    pub fn error(&self, params: &Params) -> f64 {
+        self.error_and_gradient(&weights(params)).0
+    }
+
+    /// Find the scaling that makes the evaluation with the given parameters predict the results best, and use it.
+    /// Tuning with a fitted scaling changes the parameters as little as needed, instead of making them all larger or smaller.
+    pub fn fit_scaling(&mut self, params: &Params) -> f64 {
+        let weights = weights(params);
+        let mut best = self.error_and_gradient(&weights).0;
+        for step in [0.1, 0.01, 0.001] {
+            loop {
+                let error_at = |scaling: f64| {
+                    let tuner = Tuner { scaling, ..*self };
+                    (tuner.error_and_gradient(&weights).0, scaling)
+                };
+                let (error, scaling) = [self.scaling - step, self.scaling + step]
+                    .into_iter()
+                    .filter(|&scaling| scaling > 0.0)
+                    .map(error_at)
+                    .min_by(|a, b| a.0.total_cmp(&b.0))
+                    .expect("a scaling to try");
+                if error >= best {
+                    break;
+                }
+                best = error;
+                self.scaling = scaling;
+            }
+        }
+        self.scaling
+    }
+
+    /// Tune every parameter, starting from `params`, for the given number of passes over the samples.
+    /// After each pass, `report` is called with the number of the pass and the error before it.
+    pub fn tune(
+        &self,
+        params: &Params,
+        epochs: usize,
+        mut report: impl FnMut(usize, f64),
+    ) -> Params {
+        let mut weights = weights(params);
+        let mut momentum = vec![[0.0; 2]; PARAM_COUNT];
+        let mut velocity = vec![[0.0; 2]; PARAM_COUNT];
+        for epoch in 1..=epochs {
+            let (error, gradient) = self.error_and_gradient(&weights);
+            // Adam keeps a running average of each gradient and its square,
+            // so each parameter moves at about the learning rate in the direction it has been moving.
+            let beta1_correction = 1.0 - ADAM_BETA1.powi(epoch as i32);
+            let beta2_correction = 1.0 - ADAM_BETA2.powi(epoch as i32);
+            for i in 0..PARAM_COUNT {
+                for phase in 0..2 {
+                    let g = gradient[i][phase];
+                    momentum[i][phase] = ADAM_BETA1 * momentum[i][phase] + (1.0 - ADAM_BETA1) * g;
+                    velocity[i][phase] =
+                        ADAM_BETA2 * velocity[i][phase] + (1.0 - ADAM_BETA2) * g * g;
+                    let m = momentum[i][phase] / beta1_correction;
+                    let v = velocity[i][phase] / beta2_correction;
+                    weights[i][phase] -= self.learning_rate * m / (v.sqrt() + ADAM_EPSILON);
+                }
+            }
+            report(epoch, error);
+        }
+
+        let mut tuned = params.clone();
+        for (value, [mg, eg]) in tuned.values_mut().iter_mut().zip(weights) {
+            *value = taper(mg.round() as i32, eg.round() as i32);
+        }
+        tuned
+    }
+
+    /// The error of the samples evaluated with `weights`, and how fast it changes with each weight.
+    fn error_and_gradient(&self, weights: &[[f64; 2]]) -> (f64, Vec<[f64; 2]>) {
+        let chunk_size = self.samples.len().div_ceil(self.threads).max(1);
+        let (error, gradient) = std::thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .samples
+                .chunks(chunk_size)
+                .map(|chunk| scope.spawn(move || self.chunk_error_and_gradient(chunk, weights)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("tuning thread not to panic"))
+                .fold(
+                    (0.0, vec![[0.0; 2]; PARAM_COUNT]),
+                    |(error, mut gradient), (chunk_error, chunk_gradient)| {
+                        for (total, chunk) in gradient.iter_mut().zip(chunk_gradient) {
+                            total[0] += chunk[0];
+                            total[1] += chunk[1];
+                        }
+                        (error + chunk_error, gradient)
+                    },
+                )
+        });
+        let count = self.samples.len().max(1) as f64;
+        let gradient = gradient
+            .into_iter()
+            .map(|[mg, eg]| [mg / count, eg / count])
+            .collect();
+        (error / count, gradient)
+    }
+
+    /// The summed error and gradient of some of the samples.
+    fn chunk_error_and_gradient(
+        &self,
+        samples: &[Sample],
+        weights: &[[f64; 2]],
+    ) -> (f64, Vec<[f64; 2]>) {
+        let mut error = 0.0;
+        let mut gradient = vec![[0.0; 2]; PARAM_COUNT];
+        // The sigmoid is 1 / (1 + e^(-k * score)), with k scaled so that the base is 10 and the unit 400 centipawns.
+        let k = self.scaling * std::f64::consts::LN_10 / 400.0;
+        for sample in samples {
+            let expected = sigmoid(sample.evaluate(weights), self.scaling);
+            let difference = expected - sample.result;
+            error += difference * difference;
+
+            // The derivative of the squared error by the score, which each weight adds to by its coefficient,
+            // split between the middlegame and endgame values by the phase.
+            let slope = 2.0 * difference * expected * (1.0 - expected) * k;
+            let mg = slope * sample.phase as f64 / MAX_PHASE as f64;
+            let eg = slope * (MAX_PHASE - sample.phase) as f64 / MAX_PHASE as f64;
+            for &(index, count) in &sample.coefficients {
+                let weight = &mut gradient[index as usize];
+                weight[0] += mg * count as f64;
+                weight[1] += eg * count as f64;
+            }
+        }
+        (error, gradient)
+    }
+}
+
+/// The parameters as numbers that can be moved by less than a centipawn at a time.
+fn weights(params: &Params) -> Vec<[f64; 2]> {
+    params
+        .values()
+        .iter()
+        .map(|value| [value.mg as f64, value.eg as f64])
+        .collect()
+}
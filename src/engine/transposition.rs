@@ -0,0 +1,220 @@
+//! Remembers the results of searching positions by their [Board::hash](super::board::Board::hash),
+//! so that a position reached again, by another move order or in a later iteration, isn't searched from scratch.
+
+use super::Move;
+use super::search::{MATE, MAX_PLY};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Entries are grouped in buckets that fill a cache line, and a position can be stored in any entry of its bucket.
+const BUCKET_SIZE: usize = 4;
+
+/// Searches are numbered in six bits, after which the numbers wrap around.
+const AGES: u8 = 64;
+
+/// How a stored score relates to the true score of the position.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Bound {
+    /// The score is exact.
+    Exact,
+    /// The true score is at least this, as a move caused a beta cutoff.
+    Lower,
+    /// The true score is at most this, as no move raised alpha.
+    Upper,
+}
+
+/// The stored result of searching a position.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Entry {
+    /// The best move found, or the move that caused a cutoff.
+    pub r#move: Option<Move>,
+    /// The score for the side to move, with mates counted from the ply the entry was probed at.
+    pub value: i32,
+    pub depth: usize,
+    pub bound: Bound,
+}
+
+impl Entry {
+    /// Is the score good enough to be used instead of searching with the window `alpha` to `beta`?
+    pub fn cuts_off(&self, alpha: i32, beta: i32) -> bool {
+        match self.bound {
+            Bound::Exact => true,
+            Bound::Lower => self.value >= beta,
+            Bound::Upper => self.value <= alpha,
+        }
+    }
+}
+
+/// A fixed size table of search results shared between threads without locking.
+/// Like the perft cache, each entry stores its data next to the hash XOR'ed with the data,
+/// so an entry torn by two threads writing at once reads as empty instead of as another position's result.
+///
+/// The data packs the move in bits 0 to 15, the score in 16 to 31, the depth in 32 to 39,
+/// the bound in 40 to 41 and the age of the search that stored it in 42 to 47.
+/// A zero data word is an empty entry.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    buckets: Vec<[[AtomicU64; 2]; BUCKET_SIZE]>,
+    age: u8,
+}
+
+impl TranspositionTable {
+    /// A table taking up about the given number of megabytes, and at least one bucket.
+    pub fn new(megabytes: usize) -> Self {
+        let bytes = megabytes * 1024 * 1024;
+        let len = (bytes / size_of::<[[AtomicU64; 2]; BUCKET_SIZE]>()).max(1);
+        // Round down to a power of two, so the bucket can be picked by masking the hash.
+        let len = match len.is_power_of_two() {
+            true => len,
+            false => len.next_power_of_two() / 2,
+        };
+        Self {
+            buckets: (0..len)
+                .map(|_| std::array::from_fn(|_| [AtomicU64::new(0), AtomicU64::new(0)]))
+                .collect(),
+            age: 0,
+        }
+    }
+
+    /// Replace the table with an empty one of about the given number of megabytes.
+    pub fn resize(&mut self, megabytes: usize) {
+        *self = Self::new(megabytes);
+    }
+
+    /// Forget every stored result, as when starting a new game.
+    pub fn clear(&mut self) {
+        for entry in self.buckets.iter_mut().flatten().flatten() {
+            *entry.get_mut() = 0;
+        }
+        self.age = 0;
+    }
+
+    /// Mark the start of a new search. Results of earlier searches are replaced before results of this one.
+    pub fn new_search(&mut self) {
+        self.age = (self.age + 1) % AGES;
+    }
+
+    fn bucket(&self, hash: u64) -> &[[AtomicU64; 2]; BUCKET_SIZE] {
+        &self.buckets[hash as usize & (self.buckets.len() - 1)]
+    }
+
+    /// The data stored for the position in an entry, if any.
+    fn read(entry: &[AtomicU64; 2], hash: u64) -> Option<u64> {
+        let [check, data] = entry;
+        let data = data.load(Ordering::Relaxed);
+        let check = check.load(Ordering::Relaxed);
+        (data != 0 && check ^ data == hash).then_some(data)
+    }
+
+    /// Look up the stored result for a position probed `ply` plies from the root.
+    pub fn probe(&self, hash: u64, ply: usize) -> Option<Entry> {
+        let data = self
+            .bucket(hash)
+            .iter()
+            .find_map(|entry| Self::read(entry, hash))?;
+        let r#move = Move::from_bits(data as u16);
+        Some(Entry {
+            r#move: (r#move != Move::NULL).then_some(r#move),
+            value: value_from_table((data >> 16) as u16 as i16 as i32, ply),
+            depth: (data >> 32 & 0xff) as usize,
+            bound: match data >> 40 & 0b11 {
+                0 => Bound::Exact,
+                1 => Bound::Lower,
+                _ => Bound::Upper,
+            },
+        })
+    }
+
+    /// Store the result of searching a position `ply` plies from the root.
+    ///
+    /// Another result for the same position is kept if it comes from a deeper search in this search,
+    /// unless the new score is exact. Otherwise the entry of the bucket that is worth the least is replaced,
+    /// preferring results of earlier searches and then results of shallower searches.
+    pub fn store(
+        &self,
+        hash: u64,
+        ply: usize,
+        depth: usize,
+        value: i32,
+        bound: Bound,
+        r#move: Option<Move>,
+    ) {
+        let bucket = self.bucket(hash);
+        let existing = bucket
+            .iter()
+            .find_map(|entry| Self::read(entry, hash).map(|data| (entry, data)));
+        let mut r#move = r#move;
+        let entry = match existing {
+            Some((entry, data)) => {
+                let old_depth = (data >> 32 & 0xff) as usize;
+                let old_age = (data >> 42 & 0x3f) as u8;
+                if bound != Bound::Exact && old_age == self.age && old_depth > depth + 2 {
+                    return;
+                }
+                // Keep the move of the earlier result rather than forgetting that it was good.
+                if r#move.is_none() {
+                    r#move = Some(Move::from_bits(data as u16)).filter(|&m| m != Move::NULL);
+                }
+                entry
+            }
+            None => bucket
+                .iter()
+                .min_by_key(|entry| {
+                    let data = entry[1].load(Ordering::Relaxed);
+                    if data == 0 {
+                        return i32::MIN;
+                    }
+                    let depth = (data >> 32 & 0xff) as i32;
+                    let age = (data >> 42 & 0x3f) as u8;
+                    let searches_ago = (self.age + AGES - age) % AGES;
+                    depth - 8 * searches_ago as i32
+                })
+                .expect("buckets not to be empty"),
+        };
+
+        let bound = match bound {
+            Bound::Exact => 0,
+            Bound::Lower => 1,
+            Bound::Upper => 2,
+        };
+        let data = r#move.unwrap_or(Move::NULL).bits() as u64
+            | (value_to_table(value, ply) as i16 as u16 as u64) << 16
+            | (depth.min(0xff) as u64) << 32
+            | bound << 40
+            | (self.age as u64) << 42;
+        let [check, stored] = entry;
+        check.store(hash ^ data, Ordering::Relaxed);
+        stored.store(data, Ordering::Relaxed);
+    }
+
+    /// How full the table is with results of the current search, in permille as reported by UCI's 'hashfull'.
+    /// Counted from a sample of the first thousand entries.
+    pub fn hashfull(&self) -> u32 {
+        let sample = self.buckets.iter().flatten().take(1000);
+        let total = sample.clone().count() as u32;
+        let used = sample
+            .filter(|[_, data]| {
+                let data = data.load(Ordering::Relaxed);
+                data != 0 && (data >> 42 & 0x3f) as u8 == self.age
+            })
+            .count() as u32;
+        used * 1000 / total.max(1)
+    }
+}
+
+/// Mates are scored by their distance from the root, but are stored by their distance from the position,
+/// so the score holds wherever the position is reached.
+fn value_to_table(value: i32, ply: usize) -> i32 {
+    match value {
+        v if v >= MATE - MAX_PLY as i32 => v + ply as i32,
+        v if v <= -MATE + MAX_PLY as i32 => v - ply as i32,
+        v => v,
+    }
+}
+
+fn value_from_table(value: i32, ply: usize) -> i32 {
+    match value {
+        v if v >= MATE - MAX_PLY as i32 => v - ply as i32,
+        v if v <= -MATE + MAX_PLY as i32 => v + ply as i32,
+        v => v,
+    }
+}
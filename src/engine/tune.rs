@@ -0,0 +1,56 @@
+use std::time::Instant;
+
+use justok::eval::params::Params;
+use justok::texel::{Labels, Tuner, parse_samples};
+
+const USAGE: &str =
+    "usage: tune <results|scores> <samples> <output> [epochs] [learning rate] [start params]";
+
+/// Tune the evaluation to a file of positions labeled with results or scores, see [parse_samples] for the format,
+/// and write the tuned parameters to a file that the engine loads with its 'EvalFile' option.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let labels = match args.first().map(String::as_str) {
+        Some("results") => Some(Labels::Results),
+        Some("scores") => Some(Labels::Scores),
+        _ => None,
+    };
+    let (Some(labels), Some(samples), Some(output)) = (labels, args.get(1), args.get(2)) else {
+        eprintln!("{USAGE}");
+        std::process::exit(2);
+    };
+    let epochs = args.get(3).map_or(Ok(1000), |epochs| epochs.parse())?;
+    let learning_rate = args.get(4).map_or(Ok(1.0), |rate| rate.parse())?;
+    let params = match args.get(5) {
+        Some(path) => std::fs::read_to_string(path)?.parse()?,
+        None => Params::default(),
+    };
+
+    let start = Instant::now();
+    let samples = parse_samples(&std::fs::read_to_string(samples)?, labels)?;
+    println!(
+        "Loaded {} samples in {:.1}s",
+        samples.len(),
+        start.elapsed().as_secs_f64()
+    );
+
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut tuner = Tuner::new(&samples)
+        .learning_rate(learning_rate)
+        .threads(threads);
+    let scaling = tuner.fit_scaling(&params);
+    println!(
+        "Fitted scaling {scaling:.3} with error {:.6}",
+        tuner.error(&params)
+    );
+
+    let tuned = tuner.tune(&params, epochs, |epoch, error| {
+        if epoch % 50 == 0 || epoch == 1 {
+            println!("Epoch {epoch} error {error:.6}");
+        }
+    });
+    println!("Final error {:.6}", tuner.error(&tuned));
+    std::fs::write(output, tuned.to_string())?;
+    println!("Wrote tuned parameters to {output}");
+    Ok(())
+}
@@ -0,0 +1,36 @@
+//! Random keys for Zobrist hashing of positions.
+//! A position's hash is the XOR of the keys for every feature of the position,
+//! which lets a move update the hash by XOR'ing only the features that changed.
+
+/// Generates a table of pseudo random keys at compile time using SplitMix64.
+const fn random_keys<const N: usize>(seed: u64) -> [u64; N] {
+    let mut keys = [0; N];
+    let mut state = seed;
+    let mut i = 0;
+    while i < N {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+/// One key for each piece on each square, indexed by `piece as usize * 64 + square`.
+const PIECE_SQUARE: [u64; 14 * 64] = random_keys(0x6a75_7374_6f6b);
+
+/// Key toggled when it is black's turn to move.
+pub const BLACK_TO_MOVE: u64 = random_keys::<1>(0x7475_726e)[0];
+
+/// One key per castling right, in the order white king side, white queen side, black king side, black queen side.
+pub const CASTLING: [u64; 4] = random_keys(0x6361_7374_6c65);
+
+/// One key per file of the en pessant square.
+pub const EN_PESSANT_FILE: [u64; 8] = random_keys(0x7061_7373_616e);
+
+/// The key for a piece (given by its discriminant) standing on a square.
+pub fn piece_square(piece: usize, square: u8) -> u64 {
+    PIECE_SQUARE[piece * 64 + square as usize]
+}
@@ -37,8 +37,8 @@ fn on_select(
         material.base_color.set_alpha(1.0);
 
         // Also highlight squares the piece can move to.
-        let valid_moves = game_state.board.generate_square_moves(e.0);
-        let target_squares = valid_moves.iter().map(|m| m.to);
+        let valid_moves = game_state.game.board().generate_square_moves(e.0);
+        let target_squares = valid_moves.iter().map(|m| m.to());
         for s in target_squares {
             let material = square_highlights
                 .0
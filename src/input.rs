@@ -83,10 +83,10 @@ fn handle_click(
         }
 
         let target = r as u8 * 8 + c as u8;
-        let square_has_friendly_piece = game_state
-            .board
+        let board = game_state.game.board();
+        let square_has_friendly_piece = board
             .at(target)
-            .is_some_and(|p| p.is_white() == game_state.board.white_to_move);
+            .is_some_and(|p| p.is_white() == board.white_to_move);
 
         if let Some(selected) = selected_square.0 {
             if selected == target {
@@ -101,10 +101,7 @@ fn handle_click(
             }
 
             // Handle making a move.
-            let is_pawn_move = game_state
-                .board
-                .at(selected)
-                .is_some_and(|p| p == Piece::PawnWhite);
+            let is_pawn_move = board.at(selected).is_some_and(|p| p == Piece::PawnWhite);
             let is_moving_to_back_rank =
                 target > ((game_state.width * game_state.height - 1) - game_state.width) as u8;
 
@@ -113,14 +110,11 @@ fn handle_click(
             } else {
                 None
             };
-            let m = Move {
-                from: selected,
-                to: target,
-                promote_to,
-            };
-            let moves = game_state.board.generate_moves();
-            let is_valid = moves.iter().find(|&vm| *vm == m).is_some();
-            if is_valid {
+            let moves = board.generate_moves();
+            let valid_move = moves
+                .iter()
+                .find(|m| m.from() == selected && m.to() == target && m.promote_to() == promote_to);
+            if let Some(&m) = valid_move {
                 move_events.write(MoveMade(m));
             }
             deselect_events.write(DeselectSquare(selected));
@@ -1,14 +1,14 @@
 mod camera;
-mod engine;
 mod highlight;
 mod input;
 mod pieces;
 
 use bevy::{color::palettes::tailwind::*, pbr::DirectionalLightShadowMap, prelude::*};
 use camera::CameraControllerPlugin;
-use engine::board::Board;
+use engine::game::Game;
 use highlight::HighlightPlugin;
 use input::{InputPlugin, MoveMade};
+use justok as engine;
 use pieces::PiecePlugin;
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
@@ -16,7 +16,7 @@ use std::f32::consts::FRAC_PI_3;
 
 #[derive(Resource)]
 pub struct GameState {
-    pub board: Board,
+    pub game: Game,
     pub width: usize,
     pub height: usize,
 }
@@ -27,11 +27,11 @@ pub struct Rand(pub ChaCha8Rng);
 impl Default for GameState {
     fn default() -> Self {
         // "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1"
-        return Self {
-            board: Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"),
+        Self {
+            game: Game::default(),
             width: 8,
             height: 8,
-        };
+        }
     }
 }
 
@@ -57,13 +57,18 @@ fn play_as_black(
     mut events: EventWriter<MoveMade>,
     mut rng: ResMut<Rand>,
 ) {
-    if game_state.board.white_to_move {
+    if game_state.game.board().white_to_move {
         // Bot is black so do nothing.
         return;
     }
 
-    let moves = game_state.board.generate_moves();
-    if moves.len() > 0 {
+    // Stop playing once the game is over.
+    if game_state.game.outcome().is_some() {
+        return;
+    }
+
+    let moves = game_state.game.board().generate_moves();
+    if !moves.is_empty() {
         let idx = rng.0.random_range(0..moves.len());
         events.write(MoveMade(moves[idx]));
     }
@@ -101,7 +101,7 @@ fn setup(
     for r in 0..game_state.width {
         for c in 0..game_state.height {
             let square = (r * 8 + c) as u8;
-            if let Some(piece) = game_state.board.at(square) {
+            if let Some(piece) = game_state.game.board().at(square) {
                 spawn_piece(
                     &mut commands,
                     &game_state,
@@ -165,10 +165,11 @@ fn apply_moves_to_game_state(
     handles: Res<PieceHandles>,
 ) {
     for m in moves.read() {
-        let move_info = game_state.board.apply(m.0);
-        info!("{:?}", move_info);
+        let san = m.0.to_san(game_state.game.board());
+        let move_info = game_state.game.push(m.0).clone();
+        info!("{san} {:?}", move_info);
 
-        for removed in move_info.removed_pieces {
+        for (removed, _) in move_info.removed_pieces {
             // Remove the entity.
             let mut commands = pieces
                 .0
@@ -0,0 +1,70 @@
+use justok::{board::Board, square_from_algebraic};
+
+fn square(algebraic: &str) -> u8 {
+    square_from_algebraic(algebraic).unwrap()
+}
+
+#[test]
+fn attackers_of() {
+    // Kiwipete, where the center is contested by several pieces of both colors.
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+
+    let white: Vec<_> = board.attackers_of(square("e6"), true).collect();
+    assert_eq!(white, vec![square("d5")]);
+
+    let black: Vec<_> = board.attackers_of(square("d5"), false).collect();
+    assert_eq!(black, vec![square("b6"), square("e6"), square("f6")]);
+
+    // Sliding pieces are blocked by pieces in the way.
+    assert!(!board.is_square_attacked(square("h2"), false));
+    assert!(board.is_square_attacked(square("g2"), false));
+    assert!(!board.is_square_attacked(square("e1"), false));
+}
+
+#[test]
+fn attack_map() {
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+
+    let white = board.attack_map(true);
+    let black = board.attack_map(false);
+    // Every square on the third rank is attacked by pawns, and some by knights as well.
+    assert_eq!(white.count(square("a3")), 2);
+    assert_eq!(white.count(square("c3")), 3);
+    assert_eq!(white.count(square("d3")), 2);
+    assert_eq!(white.count(square("e4")), 0);
+    // The pieces on the back rank defend the pawns in front of them.
+    assert_eq!(white.count(square("e2")), 4);
+    assert_eq!(white.attacked.count(), 22);
+    assert_eq!(black.count(square("f6")), 3);
+    assert!(white.attacked.contains(square("h3")) && !white.attacked.contains(square("h6")));
+    for square in white.attacked {
+        assert_eq!(
+            board.attackers_of(square, true).count() as u8,
+            white.count(square)
+        );
+    }
+}
+
+#[test]
+fn attacks_from() {
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    let targets = |from: &str| {
+        let mut targets: Vec<_> = board.attacks_from(square(from)).collect();
+        targets.sort();
+        targets
+    };
+    // Squares with pieces of either color are attacked.
+    assert_eq!(
+        targets("e5"),
+        ["d3", "f3", "c4", "g4", "c6", "g6", "d7", "f7"].map(square)
+    );
+    // Sliding pieces stop at the first piece in their way.
+    assert_eq!(targets("a1"), ["b1", "c1", "d1", "e1", "a2"].map(square));
+    assert_eq!(targets("e4").len(), 2);
+    assert!(board.attacks_from(square("e3")).is_empty());
+}
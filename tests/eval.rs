@@ -0,0 +1,321 @@
+use justok::board::Board;
+use justok::eval::params::{
+    BACKWARD_PAWN, BISHOP_PAIR, CANDIDATE_PASSER, CONNECTED_PAWN, DEFAULT_PARAMS, DOUBLED_PAWN,
+    ISOLATED_PAWN, KING_ATTACK, KING_ATTACKERS, KING_FILE, MATERIAL, MOBILITY, MOBILITY_OFFSETS,
+    PASSED_KING_DISTANCE, PASSED_PAWN, PAWN_ISLAND, PAWN_SHIELD, PAWN_STORM, PIECE_SQUARE, Params,
+    ParamsError, ROOK_FILE, TEMPO, Taper, Term,
+};
+use justok::eval::{Evaluator, MAX_PHASE, blend, phase};
+
+const POSITIONS: [&str; 5] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r1bq1rk1/pp2bppp/2n1pn2/3p4/2PP4/2N1PN2/P4PPP/R2QKB1R b KQ - 1 9",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "4k3/8/8/3B4/8/8/8/2B1K3 b - - 0 1",
+];
+
+/// The same position with the colors swapped and the board flipped, so that white's view of it is black's view of the original.
+fn mirror(fen: &str) -> Board {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    let swap_case = |c: char| match c.is_ascii_uppercase() {
+        true => c.to_ascii_lowercase(),
+        false => c.to_ascii_uppercase(),
+    };
+    let placement: Vec<String> = fields[0]
+        .split('/')
+        .rev()
+        .map(|rank| rank.chars().map(swap_case).collect())
+        .collect();
+    let side = match fields[1] {
+        "w" => "b",
+        _ => "w",
+    };
+    let castling = match fields[2] {
+        "-" => "-".to_owned(),
+        rights => {
+            let mut swapped: Vec<char> = rights.chars().map(swap_case).collect();
+            swapped.sort_by_key(|c| (c.is_ascii_lowercase(), *c));
+            swapped.into_iter().collect()
+        }
+    };
+    let en_passant = match fields[3] {
+        "-" => "-".to_owned(),
+        square => square.replace('3', "6").replace('6', "3"),
+    };
+    let fen = format!(
+        "{} {side} {castling} {en_passant} {} {}",
+        placement.join("/"),
+        fields[4],
+        fields[5]
+    );
+    Board::from_fen(&fen).unwrap()
+}
+
+#[test]
+fn start_position_is_worth_the_tempo() {
+    let mut evaluator = Evaluator::new();
+    let board = Board::from_fen(POSITIONS[0]).unwrap();
+    let tempo = DEFAULT_PARAMS.get(TEMPO, 0);
+    assert_eq!(evaluator.evaluate(&board), blend(tempo, MAX_PHASE));
+}
+
+/// A position is worth as much to white as the mirrored position is to black.
+#[test]
+fn is_symmetric() {
+    let mut evaluator = Evaluator::new();
+    for fen in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(
+            evaluator.evaluate(&board),
+            evaluator.evaluate(&mirror(fen)),
+            "{fen}"
+        );
+    }
+}
+
+#[test]
+fn counts_material_for_the_side_to_move() {
+    let mut evaluator = Evaluator::new();
+    let white = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+    let black = Board::from_fen("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+    assert!(evaluator.evaluate(&white) > 800);
+    assert!(evaluator.evaluate(&black) < -800);
+
+    let trace = evaluator.trace(&white);
+    let material = trace.terms.iter().find(|t| t.term == MATERIAL).unwrap();
+    assert_eq!(material.white, DEFAULT_PARAMS.get(MATERIAL, 4));
+    assert_eq!(material.black, Default::default());
+}
+
+#[test]
+fn phase_goes_down_as_pieces_are_traded() {
+    assert_eq!(phase(&Board::from_fen(POSITIONS[0]).unwrap()), MAX_PHASE);
+    assert_eq!(phase(&Board::from_fen(POSITIONS[3]).unwrap()), 4);
+    assert_eq!(
+        phase(&Board::from_fen("4k3/pppp4/8/8/8/8/4PPPP/4K3 w - - 0 1").unwrap()),
+        0
+    );
+}
+
+#[test]
+fn rewards_bishop_pair_and_rooks_on_open_files() {
+    let pair = DEFAULT_PARAMS.get(BISHOP_PAIR, 0);
+    assert_eq!(term(POSITIONS[4], BISHOP_PAIR), (pair, Default::default()));
+    assert_eq!(
+        term("2b1kn2/8/8/8/8/8/8/2B1KB2 w - - 0 1", BISHOP_PAIR),
+        (pair, Default::default())
+    );
+
+    // White's rook is on an open file, black's on a file with only a white pawn on it.
+    let open = DEFAULT_PARAMS.get(ROOK_FILE, 0);
+    let semi_open = DEFAULT_PARAMS.get(ROOK_FILE, 1);
+    assert_eq!(
+        term("3rk3/8/8/8/8/8/3P4/R3K3 w - - 0 1", ROOK_FILE),
+        (open, semi_open)
+    );
+    // Rooks behind their own pawns get nothing.
+    assert_eq!(
+        term("r3k3/p7/8/8/8/8/P7/R3K3 w - - 0 1", ROOK_FILE),
+        (Default::default(), Default::default())
+    );
+}
+
+/// What a term contributes for white and for black in a position.
+fn term(fen: &str, term: Term) -> (Taper, Taper) {
+    let trace = Evaluator::new().trace(&Board::from_fen(fen).unwrap());
+    let score = trace.terms.iter().find(|t| t.term == term).unwrap();
+    (score.white, score.black)
+}
+
+#[test]
+fn scores_pawn_structure() {
+    let param = |term, index| DEFAULT_PARAMS.get(term, index);
+    // White has doubled pawns on the c-file and a pawn on e5 defended by d4.
+    // Black's pawns on f7, g6 and h7 form one island, and a7 another.
+    let fen = "4k3/p4p1p/6p1/4P3/2PP4/2P5/8/4K3 w - - 0 1";
+    assert_eq!(
+        term(fen, DOUBLED_PAWN),
+        (param(DOUBLED_PAWN, 0), Taper::default())
+    );
+    assert_eq!(
+        term(fen, PAWN_ISLAND),
+        (param(PAWN_ISLAND, 0), param(PAWN_ISLAND, 0) * 2)
+    );
+    // c4 and d4 are passed, but not c3 behind c4 or e5 which f7 can stop.
+    // Every black pawn is passed, except f7 which e5 can stop.
+    assert_eq!(
+        term(fen, PASSED_PAWN),
+        (
+            param(PASSED_PAWN, 3) * 2,
+            param(PASSED_PAWN, 1) * 2 + param(PASSED_PAWN, 2)
+        )
+    );
+    // c4 and d4 are next to each other and e5 is defended by d4. On black's side f7 and h7 defend g6.
+    assert_eq!(
+        term(fen, CONNECTED_PAWN),
+        (
+            param(CONNECTED_PAWN, 3) * 2 + param(CONNECTED_PAWN, 4),
+            param(CONNECTED_PAWN, 2)
+        )
+    );
+    assert_eq!(
+        term(fen, ISOLATED_PAWN),
+        (Taper::default(), param(ISOLATED_PAWN, 0))
+    );
+
+    // Black's d6 pawn can't be defended by the pawns on c5 and e5, and d5 is covered by white's e4 pawn.
+    let fen = "4k3/8/3p4/2p1p3/4P3/8/8/4K3 b - - 0 1";
+    assert_eq!(
+        term(fen, BACKWARD_PAWN),
+        (Taper::default(), param(BACKWARD_PAWN, 0))
+    );
+
+    // White's c5 pawn faces one enemy pawn on the b-file with its neighbour on d4 to help it through.
+    let fen = "4k3/1p6/8/2P5/3P4/8/8/4K3 w - - 0 1";
+    assert_eq!(
+        term(fen, CANDIDATE_PASSER),
+        (param(CANDIDATE_PASSER, 4), Taper::default())
+    );
+}
+
+/// Passed pawns are better with the own king close and the enemy king far away, more so as they advance.
+#[test]
+fn scales_passed_pawns_by_king_distance() {
+    let near = term("8/8/4PK2/8/8/8/8/k7 w - - 0 1", PASSED_KING_DISTANCE);
+    let own = DEFAULT_PARAMS.get(PASSED_KING_DISTANCE, 0);
+    let enemy = DEFAULT_PARAMS.get(PASSED_KING_DISTANCE, 1);
+    // The pawn is on its sixth rank, four ranks up, with the own king one move and the enemy king six moves from e7.
+    assert_eq!(near.0, (own + enemy * 6) * 4);
+
+    let mut evaluator = Evaluator::new();
+    let escorted = evaluator.evaluate(&Board::from_fen("8/8/4PK2/8/8/8/8/k7 w - - 0 1").unwrap());
+    let stopped = evaluator.evaluate(&Board::from_fen("4k3/8/4P3/8/8/8/8/K7 w - - 0 1").unwrap());
+    assert!(escorted > stopped + 50);
+}
+
+/// Pawns on the last rank only occur in positions that aren't valid, but can still be read from a FEN.
+#[test]
+fn evaluates_pawns_on_the_last_rank() {
+    for fen in [
+        "4k3/8/8/8/8/8/8/p3K3 w - - 0 1",
+        "P3k3/8/8/8/8/8/8/4K3 b - - 0 1",
+        "P3k3/1p6/8/8/8/8/1P6/p3K3 w - - 0 1",
+    ] {
+        Evaluator::new().evaluate(&Board::from_fen(fen).unwrap());
+        assert_eq!(
+            term(fen, PASSED_KING_DISTANCE),
+            (Taper::default(), Taper::default()),
+            "{fen}"
+        );
+    }
+}
+
+#[test]
+fn counts_mobility() {
+    let param = |index| DEFAULT_PARAMS.get(MOBILITY, index);
+    // The knight on a1 can't go to b3 with its own pawn on it, nor to c2 which the pawn on d3 covers.
+    // The bishop on h8 sees seven squares, down to the knight on a1.
+    let fen = "4k2b/8/8/8/8/1P1p4/8/N3K3 w - - 0 1";
+    let (white, black) = term(fen, MOBILITY);
+    assert_eq!(white, param(MOBILITY_OFFSETS[0]));
+    assert_eq!(black, param(MOBILITY_OFFSETS[1] + 7));
+}
+
+#[test]
+fn scores_king_safety() {
+    let param = |term, index| DEFAULT_PARAMS.get(term, index);
+    // White's queen on h5 and knight on g5 both attack the squares around black's king on g8.
+    let fen = "r4rk1/ppp2ppp/8/6NQ/8/8/PPP2PPP/R5K1 w - - 0 1";
+    let (white, black) = term(fen, KING_ATTACKERS);
+    assert_eq!(white, param(KING_ATTACKERS, 2));
+    assert_eq!(black, param(KING_ATTACKERS, 0));
+    // The knight attacks f7 and h7, and so does the queen.
+    let (white, _) = term(fen, KING_ATTACK);
+    assert_eq!(white, param(KING_ATTACK, 0) * 2 + param(KING_ATTACK, 3) * 2);
+
+    // Both kings are behind three unmoved pawns, and no files near them are open.
+    let (white, black) = term(fen, PAWN_SHIELD);
+    assert_eq!(white, param(PAWN_SHIELD, 0) * 3);
+    assert_eq!(black, param(PAWN_SHIELD, 0) * 3);
+    assert_eq!(term(fen, KING_FILE), (Taper::default(), Taper::default()));
+
+    // White's king has an open g-file and a half-open h-file, with a black pawn on h4 storming it.
+    // Only the f2 pawn shields it. Black's king has an open g-file.
+    let fen = "6k1/5p1p/8/8/7p/8/5P2/6K1 w - - 0 1";
+    let (white, _) = term(fen, PAWN_SHIELD);
+    assert_eq!(white, param(PAWN_SHIELD, 0) + param(PAWN_SHIELD, 2) * 2);
+    let (white, _) = term(fen, PAWN_STORM);
+    assert_eq!(white, param(PAWN_STORM, 2) + param(PAWN_STORM, 3));
+    let (white, black) = term(fen, KING_FILE);
+    assert_eq!(white, param(KING_FILE, 0) + param(KING_FILE, 1));
+    assert_eq!(black, param(KING_FILE, 0));
+}
+
+/// Cached pawn structures score the same as pawn structures scored from scratch,
+/// and are forgotten when the weights change.
+#[test]
+fn caches_pawn_structures() {
+    let mut evaluator = Evaluator::new();
+    let scores: Vec<i32> = POSITIONS
+        .iter()
+        .map(|fen| evaluator.evaluate(&Board::from_fen(fen).unwrap()))
+        .collect();
+    for (fen, score) in POSITIONS.iter().zip(&scores) {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(evaluator.evaluate(&board), *score);
+        assert_eq!(Evaluator::new().evaluate(&board), *score);
+    }
+
+    let board = Board::from_fen("4k3/8/8/8/8/2P5/2P5/4K3 w - - 0 1").unwrap();
+    let before = evaluator.evaluate(&board);
+    let mut params = evaluator.params().clone();
+    params.term_mut(DOUBLED_PAWN)[0] = Taper::default();
+    evaluator.set_params(params);
+    assert_eq!(
+        evaluator.evaluate(&board),
+        before - blend(DEFAULT_PARAMS.get(DOUBLED_PAWN, 0), phase(&board))
+    );
+}
+
+/// The terms of a trace add up to the score of the evaluation.
+#[test]
+fn trace_adds_up() {
+    let mut evaluator = Evaluator::new();
+    for fen in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        let trace = evaluator.trace(&board);
+        assert_eq!(trace.score, evaluator.evaluate(&board));
+        let white_score = blend(trace.total(), trace.phase);
+        match board.white_to_move {
+            true => assert_eq!(trace.score, white_score),
+            false => assert_eq!(trace.score, -white_score),
+        }
+        assert!(trace.to_string().contains("bishop_pair"));
+    }
+}
+
+/// Parameters written as text read back the same, and parameters left out keep their default values.
+#[test]
+fn params_round_trip_through_text() {
+    let mut params = DEFAULT_PARAMS;
+    params.term_mut(TEMPO)[0] = Taper { mg: 7, eg: -3 };
+    params.term_mut(PIECE_SQUARE)[200] = Taper { mg: 1, eg: 2 };
+    let text = params.to_string();
+    assert!(text.contains("\ntempo 0 7 -3\n"));
+    assert_eq!(text.parse::<Params>(), Ok(params));
+
+    let partial: Params = "# only the tempo\n\ntempo 0 7 -3\n".parse().unwrap();
+    assert_eq!(partial.get(TEMPO, 0), Taper { mg: 7, eg: -3 });
+    assert_eq!(partial.get(MATERIAL, 0), DEFAULT_PARAMS.get(MATERIAL, 0));
+
+    assert_eq!("tempo 0 7".parse::<Params>(), Err(ParamsError::Syntax(1)));
+    assert_eq!(
+        "\nspeed 0 7 7".parse::<Params>(),
+        Err(ParamsError::UnknownTerm("speed".to_owned()))
+    );
+    assert_eq!(
+        "tempo 1 7 7".parse::<Params>(),
+        Err(ParamsError::Index("tempo".to_owned(), 1))
+    );
+}
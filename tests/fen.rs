@@ -0,0 +1,192 @@
+use justok::board::{Board, FenError, PositionError};
+
+#[test]
+fn round_trip() {
+    let positions = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+        "8/8/8/2k5/2pP4/8/B7/4K3 b - d3 0 3",
+        "4k3/8/8/8/8/8/5p2/1K6 b - - 20 1",
+    ];
+    for fen in positions {
+        assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+        assert_eq!(Board::from_fen(fen).unwrap().validate(), Ok(()));
+    }
+}
+
+#[test]
+fn chess960_castling_rights() {
+    // X-FEN uses 'KQkq' for the outermost rooks, and rook files otherwise.
+    let positions = [
+        (
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w KQkq - 2 9",
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        ),
+        (
+            "2r1kr2/8/8/8/8/8/8/1R2K1R1 w KQkq - 0 1",
+            "2r1kr2/8/8/8/8/8/8/1R2K1R1 w GBfc - 0 1",
+        ),
+        (
+            "rk1r4/8/8/8/8/8/8/RK1R1R2 w Dq - 0 1",
+            "rk1r4/8/8/8/8/8/8/RK1R1R2 w Da - 0 1",
+        ),
+    ];
+    for (x_fen, shredder_fen) in positions {
+        for fen in [x_fen, shredder_fen] {
+            let board = Board::from_fen(fen).unwrap();
+            assert!(board.is_chess960(), "{fen}");
+            assert_eq!(board.validate(), Ok(()), "{fen}");
+            assert_eq!(board.to_fen(), x_fen);
+            assert_eq!(board.to_shredder_fen(), shredder_fen);
+        }
+    }
+
+    // Standard positions stay standard, even when written as Shredder-FEN.
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w HAha - 0 1").unwrap();
+    assert!(!board.is_chess960());
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+    );
+}
+
+#[test]
+fn clocks_are_optional() {
+    let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -").unwrap();
+    assert_eq!(
+        board.to_fen(),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"
+    );
+}
+
+#[test]
+fn syntax_errors() {
+    let cases = [
+        ("", FenError::FieldCount(0)),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 extra",
+            FenError::FieldCount(7),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            FenError::RankCount(7),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN w KQkq - 0 1",
+            FenError::RankLength(1),
+        ),
+        (
+            "rnbqkbnr/ppppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            FenError::RankLength(7),
+        ),
+        (
+            "rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            FenError::UnknownPiece('9'),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1",
+            FenError::UnknownPiece('X'),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+            FenError::SideToMove("x".to_owned()),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkx - 0 1",
+            FenError::Castling("KQkx".to_owned()),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KKq - 0 1",
+            FenError::Castling("KKq".to_owned()),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq i6 0 1",
+            FenError::EnPessant("i6".to_owned()),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e3 0 1",
+            FenError::EnPessant("e3".to_owned()),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1",
+            FenError::HalfMoveClock("x".to_owned()),
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 -1",
+            FenError::FullMoveCounter("-1".to_owned()),
+        ),
+    ];
+    for (fen, error) in cases {
+        assert_eq!(Board::from_fen(fen), Err(error), "{fen}");
+    }
+}
+
+#[test]
+fn position_errors() {
+    let cases = [
+        (
+            "rnbq1bnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1",
+            PositionError::KingCount {
+                white: false,
+                count: 0,
+            },
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBKKBNR w kq - 0 1",
+            PositionError::KingCount {
+                white: true,
+                count: 2,
+            },
+        ),
+        (
+            "Pnbqkbnr/pppppppp/8/8/8/8/1PPPPPPP/RNBQKBNR w KQk - 0 1",
+            PositionError::PawnOnBackRank(56),
+        ),
+        (
+            "4k3/4R3/8/8/8/8/8/4K3 w - - 0 1",
+            PositionError::OpponentInCheck,
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 b - e3 0 1",
+            PositionError::EnPessant(20),
+        ),
+        (
+            "4k3/8/8/8/4P3/8/4P3/4K3 b - e3 0 1",
+            PositionError::EnPessant(20),
+        ),
+    ];
+    for (fen, error) in cases {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(board.validate(), Err(error), "{fen}");
+    }
+}
+
+#[test]
+fn castling_rights_out_of_place() {
+    // Rights whose king or rook is not in place are dropped.
+    let cases = [
+        (
+            "4k3/8/8/8/8/8/8/4K2R w KQ - 0 1",
+            "4k3/8/8/8/8/8/8/4K2R w K - 0 1",
+        ),
+        (
+            "4k3/8/8/8/8/8/8/R3K2R w G - 0 1",
+            "4k3/8/8/8/8/8/8/R3K2R w - - 0 1",
+        ),
+        (
+            "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+        ),
+        (
+            "r3k2r/8/8/8/8/8/4K3/R6R w KQkq - 0 1",
+            "r3k2r/8/8/8/8/8/4K3/R6R w kq - 0 1",
+        ),
+    ];
+    for (fen, expected) in cases {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(board.to_fen(), expected, "{fen}");
+        assert_eq!(board.validate(), Ok(()), "{fen}");
+    }
+}
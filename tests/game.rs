@@ -0,0 +1,82 @@
+use justok::Move;
+use justok::board::Board;
+use justok::game::{Game, STANDARD_FEN};
+use justok::outcome::GameOutcome;
+use justok::piece::Piece;
+
+fn play(game: &mut Game, moves: &[&str]) {
+    for m in moves {
+        game.push(Move::from_uci(game.board(), m).unwrap());
+    }
+}
+
+#[test]
+fn undo_and_redo() {
+    let mut game = Game::default();
+    play(&mut game, &["e2e4", "e7e5", "g1f3"]);
+    let after_three = *game.board();
+
+    let undone = |game: &mut Game| game.undo().map(|p| p.r#move.to_string());
+    assert_eq!(undone(&mut game).as_deref(), Some("g1f3"));
+    assert_eq!(undone(&mut game).as_deref(), Some("e7e5"));
+    assert_eq!(game.ply(), 1);
+    assert_eq!(
+        game.board().to_fen(),
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+
+    assert!(game.redo().is_some());
+    assert!(game.redo().is_some());
+    assert!(game.redo().is_none());
+    assert_eq!(*game.board(), after_three);
+
+    // Playing a different move forgets the moves that could be redone.
+    game.undo();
+    play(&mut game, &["b1c3"]);
+    assert!(game.redo().is_none());
+    assert_eq!(game.ply(), 3);
+
+    while game.undo().is_some() {}
+    assert_eq!(game.board(), game.start());
+    assert_eq!(game.keys(), [game.start().hash()]);
+}
+
+#[test]
+fn position_at_and_iteration() {
+    let mut game = Game::default();
+    let moves = ["d2d4", "d7d5", "c2c4", "d5c4", "e2e3"];
+    play(&mut game, &moves);
+
+    let mut board = Board::from_fen(STANDARD_FEN).unwrap();
+    for (ply, (position, played)) in game.iter().enumerate() {
+        assert_eq!(game.position_at(ply), Some(position));
+        assert_eq!(position, board);
+        assert_eq!(played.r#move.to_string(), moves[ply]);
+        assert_eq!(played.info, board.apply(played.r#move));
+        assert_eq!(game.keys()[ply + 1], board.hash());
+    }
+    assert_eq!(game.position_at(moves.len()), Some(*game.board()));
+    assert_eq!(game.position_at(moves.len() + 1), None);
+
+    // The captured pawn is reported to the caller of push.
+    game.undo();
+    game.undo();
+    let capture = Move::from_uci(game.board(), "d5c4").unwrap();
+    let info = game.push(capture).clone();
+    assert_eq!(info.removed_pieces, vec![(26, Piece::PawnWhite)]);
+    assert_eq!(info.captured, Some((26, Piece::PawnWhite)));
+}
+
+#[test]
+fn repetition() {
+    let mut game = Game::default();
+    let knight_dance = ["g1f3", "g8f6", "f3g1", "f6g8"];
+    play(&mut game, &knight_dance);
+    assert_eq!(game.outcome(), None);
+    play(&mut game, &knight_dance);
+    assert_eq!(game.outcome(), Some(GameOutcome::ThreefoldRepetition));
+
+    // Taking a move back takes back the repetition.
+    game.undo();
+    assert_eq!(game.outcome(), None);
+}
@@ -0,0 +1,145 @@
+use justok::board::{Board, MoveInfo};
+use justok::piece::Piece;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+/// Positions covering castling, Chess960 castling, en pessant, promotions and checks.
+const POSITIONS: [&str; 9] = [
+    "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "8/4k3/8/8/8/8/r6r/R3K2R w KQ - 0 1",
+    "r3k2r/R6R/8/8/8/8/4K3/8 b kq - 0 1",
+    "8/8/8/2k5/2pP4/8/B7/4K3 b - d3 0 3",
+    "1k6/5P2/8/8/8/8/8/4K3 w - - 20 1",
+    "n1n5/PPPk4/8/8/8/8/4Kppp/5N1N b - - 0 1",
+    "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+];
+
+/// Play random games from each position, and check at every ply
+/// that making and unmaking every legal move leaves the board untouched.
+#[test]
+fn make_unmake_round_trips() {
+    let mut rng = ChaCha8Rng::seed_from_u64(1234);
+
+    for fen in POSITIONS {
+        for _ in 0..20 {
+            let mut board = Board::from_fen(fen).unwrap();
+            for _ in 0..60 {
+                let moves = board.generate_moves();
+                if moves.is_empty() {
+                    break;
+                }
+
+                for &m in moves.iter() {
+                    let before = board;
+                    let undo = board.make(m);
+                    board.unmake(m, undo);
+                    assert_eq!(before, board, "make/unmake of {m} in {}", before.to_fen());
+                }
+
+                let m = moves[rng.random_range(0..moves.len())];
+                board.make(m);
+            }
+        }
+    }
+}
+
+/// Check that making a move gives the same board as applying it.
+#[test]
+fn make_matches_apply() {
+    for fen in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        for m in board.generate_moves() {
+            let (mut made, mut applied) = (board, board);
+            made.make(m);
+            applied.apply(m);
+            assert_eq!(made, applied, "{m} in {fen}");
+        }
+    }
+}
+
+/// Place the pieces of a board on a list of squares, like a GUI showing the board would.
+fn squares(board: &Board) -> [Option<Piece>; 64] {
+    std::array::from_fn(|square| board.at(square as u8))
+}
+
+/// Move pieces around a list of squares as described by a [MoveInfo].
+/// Pieces are lifted off their squares before any are put down, as the king and rook may swap squares in Chess960.
+fn play_info(squares: &mut [Option<Piece>; 64], info: &MoveInfo) {
+    for &(square, piece) in &info.removed_pieces {
+        assert_eq!(squares[square as usize].take(), Some(piece));
+    }
+    let lifted: Vec<(u8, Option<Piece>)> = info
+        .moved_pieces
+        .iter()
+        .map(|&(from, to)| (to, squares[from as usize].take()))
+        .collect();
+    for (to, piece) in lifted {
+        squares[to as usize] = piece;
+    }
+    for &(square, piece) in &info.added_pieces {
+        squares[square as usize] = Some(piece);
+    }
+}
+
+/// Check that the move info of every move describes the move, and that its inverse takes it back.
+#[test]
+fn move_info_inverts() {
+    for fen in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        for m in board.generate_moves() {
+            let mut after = board;
+            let info = after.apply(m);
+
+            let mut pieces = squares(&board);
+            play_info(&mut pieces, &info);
+            assert_eq!(pieces, squares(&after), "{m} in {fen}");
+            play_info(&mut pieces, &info.inverse());
+            assert_eq!(pieces, squares(&board), "inverse of {m} in {fen}");
+
+            assert_eq!(info.captured.is_some(), m.is_capture(), "{m} in {fen}");
+            assert_eq!(info.rook_move.is_some(), m.is_castling(), "{m} in {fen}");
+            assert_eq!(info.undo.half_move_clock, board.half_move_clock());
+            assert_eq!(info.undo.hash, board.hash());
+            after.unmake(m, info.undo);
+            assert_eq!(after, board, "unmake of {m} in {fen}");
+        }
+    }
+}
+
+/// A null move only passes the turn, and hashes like the position with the other side to move.
+#[test]
+fn make_null() {
+    let mut board = Board::from_fen("8/8/8/2k5/2pP4/8/B7/4K3 b - d3 0 3").unwrap();
+    board.make_null();
+    let passed = Board::from_fen("8/8/8/2k5/2pP4/8/B7/4K3 w - - 1 4").unwrap();
+    assert_eq!(board.to_fen(), passed.to_fen());
+    assert_eq!(board.hash(), passed.hash());
+    board.make_null();
+    assert_eq!(board.to_fen(), "8/8/8/2k5/2pP4/8/B7/4K3 b - - 2 4");
+}
+
+/// The pawn hash only changes when pawns move, are captured or promote.
+#[test]
+fn pawn_hash() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    assert_eq!(board.pawn_hash(), 0);
+
+    let fen = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    let board = Board::from_fen(fen).unwrap();
+    let same_pawns =
+        Board::from_fen("4k3/p1ppqp2/4p1p1/3P4/1p2P3/7p/PPP2PPP/4K3 b - - 0 1").unwrap();
+    assert_eq!(board.pawn_hash(), same_pawns.pawn_hash());
+    for m in board.generate_moves() {
+        let mut after = board;
+        let undo = after.make(m);
+        let moves_pawn = matches!(undo.moved, Piece::PawnWhite | Piece::PawnBlack);
+        let captures_pawn = matches!(undo.captured, Some(Piece::PawnWhite | Piece::PawnBlack));
+        assert_eq!(
+            after.pawn_hash() != board.pawn_hash(),
+            moves_pawn || captures_pawn,
+            "{m} in {fen}"
+        );
+    }
+}
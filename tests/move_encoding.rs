@@ -0,0 +1,66 @@
+use justok::board::Board;
+use justok::r#move::{Move, MoveError, MoveKind};
+
+#[test]
+fn kinds() {
+    let board = Board::from_fen("r3k2r/1P6/8/3pP3/8/8/P7/R3K2R w KQkq d6 0 1").unwrap();
+    let kind = |uci: &str| Move::from_uci(&board, uci).unwrap().kind();
+    assert_eq!(kind("a2a3"), MoveKind::Quiet);
+    assert_eq!(kind("a2a4"), MoveKind::DoublePush);
+    assert_eq!(kind("e1g1"), MoveKind::KingCastle);
+    assert_eq!(kind("e1c1"), MoveKind::QueenCastle);
+    assert_eq!(kind("h1h8"), MoveKind::Capture);
+    assert_eq!(kind("e5d6"), MoveKind::EnPessant);
+    assert_eq!(kind("b7b8n"), MoveKind::KnightPromotion);
+    assert_eq!(kind("b7b8q"), MoveKind::QueenPromotion);
+    assert_eq!(kind("b7a8r"), MoveKind::RookPromotionCapture);
+    assert_eq!(kind("b7a8b"), MoveKind::BishopPromotionCapture);
+
+    let capture = Move::from_uci(&board, "b7a8q").unwrap();
+    assert!(capture.is_capture() && capture.is_promotion());
+    assert_eq!(capture.promote_to().map(|p| p.to_char()), Some('Q'));
+}
+
+#[test]
+fn bits() {
+    for fen in [
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    ] {
+        let board = Board::from_fen(fen).unwrap();
+        for m in board.generate_moves() {
+            assert_eq!(Move::from_bits(m.bits()), m);
+            assert_eq!(Move::from_uci(&board, &m.to_string()), Ok(m));
+        }
+    }
+    assert_eq!(size_of::<Move>(), 2);
+}
+
+/// Chess960 castling is written as the king capturing its own rook.
+#[test]
+fn chess960_castling() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/1R4KR w HB - 0 1").unwrap();
+    let castle = Move::from_uci(&board, "g1h1").unwrap();
+    assert_eq!(castle.kind(), MoveKind::KingCastle);
+    assert_eq!(board.castling(castle), Some([(6, 6), (7, 5)]));
+    let castle = Move::from_uci(&board, "g1b1").unwrap();
+    assert_eq!(castle.kind(), MoveKind::QueenCastle);
+    assert_eq!(board.castling(castle), Some([(6, 2), (1, 3)]));
+}
+
+#[test]
+fn errors() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+    for text in ["e2", "e2e4qq", "e2e9", "i2i4", "e7e8x"] {
+        assert_eq!(
+            Move::from_uci(&board, text),
+            Err(MoveError::Invalid(text.to_owned()))
+        );
+    }
+    for text in ["e2e5", "e8e7", "d2d4", "e2e3q"] {
+        assert_eq!(
+            Move::from_uci(&board, text),
+            Err(MoveError::Illegal(text.to_owned()))
+        );
+    }
+}
@@ -1,96 +1,105 @@
+#![allow(clippy::len_zero)]
+
 use std::collections::HashSet;
 
 use justok::board::Board;
-use justok::Move;
+use justok::r#move::{Move, MoveKind};
+use justok::square_from_algebraic;
+
+/// Read a move in UCI notation that is legal in the position.
+fn uci(fen: &str, text: &str) -> Move {
+    Move::from_uci(&Board::from_fen(fen).unwrap(), text).unwrap()
+}
 
 #[test]
 fn opening_moves() {
     // Initialize the board and generate all possible moves.
     let standard = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
     let generated_moves: HashSet<Move> = Board::from_fen(standard)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
 
     let expected = vec![
         (
-            Move::from_str("b1a3"),
+            uci(standard, "b1a3"),
             "rnbqkbnr/pppppppp/8/8/8/N7/PPPPPPPP/R1BQKBNR b KQkq - 1 1",
         ),
         (
-            Move::from_str("b1c3"),
+            uci(standard, "b1c3"),
             "rnbqkbnr/pppppppp/8/8/8/2N5/PPPPPPPP/R1BQKBNR b KQkq - 1 1",
         ),
         (
-            Move::from_str("g1f3"),
+            uci(standard, "g1f3"),
             "rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 1 1",
         ),
         (
-            Move::from_str("g1h3"),
+            uci(standard, "g1h3"),
             "rnbqkbnr/pppppppp/8/8/8/7N/PPPPPPPP/RNBQKB1R b KQkq - 1 1",
         ),
         (
-            Move::from_str("a2a3"),
+            uci(standard, "a2a3"),
             "rnbqkbnr/pppppppp/8/8/8/P7/1PPPPPPP/RNBQKBNR b KQkq - 0 1",
         ),
         (
-            Move::from_str("a2a4"),
+            uci(standard, "a2a4"),
             "rnbqkbnr/pppppppp/8/8/P7/8/1PPPPPPP/RNBQKBNR b KQkq a3 0 1",
         ),
         (
-            Move::from_str("b2b3"),
+            uci(standard, "b2b3"),
             "rnbqkbnr/pppppppp/8/8/8/1P6/P1PPPPPP/RNBQKBNR b KQkq - 0 1",
         ),
         (
-            Move::from_str("b2b4"),
+            uci(standard, "b2b4"),
             "rnbqkbnr/pppppppp/8/8/1P6/8/P1PPPPPP/RNBQKBNR b KQkq b3 0 1",
         ),
         (
-            Move::from_str("c2c3"),
+            uci(standard, "c2c3"),
             "rnbqkbnr/pppppppp/8/8/8/2P5/PP1PPPPP/RNBQKBNR b KQkq - 0 1",
         ),
         (
-            Move::from_str("c2c4"),
+            uci(standard, "c2c4"),
             "rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3 0 1",
         ),
         (
-            Move::from_str("d2d3"),
+            uci(standard, "d2d3"),
             "rnbqkbnr/pppppppp/8/8/8/3P4/PPP1PPPP/RNBQKBNR b KQkq - 0 1",
         ),
         (
-            Move::from_str("d2d4"),
+            uci(standard, "d2d4"),
             "rnbqkbnr/pppppppp/8/8/3P4/8/PPP1PPPP/RNBQKBNR b KQkq d3 0 1",
         ),
         (
-            Move::from_str("e2e3"),
+            uci(standard, "e2e3"),
             "rnbqkbnr/pppppppp/8/8/8/4P3/PPPP1PPP/RNBQKBNR b KQkq - 0 1",
         ),
         (
-            Move::from_str("e2e4"),
+            uci(standard, "e2e4"),
             "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
         ),
         (
-            Move::from_str("f2f3"),
+            uci(standard, "f2f3"),
             "rnbqkbnr/pppppppp/8/8/8/5P2/PPPPP1PP/RNBQKBNR b KQkq - 0 1",
         ),
         (
-            Move::from_str("f2f4"),
+            uci(standard, "f2f4"),
             "rnbqkbnr/pppppppp/8/8/5P2/8/PPPPP1PP/RNBQKBNR b KQkq f3 0 1",
         ),
         (
-            Move::from_str("g2g3"),
+            uci(standard, "g2g3"),
             "rnbqkbnr/pppppppp/8/8/8/6P1/PPPPPP1P/RNBQKBNR b KQkq - 0 1",
         ),
         (
-            Move::from_str("g2g4"),
+            uci(standard, "g2g4"),
             "rnbqkbnr/pppppppp/8/8/6P1/8/PPPPPP1P/RNBQKBNR b KQkq g3 0 1",
         ),
         (
-            Move::from_str("h2h3"),
+            uci(standard, "h2h3"),
             "rnbqkbnr/pppppppp/8/8/8/7P/PPPPPPP1/RNBQKBNR b KQkq - 0 1",
         ),
         (
-            Move::from_str("h2h4"),
+            uci(standard, "h2h4"),
             "rnbqkbnr/pppppppp/8/8/7P/8/PPPPPPP1/RNBQKBNR b KQkq h3 0 1",
         ),
     ];
@@ -104,9 +113,10 @@ fn opening_moves() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(standard);
+        let mut board = Board::from_fen(standard).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -116,129 +126,130 @@ fn ruy_lopez() {
     // Check possible moves after the popular Ruy Lopez opening.
     let standard = "r1bqkbnr/pppp1ppp/2n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R b - - 11 7";
     let generated_moves: HashSet<Move> = Board::from_fen(standard)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
 
     let expected = vec![
         (
-            Move::from_str("c6b4"),
+            uci(standard, "c6b4"),
             "r1bqkbnr/pppp1ppp/8/1B2p3/1n2P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("c6d4"),
+            uci(standard, "c6d4"),
             "r1bqkbnr/pppp1ppp/8/1B2p3/3nP3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("c6a5"),
+            uci(standard, "c6a5"),
             "r1bqkbnr/pppp1ppp/8/nB2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("c6e7"),
+            uci(standard, "c6e7"),
             "r1bqkbnr/ppppnppp/8/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("c6b8"),
+            uci(standard, "c6b8"),
             "rnbqkbnr/pppp1ppp/8/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("a7a6"),
+            uci(standard, "a7a6"),
             "r1bqkbnr/1ppp1ppp/p1n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 0 8",
         ),
         (
-            Move::from_str("a7a5"),
+            uci(standard, "a7a5"),
             "r1bqkbnr/1ppp1ppp/2n5/pB2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - a6 0 8",
         ),
         (
-            Move::from_str("b7b6"),
+            uci(standard, "b7b6"),
             "r1bqkbnr/p1pp1ppp/1pn5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 0 8",
         ),
         (
-            Move::from_str("d7d6"),
+            uci(standard, "d7d6"),
             "r1bqkbnr/ppp2ppp/2np4/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 0 8",
         ),
         (
-            Move::from_str("d7d5"),
+            uci(standard, "d7d5"),
             "r1bqkbnr/ppp2ppp/2n5/1B1pp3/4P3/5N2/PPPP1PPP/RNBQK2R w - d6 0 8",
         ),
         (
-            Move::from_str("f7f6"),
+            uci(standard, "f7f6"),
             "r1bqkbnr/pppp2pp/2n2p2/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 0 8",
         ),
         (
-            Move::from_str("f7f5"),
+            uci(standard, "f7f5"),
             "r1bqkbnr/pppp2pp/2n5/1B2pp2/4P3/5N2/PPPP1PPP/RNBQK2R w - f6 0 8",
         ),
         (
-            Move::from_str("g7g6"),
+            uci(standard, "g7g6"),
             "r1bqkbnr/pppp1p1p/2n3p1/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 0 8",
         ),
         (
-            Move::from_str("g7g5"),
+            uci(standard, "g7g5"),
             "r1bqkbnr/pppp1p1p/2n5/1B2p1p1/4P3/5N2/PPPP1PPP/RNBQK2R w - g6 0 8",
         ),
         (
-            Move::from_str("h7h6"),
+            uci(standard, "h7h6"),
             "r1bqkbnr/pppp1pp1/2n4p/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 0 8",
         ),
         (
-            Move::from_str("h7h5"),
+            uci(standard, "h7h5"),
             "r1bqkbnr/pppp1pp1/2n5/1B2p2p/4P3/5N2/PPPP1PPP/RNBQK2R w - h6 0 8",
         ),
         (
-            Move::from_str("a8b8"),
+            uci(standard, "a8b8"),
             "1rbqkbnr/pppp1ppp/2n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("d8e7"),
+            uci(standard, "d8e7"),
             "r1b1kbnr/ppppqppp/2n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("d8f6"),
+            uci(standard, "d8f6"),
             "r1b1kbnr/pppp1ppp/2n2q2/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("d8g5"),
+            uci(standard, "d8g5"),
             "r1b1kbnr/pppp1ppp/2n5/1B2p1q1/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("d8h4"),
+            uci(standard, "d8h4"),
             "r1b1kbnr/pppp1ppp/2n5/1B2p3/4P2q/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("e8e7"),
+            uci(standard, "e8e7"),
             "r1bq1bnr/ppppkppp/2n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("f8e7"),
+            uci(standard, "f8e7"),
             "r1bqk1nr/ppppbppp/2n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("f8d6"),
+            uci(standard, "f8d6"),
             "r1bqk1nr/pppp1ppp/2nb4/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("f8c5"),
+            uci(standard, "f8c5"),
             "r1bqk1nr/pppp1ppp/2n5/1Bb1p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("f8b4"),
+            uci(standard, "f8b4"),
             "r1bqk1nr/pppp1ppp/2n5/1B2p3/1b2P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("f8a3"),
+            uci(standard, "f8a3"),
             "r1bqk1nr/pppp1ppp/2n5/1B2p3/4P3/b4N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("g8f6"),
+            uci(standard, "g8f6"),
             "r1bqkb1r/pppp1ppp/2n2n2/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("g8h6"),
+            uci(standard, "g8h6"),
             "r1bqkb1r/pppp1ppp/2n4n/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
         (
-            Move::from_str("g8e7"),
+            uci(standard, "g8e7"),
             "r1bqkb1r/ppppnppp/2n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w - - 12 8",
         ),
     ];
@@ -252,9 +263,10 @@ fn ruy_lopez() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(standard);
+        let mut board = Board::from_fen(standard).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -263,173 +275,174 @@ fn ruy_lopez() {
 fn game_of_the_century() {
     let position = "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2N2/P4PPP/3R1K1R w - - 4 18";
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
 
     let expected = vec![
         (
-            Move::from_str("d1c1"),
+            uci(position, "d1c1"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2N2/P4PPP/2R2K1R b - - 5 18",
         ),
         (
-            Move::from_str("d1b1"),
+            uci(position, "d1b1"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2N2/P4PPP/1R3K1R b - - 5 18",
         ),
         (
-            Move::from_str("d1a1"),
+            uci(position, "d1a1"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2N2/P4PPP/R4K1R b - - 5 18",
         ),
         (
-            Move::from_str("d1e1"),
+            uci(position, "d1e1"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2N2/P4PPP/4RK1R b - - 5 18",
         ),
         (
-            Move::from_str("d1d2"),
+            uci(position, "d1d2"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2N2/P2R1PPP/5K1R b - - 5 18",
         ),
         (
-            Move::from_str("d1d3"),
+            uci(position, "d1d3"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1nR1N2/P4PPP/5K1R b - - 5 18",
         ),
         (
-            Move::from_str("f1e1"),
+            uci(position, "f1e1"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2N2/P4PPP/3RK2R b - - 5 18",
         ),
         (
-            Move::from_str("f1g1"),
+            uci(position, "f1g1"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2N2/P4PPP/3R2KR b - - 5 18",
         ),
         (
-            Move::from_str("h1g1"),
+            uci(position, "h1g1"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2N2/P4PPP/3R1KR1 b - - 5 18",
         ),
         (
-            Move::from_str("g2g3"),
+            uci(position, "g2g3"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2NP1/P4P1P/3R1K1R b - - 0 18",
         ),
         (
-            Move::from_str("g2g4"),
+            uci(position, "g2g4"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP2P1/Q1n2N2/P4P1P/3R1K1R b - g3 0 18",
         ),
         (
-            Move::from_str("h2h3"),
+            uci(position, "h2h3"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n2N1P/P4PP1/3R1K1R b - - 0 18",
         ),
         (
-            Move::from_str("h2h4"),
+            uci(position, "h2h4"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP3P/Q1n2N2/P4PP1/3R1K1R b - h3 0 18",
         ),
         (
-            Move::from_str("a3b2"),
+            uci(position, "a3b2"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/2n2N2/PQ3PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("a3c1"),
+            uci(position, "a3c1"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/2n2N2/P4PPP/2QR1K1R b - - 5 18",
         ),
         (
-            Move::from_str("a3b3"),
+            uci(position, "a3b3"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/1Qn2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("a3c3"),
+            uci(position, "a3c3"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/2Q2N2/P4PPP/3R1K1R b - - 0 18",
         ),
         (
-            Move::from_str("a3a4"),
+            uci(position, "a3a4"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/Q1BP4/2n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("a3a5"),
+            uci(position, "a3a5"),
             "r3r1k1/pp3pbp/1qp1b1p1/Q1B5/2BP4/2n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("a3a6"),
+            uci(position, "a3a6"),
             "r3r1k1/pp3pbp/Qqp1b1p1/2B5/2BP4/2n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("a3a7"),
+            uci(position, "a3a7"),
             "r3r1k1/Qp3pbp/1qp1b1p1/2B5/2BP4/2n2N2/P4PPP/3R1K1R b - - 0 18",
         ),
         (
-            Move::from_str("a3b4"),
+            uci(position, "a3b4"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/1QBP4/2n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("f3e1"),
+            uci(position, "f3e1"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n5/P4PPP/3RNK1R b - - 5 18",
         ),
         (
-            Move::from_str("f3g1"),
+            uci(position, "f3g1"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n5/P4PPP/3R1KNR b - - 5 18",
         ),
         (
-            Move::from_str("f3d2"),
+            uci(position, "f3d2"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP4/Q1n5/P2N1PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("f3h4"),
+            uci(position, "f3h4"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/2BP3N/Q1n5/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("f3e5"),
+            uci(position, "f3e5"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B1N3/2BP4/Q1n5/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("f3g5"),
+            uci(position, "f3g5"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B3N1/2BP4/Q1n5/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("c4b3"),
+            uci(position, "c4b3"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/3P4/QBn2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("c4d3"),
+            uci(position, "c4d3"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/3P4/Q1nB1N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("c4e2"),
+            uci(position, "c4e2"),
             "r3r1k1/pp3pbp/1qp1b1p1/2B5/3P4/Q1n2N2/P3BPPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("c4b5"),
+            uci(position, "c4b5"),
             "r3r1k1/pp3pbp/1qp1b1p1/1BB5/3P4/Q1n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("c4a6"),
+            uci(position, "c4a6"),
             "r3r1k1/pp3pbp/Bqp1b1p1/2B5/3P4/Q1n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("c4d5"),
+            uci(position, "c4d5"),
             "r3r1k1/pp3pbp/1qp1b1p1/2BB4/3P4/Q1n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("c4e6"),
+            uci(position, "c4e6"),
             "r3r1k1/pp3pbp/1qp1B1p1/2B5/3P4/Q1n2N2/P4PPP/3R1K1R b - - 0 18",
         ),
         (
-            Move::from_str("d4d5"),
+            uci(position, "d4d5"),
             "r3r1k1/pp3pbp/1qp1b1p1/2BP4/2B5/Q1n2N2/P4PPP/3R1K1R b - - 0 18",
         ),
         (
-            Move::from_str("c5b4"),
+            uci(position, "c5b4"),
             "r3r1k1/pp3pbp/1qp1b1p1/8/1BBP4/Q1n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("c5b6"),
+            uci(position, "c5b6"),
             "r3r1k1/pp3pbp/1Bp1b1p1/8/2BP4/Q1n2N2/P4PPP/3R1K1R b - - 0 18",
         ),
         (
-            Move::from_str("c5d6"),
+            uci(position, "c5d6"),
             "r3r1k1/pp3pbp/1qpBb1p1/8/2BP4/Q1n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("c5e7"),
+            uci(position, "c5e7"),
             "r3r1k1/pp2Bpbp/1qp1b1p1/8/2BP4/Q1n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
         (
-            Move::from_str("c5f8"),
+            uci(position, "c5f8"),
             "r3rBk1/pp3pbp/1qp1b1p1/8/2BP4/Q1n2N2/P4PPP/3R1K1R b - - 5 18",
         ),
     ];
@@ -444,9 +457,10 @@ fn game_of_the_century() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -455,213 +469,214 @@ fn game_of_the_century() {
 fn gold_coins() {
     let position = "5rk1/pp4pp/4p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 b - - 0 1";
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
 
     let expected = vec![
         (
-            Move::from_str("c3b2"),
+            uci(position, "c3b2"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/7r/PqP2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3a1"),
+            uci(position, "c3a1"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/7r/P1P2PPP/q4RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3c2"),
+            uci(position, "c3c2"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/7r/P1q2PPP/5RK1 w - - 0 2",
         ),
         (
-            Move::from_str("c3d2"),
+            uci(position, "c3d2"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/7r/P1Pq1PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3e1"),
+            uci(position, "c3e1"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/7r/P1P2PPP/4qRK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3b3"),
+            uci(position, "c3b3"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/1q5r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3a3"),
+            uci(position, "c3a3"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/q6r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3d3"),
+            uci(position, "c3d3"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/3q3r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3e3"),
+            uci(position, "c3e3"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/4q2r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3f3"),
+            uci(position, "c3f3"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/5q1r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3g3"),
+            uci(position, "c3g3"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/6qr/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3b4"),
+            uci(position, "c3b4"),
             "5rk1/pp4pp/4p3/2R3Q1/1q1n4/7r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3a5"),
+            uci(position, "c3a5"),
             "5rk1/pp4pp/4p3/q1R3Q1/3n4/7r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3c4"),
+            uci(position, "c3c4"),
             "5rk1/pp4pp/4p3/2R3Q1/2qn4/7r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("c3c5"),
+            uci(position, "c3c5"),
             "5rk1/pp4pp/4p3/2q3Q1/3n4/7r/P1P2PPP/5RK1 w - - 0 2",
         ),
         (
-            Move::from_str("h3h2"),
+            uci(position, "h3h2"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/2q5/P1P2PPr/5RK1 w - - 0 2",
         ),
         (
-            Move::from_str("h3g3"),
+            uci(position, "h3g3"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/2q3r1/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("h3f3"),
+            uci(position, "h3f3"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/2q2r2/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("h3e3"),
+            uci(position, "h3e3"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/2q1r3/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("h3d3"),
+            uci(position, "h3d3"),
             "5rk1/pp4pp/4p3/2R3Q1/3n4/2qr4/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("h3h4"),
+            uci(position, "h3h4"),
             "5rk1/pp4pp/4p3/2R3Q1/3n3r/2q5/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("h3h5"),
+            uci(position, "h3h5"),
             "5rk1/pp4pp/4p3/2R3Qr/3n4/2q5/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("h3h6"),
+            uci(position, "h3h6"),
             "5rk1/pp4pp/4p2r/2R3Q1/3n4/2q5/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("d4c2"),
+            uci(position, "d4c2"),
             "5rk1/pp4pp/4p3/2R3Q1/8/2q4r/P1n2PPP/5RK1 w - - 0 2",
         ),
         (
-            Move::from_str("d4e2"),
+            uci(position, "d4e2"),
             "5rk1/pp4pp/4p3/2R3Q1/8/2q4r/P1P1nPPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("d4b3"),
+            uci(position, "d4b3"),
             "5rk1/pp4pp/4p3/2R3Q1/8/1nq4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("d4f3"),
+            uci(position, "d4f3"),
             "5rk1/pp4pp/4p3/2R3Q1/8/2q2n1r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("d4b5"),
+            uci(position, "d4b5"),
             "5rk1/pp4pp/4p3/1nR3Q1/8/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("d4f5"),
+            uci(position, "d4f5"),
             "5rk1/pp4pp/4p3/2R2nQ1/8/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("d4c6"),
+            uci(position, "d4c6"),
             "5rk1/pp4pp/2n1p3/2R3Q1/8/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("e6e5"),
+            uci(position, "e6e5"),
             "5rk1/pp4pp/8/2R1p1Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 0 2",
         ),
         (
-            Move::from_str("a7a6"),
+            uci(position, "a7a6"),
             "5rk1/1p4pp/p3p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 0 2",
         ),
         (
-            Move::from_str("a7a5"),
+            uci(position, "a7a5"),
             "5rk1/1p4pp/4p3/p1R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - a6 0 2",
         ),
         (
-            Move::from_str("b7b6"),
+            uci(position, "b7b6"),
             "5rk1/p5pp/1p2p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 0 2",
         ),
         (
-            Move::from_str("b7b5"),
+            uci(position, "b7b5"),
             "5rk1/p5pp/4p3/1pR3Q1/3n4/2q4r/P1P2PPP/5RK1 w - b6 0 2",
         ),
         (
-            Move::from_str("g7g6"),
+            uci(position, "g7g6"),
             "5rk1/pp5p/4p1p1/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 0 2",
         ),
         (
-            Move::from_str("h7h6"),
+            uci(position, "h7h6"),
             "5rk1/pp4p1/4p2p/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 0 2",
         ),
         (
-            Move::from_str("h7h5"),
+            uci(position, "h7h5"),
             "5rk1/pp4p1/4p3/2R3Qp/3n4/2q4r/P1P2PPP/5RK1 w - h6 0 2",
         ),
         (
-            Move::from_str("f8f7"),
+            uci(position, "f8f7"),
             "6k1/pp3rpp/4p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("f8f6"),
+            uci(position, "f8f6"),
             "6k1/pp4pp/4pr2/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("f8f5"),
+            uci(position, "f8f5"),
             "6k1/pp4pp/4p3/2R2rQ1/3n4/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("f8f4"),
+            uci(position, "f8f4"),
             "6k1/pp4pp/4p3/2R3Q1/3n1r2/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("f8f3"),
+            uci(position, "f8f3"),
             "6k1/pp4pp/4p3/2R3Q1/3n4/2q2r1r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("f8f2"),
+            uci(position, "f8f2"),
             "6k1/pp4pp/4p3/2R3Q1/3n4/2q4r/P1P2rPP/5RK1 w - - 0 2",
         ),
         (
-            Move::from_str("f8e8"),
+            uci(position, "f8e8"),
             "4r1k1/pp4pp/4p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("f8d8"),
+            uci(position, "f8d8"),
             "3r2k1/pp4pp/4p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("f8c8"),
+            uci(position, "f8c8"),
             "2r3k1/pp4pp/4p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("f8b8"),
+            uci(position, "f8b8"),
             "1r4k1/pp4pp/4p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("f8a8"),
+            uci(position, "f8a8"),
             "r5k1/pp4pp/4p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("g8f7"),
+            uci(position, "g8f7"),
             "5r2/pp3kpp/4p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
         (
-            Move::from_str("g8h8"),
+            uci(position, "g8h8"),
             "5r1k/pp4pp/4p3/2R3Q1/3n4/2q4r/P1P2PPP/5RK1 w - - 1 2",
         ),
     ];
@@ -675,9 +690,10 @@ fn gold_coins() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -686,18 +702,19 @@ fn gold_coins() {
 fn en_pessant_1() {
     let position = "7k/8/8/8/pPp5/8/8/7K b - b3 0 1";
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
 
     let expected = vec![
-        (Move::from_str("a4a3"), "7k/8/8/8/1Pp5/p7/8/7K w - - 0 2"),
-        (Move::from_str("a4b3"), "7k/8/8/8/2p5/1p6/8/7K w - - 0 2"),
-        (Move::from_str("c4c3"), "7k/8/8/8/pP6/2p5/8/7K w - - 0 2"),
-        (Move::from_str("c4b3"), "7k/8/8/8/p7/1p6/8/7K w - - 0 2"),
-        (Move::from_str("h8g7"), "8/6k1/8/8/pPp5/8/8/7K w - - 1 2"),
-        (Move::from_str("h8h7"), "8/7k/8/8/pPp5/8/8/7K w - - 1 2"),
-        (Move::from_str("h8g8"), "6k1/8/8/8/pPp5/8/8/7K w - - 1 2"),
+        (uci(position, "a4a3"), "7k/8/8/8/1Pp5/p7/8/7K w - - 0 2"),
+        (uci(position, "a4b3"), "7k/8/8/8/2p5/1p6/8/7K w - - 0 2"),
+        (uci(position, "c4c3"), "7k/8/8/8/pP6/2p5/8/7K w - - 0 2"),
+        (uci(position, "c4b3"), "7k/8/8/8/p7/1p6/8/7K w - - 0 2"),
+        (uci(position, "h8g7"), "8/6k1/8/8/pPp5/8/8/7K w - - 1 2"),
+        (uci(position, "h8h7"), "8/7k/8/8/pPp5/8/8/7K w - - 1 2"),
+        (uci(position, "h8g8"), "6k1/8/8/8/pPp5/8/8/7K w - - 1 2"),
     ];
 
     // Check that the generated moves match the expected ones.
@@ -709,9 +726,10 @@ fn en_pessant_1() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -720,18 +738,19 @@ fn en_pessant_1() {
 fn en_pessant_2() {
     let position = "7k/8/8/PpP5/8/8/8/7K w - b6 0 1";
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
 
     let expected = vec![
-        (Move::from_str("h1g1"), "7k/8/8/PpP5/8/8/8/6K1 b - - 1 1"),
-        (Move::from_str("h1g2"), "7k/8/8/PpP5/8/8/6K1/8 b - - 1 1"),
-        (Move::from_str("h1h2"), "7k/8/8/PpP5/8/8/7K/8 b - - 1 1"),
-        (Move::from_str("a5a6"), "7k/8/P7/1pP5/8/8/8/7K b - - 0 1"),
-        (Move::from_str("a5b6"), "7k/8/1P6/2P5/8/8/8/7K b - - 0 1"),
-        (Move::from_str("c5c6"), "7k/8/2P5/Pp6/8/8/8/7K b - - 0 1"),
-        (Move::from_str("c5b6"), "7k/8/1P6/P7/8/8/8/7K b - - 0 1"),
+        (uci(position, "h1g1"), "7k/8/8/PpP5/8/8/8/6K1 b - - 1 1"),
+        (uci(position, "h1g2"), "7k/8/8/PpP5/8/8/6K1/8 b - - 1 1"),
+        (uci(position, "h1h2"), "7k/8/8/PpP5/8/8/7K/8 b - - 1 1"),
+        (uci(position, "a5a6"), "7k/8/P7/1pP5/8/8/8/7K b - - 0 1"),
+        (uci(position, "a5b6"), "7k/8/1P6/2P5/8/8/8/7K b - - 0 1"),
+        (uci(position, "c5c6"), "7k/8/2P5/Pp6/8/8/8/7K b - - 0 1"),
+        (uci(position, "c5b6"), "7k/8/1P6/P7/8/8/8/7K b - - 0 1"),
     ];
 
     // Check that the generated moves match the expected ones.
@@ -743,9 +762,10 @@ fn en_pessant_2() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -754,20 +774,21 @@ fn en_pessant_2() {
 fn en_pessant_3() {
     let position = "8/8/4k3/8/2pPp3/8/B7/7K b - d3 0 1";
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
 
     let expected = vec![
-        (Move::from_str("e4e3"), "8/8/4k3/8/2pP4/4p3/B7/7K w - - 0 2"),
-        (Move::from_str("e4d3"), "8/8/4k3/8/2p5/3p4/B7/7K w - - 0 2"),
-        (Move::from_str("e6d5"), "8/8/8/3k4/2pPp3/8/B7/7K w - - 1 2"),
-        (Move::from_str("e6f5"), "8/8/8/5k2/2pPp3/8/B7/7K w - - 1 2"),
-        (Move::from_str("e6d6"), "8/8/3k4/8/2pPp3/8/B7/7K w - - 1 2"),
-        (Move::from_str("e6f6"), "8/8/5k2/8/2pPp3/8/B7/7K w - - 1 2"),
-        (Move::from_str("e6d7"), "8/3k4/8/8/2pPp3/8/B7/7K w - - 1 2"),
-        (Move::from_str("e6e7"), "8/4k3/8/8/2pPp3/8/B7/7K w - - 1 2"),
-        (Move::from_str("e6f7"), "8/5k2/8/8/2pPp3/8/B7/7K w - - 1 2"),
+        (uci(position, "e4e3"), "8/8/4k3/8/2pP4/4p3/B7/7K w - - 0 2"),
+        (uci(position, "e4d3"), "8/8/4k3/8/2p5/3p4/B7/7K w - - 0 2"),
+        (uci(position, "e6d5"), "8/8/8/3k4/2pPp3/8/B7/7K w - - 1 2"),
+        (uci(position, "e6f5"), "8/8/8/5k2/2pPp3/8/B7/7K w - - 1 2"),
+        (uci(position, "e6d6"), "8/8/3k4/8/2pPp3/8/B7/7K w - - 1 2"),
+        (uci(position, "e6f6"), "8/8/5k2/8/2pPp3/8/B7/7K w - - 1 2"),
+        (uci(position, "e6d7"), "8/3k4/8/8/2pPp3/8/B7/7K w - - 1 2"),
+        (uci(position, "e6e7"), "8/4k3/8/8/2pPp3/8/B7/7K w - - 1 2"),
+        (uci(position, "e6f7"), "8/5k2/8/8/2pPp3/8/B7/7K w - - 1 2"),
     ];
 
     // Check that the generated moves match the expected ones.
@@ -779,9 +800,10 @@ fn en_pessant_3() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -790,20 +812,21 @@ fn en_pessant_3() {
 fn en_pessant_4() {
     let position = "7k/b7/8/2PpP3/8/4K3/8/8 w - d6 0 1";
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
 
     let expected = vec![
-        (Move::from_str("e3d2"), "7k/b7/8/2PpP3/8/8/3K4/8 b - - 1 1"),
-        (Move::from_str("e3e2"), "7k/b7/8/2PpP3/8/8/4K3/8 b - - 1 1"),
-        (Move::from_str("e3f2"), "7k/b7/8/2PpP3/8/8/5K2/8 b - - 1 1"),
-        (Move::from_str("e3d3"), "7k/b7/8/2PpP3/8/3K4/8/8 b - - 1 1"),
-        (Move::from_str("e3f3"), "7k/b7/8/2PpP3/8/5K2/8/8 b - - 1 1"),
-        (Move::from_str("e3d4"), "7k/b7/8/2PpP3/3K4/8/8/8 b - - 1 1"),
-        (Move::from_str("e3f4"), "7k/b7/8/2PpP3/5K2/8/8/8 b - - 1 1"),
-        (Move::from_str("e5e6"), "7k/b7/4P3/2Pp4/8/4K3/8/8 b - - 0 1"),
-        (Move::from_str("e5d6"), "7k/b7/3P4/2P5/8/4K3/8/8 b - - 0 1"),
+        (uci(position, "e3d2"), "7k/b7/8/2PpP3/8/8/3K4/8 b - - 1 1"),
+        (uci(position, "e3e2"), "7k/b7/8/2PpP3/8/8/4K3/8 b - - 1 1"),
+        (uci(position, "e3f2"), "7k/b7/8/2PpP3/8/8/5K2/8 b - - 1 1"),
+        (uci(position, "e3d3"), "7k/b7/8/2PpP3/8/3K4/8/8 b - - 1 1"),
+        (uci(position, "e3f3"), "7k/b7/8/2PpP3/8/5K2/8/8 b - - 1 1"),
+        (uci(position, "e3d4"), "7k/b7/8/2PpP3/3K4/8/8/8 b - - 1 1"),
+        (uci(position, "e3f4"), "7k/b7/8/2PpP3/5K2/8/8/8 b - - 1 1"),
+        (uci(position, "e5e6"), "7k/b7/4P3/2Pp4/8/4K3/8/8 b - - 0 1"),
+        (uci(position, "e5d6"), "7k/b7/3P4/2P5/8/4K3/8/8 b - - 0 1"),
     ];
 
     // Check that the generated moves match the expected ones.
@@ -815,9 +838,10 @@ fn en_pessant_4() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -825,43 +849,43 @@ fn en_pessant_4() {
 #[test]
 fn checkmate_1() {
     let position = "1R3k2/2R5/8/8/8/1K6/8/8 b - - 0 1 ";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 
 #[test]
 fn checkmate_2() {
     let position = "8/8/1k6/8/8/8/2r5/1r3K2 w - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 
 #[test]
 fn checkmate_3() {
     let position = "8/6N1/3R4/6k1/5Pp1/1K2P3/8/4B1R1 b - f3 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 
 #[test]
 fn checkmate_4() {
     let position = "4b1r1/8/1k2p3/5pP1/6K1/3r4/6n1/8 w - f6 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 
 #[test]
 fn checkmate_5() {
     let position = "kr6/ppN5/8/8/8/8/2K5/8 b - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 
 #[test]
 fn checkmate_6() {
     let position = "8/2k5/8/8/8/8/PPn5/KR6 w - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 
 #[test]
 fn checkmate_7() {
     let position = "k1K5/p1N5/8/8/8/8/8/8 b - - 0 1";
-    let moves = Board::from_fen(position).generate_moves();
+    let moves = Board::from_fen(position).unwrap().generate_moves();
     for m in moves.clone() {
         println!("{m}");
     }
@@ -871,75 +895,75 @@ fn checkmate_7() {
 #[test]
 fn checkmate_8() {
     let position = "8/8/8/8/8/8/P1n5/K1k5 w - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 
 #[test]
 fn stalemate_1() {
     let position = "8/8/8/8/8/8/P1n5/K1k5 w - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 
 #[test]
 fn stalemate_2() {
     let position = "k7/1R6/2K5/8/8/8/8/8 b - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 #[test]
 fn stalemate_3() {
     let position = "8/8/8/8/8/2k5/1r6/K7 w - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 #[test]
 fn stalemate_4() {
     let position = "k7/8/2N5/8/8/2K5/1R6/8 b - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 #[test]
 fn stalemate_5() {
     let position = "8/1r6/2k5/8/8/2n5/8/K7 w - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 #[test]
 fn stalemate_6() {
     let position = "k7/2Q5/8/8/8/2K5/8/8 b - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 #[test]
 fn stalemate_7() {
     let position = "8/8/5R2/4k1P1/3R4/2K5/8/8 b - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 #[test]
 fn stalemate_8() {
     let position = "8/8/2k5/8/8/8/2q5/K7 w - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 #[test]
 fn stalemate_9() {
     let position = "8/8/2k5/3r4/4K1p1/5r2/8/8 w - - 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 #[test]
 fn stalemate_10() {
     let position = "5bnr/4p1pq/4Qpkr/7p/7P/4P3/PPPP1PP1/RNB1KBNR b KQ - 2 10";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 #[test]
 fn stalemate_11() {
     let position = "rnb1kbnr/pppp1pp1/4p3/7p/7P/4qPKR/4P1PQ/5BNR w kq - 2 10";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 #[test]
 fn stalemate_12() {
     let position = "8/8/R7/4k3/4Pp2/2P2P2/7B/1K6 b - e3 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 
 #[test]
 fn stalemate_13() {
     let position = "1k6/7b/2p2p2/4pP2/4K3/r7/8/8 w - e6 0 1";
-    assert!(Board::from_fen(position).generate_moves().len() == 0);
+    assert!(Board::from_fen(position).unwrap().generate_moves().len() == 0);
 }
 
 #[test]
@@ -947,21 +971,22 @@ fn castling_1() {
     let position = "8/4k3/8/8/8/8/r6r/R3K2R w KQ - 0 1";
 
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
     let expected = vec![
-        (Move::from_str("a1b1"), "8/4k3/8/8/8/8/r6r/1R2K2R b K - 1 1"),
-        (Move::from_str("a1c1"), "8/4k3/8/8/8/8/r6r/2R1K2R b K - 1 1"),
-        (Move::from_str("a1d1"), "8/4k3/8/8/8/8/r6r/3RK2R b K - 1 1"),
-        (Move::from_str("a1a2"), "8/4k3/8/8/8/8/R6r/4K2R b K - 0 1"),
-        (Move::from_str("e1d1"), "8/4k3/8/8/8/8/r6r/R2K3R b - - 1 1"),
-        (Move::from_str("e1f1"), "8/4k3/8/8/8/8/r6r/R4K1R b - - 1 1"),
-        (Move::from_str("e1c1"), "8/4k3/8/8/8/8/r6r/2KR3R b - - 1 1"),
-        (Move::from_str("e1g1"), "8/4k3/8/8/8/8/r6r/R4RK1 b - - 1 1"),
-        (Move::from_str("h1g1"), "8/4k3/8/8/8/8/r6r/R3K1R1 b Q - 1 1"),
-        (Move::from_str("h1f1"), "8/4k3/8/8/8/8/r6r/R3KR2 b Q - 1 1"),
-        (Move::from_str("h1h2"), "8/4k3/8/8/8/8/r6R/R3K3 b Q - 0 1"),
+        (uci(position, "a1b1"), "8/4k3/8/8/8/8/r6r/1R2K2R b K - 1 1"),
+        (uci(position, "a1c1"), "8/4k3/8/8/8/8/r6r/2R1K2R b K - 1 1"),
+        (uci(position, "a1d1"), "8/4k3/8/8/8/8/r6r/3RK2R b K - 1 1"),
+        (uci(position, "a1a2"), "8/4k3/8/8/8/8/R6r/4K2R b K - 0 1"),
+        (uci(position, "e1d1"), "8/4k3/8/8/8/8/r6r/R2K3R b - - 1 1"),
+        (uci(position, "e1f1"), "8/4k3/8/8/8/8/r6r/R4K1R b - - 1 1"),
+        (uci(position, "e1c1"), "8/4k3/8/8/8/8/r6r/2KR3R b - - 1 1"),
+        (uci(position, "e1g1"), "8/4k3/8/8/8/8/r6r/R4RK1 b - - 1 1"),
+        (uci(position, "h1g1"), "8/4k3/8/8/8/8/r6r/R3K1R1 b Q - 1 1"),
+        (uci(position, "h1f1"), "8/4k3/8/8/8/8/r6r/R3KR2 b Q - 1 1"),
+        (uci(position, "h1h2"), "8/4k3/8/8/8/8/r6R/R3K3 b Q - 0 1"),
     ];
 
     // Check that the generated moves match the expected ones.
@@ -976,9 +1001,10 @@ fn castling_1() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -988,21 +1014,22 @@ fn castling_2() {
     let position = "r3k2r/R6R/8/8/8/8/4K3/8 b kq - 0 1";
 
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
     let expected = vec![
-        (Move::from_str("a8a7"), "4k2r/r6R/8/8/8/8/4K3/8 w k - 0 2"),
-        (Move::from_str("a8b8"), "1r2k2r/R6R/8/8/8/8/4K3/8 w k - 1 2"),
-        (Move::from_str("a8c8"), "2r1k2r/R6R/8/8/8/8/4K3/8 w k - 1 2"),
-        (Move::from_str("a8d8"), "3rk2r/R6R/8/8/8/8/4K3/8 w k - 1 2"),
-        (Move::from_str("e8d8"), "r2k3r/R6R/8/8/8/8/4K3/8 w - - 1 2"),
-        (Move::from_str("e8f8"), "r4k1r/R6R/8/8/8/8/4K3/8 w - - 1 2"),
-        (Move::from_str("e8c8"), "2kr3r/R6R/8/8/8/8/4K3/8 w - - 1 2"),
-        (Move::from_str("e8g8"), "r4rk1/R6R/8/8/8/8/4K3/8 w - - 1 2"),
-        (Move::from_str("h8h7"), "r3k3/R6r/8/8/8/8/4K3/8 w q - 0 2"),
-        (Move::from_str("h8g8"), "r3k1r1/R6R/8/8/8/8/4K3/8 w q - 1 2"),
-        (Move::from_str("h8f8"), "r3kr2/R6R/8/8/8/8/4K3/8 w q - 1 2"),
+        (uci(position, "a8a7"), "4k2r/r6R/8/8/8/8/4K3/8 w k - 0 2"),
+        (uci(position, "a8b8"), "1r2k2r/R6R/8/8/8/8/4K3/8 w k - 1 2"),
+        (uci(position, "a8c8"), "2r1k2r/R6R/8/8/8/8/4K3/8 w k - 1 2"),
+        (uci(position, "a8d8"), "3rk2r/R6R/8/8/8/8/4K3/8 w k - 1 2"),
+        (uci(position, "e8d8"), "r2k3r/R6R/8/8/8/8/4K3/8 w - - 1 2"),
+        (uci(position, "e8f8"), "r4k1r/R6R/8/8/8/8/4K3/8 w - - 1 2"),
+        (uci(position, "e8c8"), "2kr3r/R6R/8/8/8/8/4K3/8 w - - 1 2"),
+        (uci(position, "e8g8"), "r4rk1/R6R/8/8/8/8/4K3/8 w - - 1 2"),
+        (uci(position, "h8h7"), "r3k3/R6r/8/8/8/8/4K3/8 w q - 0 2"),
+        (uci(position, "h8g8"), "r3k1r1/R6R/8/8/8/8/4K3/8 w q - 1 2"),
+        (uci(position, "h8f8"), "r3kr2/R6R/8/8/8/8/4K3/8 w q - 1 2"),
     ];
 
     // Check that the generated moves match the expected ones.
@@ -1014,9 +1041,10 @@ fn castling_2() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -1026,20 +1054,21 @@ fn castling_3() {
     let position = "8/4k3/8/8/8/8/r6r/R3K2R w Q - 0 1";
 
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
     let expected = vec![
-        (Move::from_str("a1b1"), "8/4k3/8/8/8/8/r6r/1R2K2R b - - 1 1"),
-        (Move::from_str("a1c1"), "8/4k3/8/8/8/8/r6r/2R1K2R b - - 1 1"),
-        (Move::from_str("a1d1"), "8/4k3/8/8/8/8/r6r/3RK2R b - - 1 1"),
-        (Move::from_str("a1a2"), "8/4k3/8/8/8/8/R6r/4K2R b - - 0 1"),
-        (Move::from_str("e1d1"), "8/4k3/8/8/8/8/r6r/R2K3R b - - 1 1"),
-        (Move::from_str("e1f1"), "8/4k3/8/8/8/8/r6r/R4K1R b - - 1 1"),
-        (Move::from_str("e1c1"), "8/4k3/8/8/8/8/r6r/2KR3R b - - 1 1"),
-        (Move::from_str("h1g1"), "8/4k3/8/8/8/8/r6r/R3K1R1 b Q - 1 1"),
-        (Move::from_str("h1f1"), "8/4k3/8/8/8/8/r6r/R3KR2 b Q - 1 1"),
-        (Move::from_str("h1h2"), "8/4k3/8/8/8/8/r6R/R3K3 b Q - 0 1"),
+        (uci(position, "a1b1"), "8/4k3/8/8/8/8/r6r/1R2K2R b - - 1 1"),
+        (uci(position, "a1c1"), "8/4k3/8/8/8/8/r6r/2R1K2R b - - 1 1"),
+        (uci(position, "a1d1"), "8/4k3/8/8/8/8/r6r/3RK2R b - - 1 1"),
+        (uci(position, "a1a2"), "8/4k3/8/8/8/8/R6r/4K2R b - - 0 1"),
+        (uci(position, "e1d1"), "8/4k3/8/8/8/8/r6r/R2K3R b - - 1 1"),
+        (uci(position, "e1f1"), "8/4k3/8/8/8/8/r6r/R4K1R b - - 1 1"),
+        (uci(position, "e1c1"), "8/4k3/8/8/8/8/r6r/2KR3R b - - 1 1"),
+        (uci(position, "h1g1"), "8/4k3/8/8/8/8/r6r/R3K1R1 b Q - 1 1"),
+        (uci(position, "h1f1"), "8/4k3/8/8/8/8/r6r/R3KR2 b Q - 1 1"),
+        (uci(position, "h1h2"), "8/4k3/8/8/8/8/r6R/R3K3 b Q - 0 1"),
     ];
 
     // Check that the generated moves match the expected ones.
@@ -1051,9 +1080,10 @@ fn castling_3() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -1063,89 +1093,42 @@ fn castling_4() {
     let position = "8/8/4k3/8/8/8/2p3p1/R3K2R w KQ - 0 1";
 
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
     let expected = vec![
         (
-            Move::from_str("a1b1"),
+            uci(position, "a1b1"),
             "8/8/4k3/8/8/8/2p3p1/1R2K2R b K - 1 1",
         ),
         (
-            Move::from_str("a1c1"),
+            uci(position, "a1c1"),
             "8/8/4k3/8/8/8/2p3p1/2R1K2R b K - 1 1",
         ),
-        (
-            Move::from_str("a1d1"),
-            "8/8/4k3/8/8/8/2p3p1/3RK2R b K - 1 1",
-        ),
-        (
-            Move::from_str("a1a2"),
-            "8/8/4k3/8/8/8/R1p3p1/4K2R b K - 1 1",
-        ),
-        (
-            Move::from_str("a1a3"),
-            "8/8/4k3/8/8/R7/2p3p1/4K2R b K - 1 1",
-        ),
-        (
-            Move::from_str("a1a4"),
-            "8/8/4k3/8/R7/8/2p3p1/4K2R b K - 1 1",
-        ),
-        (
-            Move::from_str("a1a5"),
-            "8/8/4k3/R7/8/8/2p3p1/4K2R b K - 1 1",
-        ),
-        (
-            Move::from_str("a1a6"),
-            "8/8/R3k3/8/8/8/2p3p1/4K2R b K - 1 1",
-        ),
-        (
-            Move::from_str("a1a7"),
-            "8/R7/4k3/8/8/8/2p3p1/4K2R b K - 1 1",
-        ),
-        (
-            Move::from_str("a1a8"),
-            "R7/8/4k3/8/8/8/2p3p1/4K2R b K - 1 1",
-        ),
-        (Move::from_str("e1d2"), "8/8/4k3/8/8/8/2pK2p1/R6R b - - 1 1"),
-        (
-            Move::from_str("e1e2"),
-            "8/8/4k3/8/8/8/2p1K1p1/R6R b - - 1 1",
-        ),
-        (Move::from_str("e1f2"), "8/8/4k3/8/8/8/2p2Kp1/R6R b - - 1 1"),
-        (
-            Move::from_str("h1g1"),
+        (uci(position, "a1d1"), "8/8/4k3/8/8/8/2p3p1/3RK2R b K - 1 1"),
+        (uci(position, "a1a2"), "8/8/4k3/8/8/8/R1p3p1/4K2R b K - 1 1"),
+        (uci(position, "a1a3"), "8/8/4k3/8/8/R7/2p3p1/4K2R b K - 1 1"),
+        (uci(position, "a1a4"), "8/8/4k3/8/R7/8/2p3p1/4K2R b K - 1 1"),
+        (uci(position, "a1a5"), "8/8/4k3/R7/8/8/2p3p1/4K2R b K - 1 1"),
+        (uci(position, "a1a6"), "8/8/R3k3/8/8/8/2p3p1/4K2R b K - 1 1"),
+        (uci(position, "a1a7"), "8/R7/4k3/8/8/8/2p3p1/4K2R b K - 1 1"),
+        (uci(position, "a1a8"), "R7/8/4k3/8/8/8/2p3p1/4K2R b K - 1 1"),
+        (uci(position, "e1d2"), "8/8/4k3/8/8/8/2pK2p1/R6R b - - 1 1"),
+        (uci(position, "e1e2"), "8/8/4k3/8/8/8/2p1K1p1/R6R b - - 1 1"),
+        (uci(position, "e1f2"), "8/8/4k3/8/8/8/2p2Kp1/R6R b - - 1 1"),
+        (
+            uci(position, "h1g1"),
             "8/8/4k3/8/8/8/2p3p1/R3K1R1 b Q - 1 1",
         ),
-        (
-            Move::from_str("h1f1"),
-            "8/8/4k3/8/8/8/2p3p1/R3KR2 b Q - 1 1",
-        ),
-        (Move::from_str("h1h2"), "8/8/4k3/8/8/8/2p3pR/R3K3 b Q - 1 1"),
-        (
-            Move::from_str("h1h3"),
-            "8/8/4k3/8/8/7R/2p3p1/R3K3 b Q - 1 1",
-        ),
-        (
-            Move::from_str("h1h4"),
-            "8/8/4k3/8/7R/8/2p3p1/R3K3 b Q - 1 1",
-        ),
-        (
-            Move::from_str("h1h5"),
-            "8/8/4k3/7R/8/8/2p3p1/R3K3 b Q - 1 1",
-        ),
-        (
-            Move::from_str("h1h6"),
-            "8/8/4k2R/8/8/8/2p3p1/R3K3 b Q - 1 1",
-        ),
-        (
-            Move::from_str("h1h7"),
-            "8/7R/4k3/8/8/8/2p3p1/R3K3 b Q - 1 1",
-        ),
-        (
-            Move::from_str("h1h8"),
-            "7R/8/4k3/8/8/8/2p3p1/R3K3 b Q - 1 1",
-        ),
+        (uci(position, "h1f1"), "8/8/4k3/8/8/8/2p3p1/R3KR2 b Q - 1 1"),
+        (uci(position, "h1h2"), "8/8/4k3/8/8/8/2p3pR/R3K3 b Q - 1 1"),
+        (uci(position, "h1h3"), "8/8/4k3/8/8/7R/2p3p1/R3K3 b Q - 1 1"),
+        (uci(position, "h1h4"), "8/8/4k3/8/7R/8/2p3p1/R3K3 b Q - 1 1"),
+        (uci(position, "h1h5"), "8/8/4k3/7R/8/8/2p3p1/R3K3 b Q - 1 1"),
+        (uci(position, "h1h6"), "8/8/4k2R/8/8/8/2p3p1/R3K3 b Q - 1 1"),
+        (uci(position, "h1h7"), "8/7R/4k3/8/8/8/2p3p1/R3K3 b Q - 1 1"),
+        (uci(position, "h1h8"), "7R/8/4k3/8/8/8/2p3p1/R3K3 b Q - 1 1"),
     ];
 
     // Check that the generated moves match the expected ones.
@@ -1157,9 +1140,10 @@ fn castling_4() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -1169,168 +1153,169 @@ fn castling_5() {
     let position = "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1N1PP/RNBQK2n w Q - 1 8";
 
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
     let expected = vec![
         (
-            Move::from_str("b1d2"),
+            uci(position, "b1d2"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPPNN1PP/R1BQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("b1a3"),
+            uci(position, "b1a3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/N7/PPP1N1PP/R1BQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("b1c3"),
+            uci(position, "b1c3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/2N5/PPP1N1PP/R1BQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c1d2"),
+            uci(position, "c1d2"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPPBN1PP/RN1QK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c1e3"),
+            uci(position, "c1e3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/4B3/PPP1N1PP/RN1QK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c1f4"),
+            uci(position, "c1f4"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B2B2/8/PPP1N1PP/RN1QK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c1g5"),
+            uci(position, "c1g5"),
             "rnbq1k1r/pp1Pbppp/2p5/6B1/2B5/8/PPP1N1PP/RN1QK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c1h6"),
+            uci(position, "c1h6"),
             "rnbq1k1r/pp1Pbppp/2p4B/8/2B5/8/PPP1N1PP/RN1QK2n b Q - 2 8",
         ),
         (
-            Move::from_str("d1d2"),
+            uci(position, "d1d2"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPPQN1PP/RNB1K2n b Q - 2 8",
         ),
         (
-            Move::from_str("d1d3"),
+            uci(position, "d1d3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/3Q4/PPP1N1PP/RNB1K2n b Q - 2 8",
         ),
         (
-            Move::from_str("d1d4"),
+            uci(position, "d1d4"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2BQ4/8/PPP1N1PP/RNB1K2n b Q - 2 8",
         ),
         (
-            Move::from_str("d1d5"),
+            uci(position, "d1d5"),
             "rnbq1k1r/pp1Pbppp/2p5/3Q4/2B5/8/PPP1N1PP/RNB1K2n b Q - 2 8",
         ),
         (
-            Move::from_str("d1d6"),
+            uci(position, "d1d6"),
             "rnbq1k1r/pp1Pbppp/2pQ4/8/2B5/8/PPP1N1PP/RNB1K2n b Q - 2 8",
         ),
         (
-            Move::from_str("e1f1"),
+            uci(position, "e1f1"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1N1PP/RNBQ1K1n b - - 2 8",
         ),
         (
-            Move::from_str("e1d2"),
+            uci(position, "e1d2"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPPKN1PP/RNBQ3n b - - 2 8",
         ),
         (
-            Move::from_str("a2a3"),
+            uci(position, "a2a3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/P7/1PP1N1PP/RNBQK2n b Q - 0 8",
         ),
         (
-            Move::from_str("a2a4"),
+            uci(position, "a2a4"),
             "rnbq1k1r/pp1Pbppp/2p5/8/P1B5/8/1PP1N1PP/RNBQK2n b Q a3 0 8",
         ),
         (
-            Move::from_str("b2b3"),
+            uci(position, "b2b3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/1P6/P1P1N1PP/RNBQK2n b Q - 0 8",
         ),
         (
-            Move::from_str("b2b4"),
+            uci(position, "b2b4"),
             "rnbq1k1r/pp1Pbppp/2p5/8/1PB5/8/P1P1N1PP/RNBQK2n b Q b3 0 8",
         ),
         (
-            Move::from_str("c2c3"),
+            uci(position, "c2c3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/2P5/PP2N1PP/RNBQK2n b Q - 0 8",
         ),
         (
-            Move::from_str("e2g1"),
+            uci(position, "e2g1"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP3PP/RNBQK1Nn b Q - 2 8",
         ),
         (
-            Move::from_str("e2c3"),
+            uci(position, "e2c3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/2N5/PPP3PP/RNBQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("e2g3"),
+            uci(position, "e2g3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/6N1/PPP3PP/RNBQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("e2d4"),
+            uci(position, "e2d4"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2BN4/8/PPP3PP/RNBQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("e2f4"),
+            uci(position, "e2f4"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B2N2/8/PPP3PP/RNBQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("g2g3"),
+            uci(position, "g2g3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/6P1/PPP1N2P/RNBQK2n b Q - 0 8",
         ),
         (
-            Move::from_str("g2g4"),
+            uci(position, "g2g4"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B3P1/8/PPP1N2P/RNBQK2n b Q g3 0 8",
         ),
         (
-            Move::from_str("h2h3"),
+            uci(position, "h2h3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B5/7P/PPP1N1P1/RNBQK2n b Q - 0 8",
         ),
         (
-            Move::from_str("h2h4"),
+            uci(position, "h2h4"),
             "rnbq1k1r/pp1Pbppp/2p5/8/2B4P/8/PPP1N1P1/RNBQK2n b Q h3 0 8",
         ),
         (
-            Move::from_str("c4b3"),
+            uci(position, "c4b3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/8/1B6/PPP1N1PP/RNBQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c4d3"),
+            uci(position, "c4d3"),
             "rnbq1k1r/pp1Pbppp/2p5/8/8/3B4/PPP1N1PP/RNBQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c4b5"),
+            uci(position, "c4b5"),
             "rnbq1k1r/pp1Pbppp/2p5/1B6/8/8/PPP1N1PP/RNBQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c4a6"),
+            uci(position, "c4a6"),
             "rnbq1k1r/pp1Pbppp/B1p5/8/8/8/PPP1N1PP/RNBQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c4d5"),
+            uci(position, "c4d5"),
             "rnbq1k1r/pp1Pbppp/2p5/3B4/8/8/PPP1N1PP/RNBQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c4e6"),
+            uci(position, "c4e6"),
             "rnbq1k1r/pp1Pbppp/2p1B3/8/8/8/PPP1N1PP/RNBQK2n b Q - 2 8",
         ),
         (
-            Move::from_str("c4f7"),
+            uci(position, "c4f7"),
             "rnbq1k1r/pp1PbBpp/2p5/8/8/8/PPP1N1PP/RNBQK2n b Q - 0 8",
         ),
         (
-            Move::from_str("d7c8Q"),
+            uci(position, "d7c8Q"),
             "rnQq1k1r/pp2bppp/2p5/8/2B5/8/PPP1N1PP/RNBQK2n b Q - 0 8",
         ),
         (
-            Move::from_str("d7c8R"),
+            uci(position, "d7c8R"),
             "rnRq1k1r/pp2bppp/2p5/8/2B5/8/PPP1N1PP/RNBQK2n b Q - 0 8",
         ),
         (
-            Move::from_str("d7c8N"),
+            uci(position, "d7c8N"),
             "rnNq1k1r/pp2bppp/2p5/8/2B5/8/PPP1N1PP/RNBQK2n b Q - 0 8",
         ),
         (
-            Move::from_str("d7c8B"),
+            uci(position, "d7c8B"),
             "rnBq1k1r/pp2bppp/2p5/8/2B5/8/PPP1N1PP/RNBQK2n b Q - 0 8",
         ),
     ];
@@ -1344,29 +1329,46 @@ fn castling_5() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
+
+/// Castling rights without the rook in place must not produce a castling move.
+#[test]
+fn castling_without_rook() {
+    let position = "4k3/8/8/8/8/8/8/4K3 w K - 0 1";
+    let board = Board::from_fen(position).unwrap();
+    let moves = board.generate_moves();
+    assert_eq!(moves.len(), 5);
+    assert!(moves.iter().all(|m| m.kind() != MoveKind::KingCastle));
+    assert!(!board.is_legal(Move::new(4, 6, MoveKind::KingCastle)));
+    for r#move in moves {
+        let mut after = board;
+        after.make(r#move);
+    }
+}
 #[test]
 fn promotion_1() {
     let position = "1k6/5P2/8/8/8/8/8/4K3 w - - 20 1";
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
     let expected = vec![
-        (Move::from_str("e1d1"), "1k6/5P2/8/8/8/8/8/3K4 b - - 21 1"),
-        (Move::from_str("e1f1"), "1k6/5P2/8/8/8/8/8/5K2 b - - 21 1"),
-        (Move::from_str("e1d2"), "1k6/5P2/8/8/8/8/3K4/8 b - - 21 1"),
-        (Move::from_str("e1e2"), "1k6/5P2/8/8/8/8/4K3/8 b - - 21 1"),
-        (Move::from_str("e1f2"), "1k6/5P2/8/8/8/8/5K2/8 b - - 21 1"),
-        (Move::from_str("f7f8Q"), "1k3Q2/8/8/8/8/8/8/4K3 b - - 0 1"),
-        (Move::from_str("f7f8R"), "1k3R2/8/8/8/8/8/8/4K3 b - - 0 1"),
-        (Move::from_str("f7f8N"), "1k3N2/8/8/8/8/8/8/4K3 b - - 0 1"),
-        (Move::from_str("f7f8B"), "1k3B2/8/8/8/8/8/8/4K3 b - - 0 1"),
+        (uci(position, "e1d1"), "1k6/5P2/8/8/8/8/8/3K4 b - - 21 1"),
+        (uci(position, "e1f1"), "1k6/5P2/8/8/8/8/8/5K2 b - - 21 1"),
+        (uci(position, "e1d2"), "1k6/5P2/8/8/8/8/3K4/8 b - - 21 1"),
+        (uci(position, "e1e2"), "1k6/5P2/8/8/8/8/4K3/8 b - - 21 1"),
+        (uci(position, "e1f2"), "1k6/5P2/8/8/8/8/5K2/8 b - - 21 1"),
+        (uci(position, "f7f8Q"), "1k3Q2/8/8/8/8/8/8/4K3 b - - 0 1"),
+        (uci(position, "f7f8R"), "1k3R2/8/8/8/8/8/8/4K3 b - - 0 1"),
+        (uci(position, "f7f8N"), "1k3N2/8/8/8/8/8/8/4K3 b - - 0 1"),
+        (uci(position, "f7f8B"), "1k3B2/8/8/8/8/8/8/4K3 b - - 0 1"),
     ];
     // Check that the generated moves match the expected ones.
     let expected_moves: HashSet<Move> = expected.iter().map(|(r#move, _)| *r#move).collect();
@@ -1377,9 +1379,10 @@ fn promotion_1() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -1387,19 +1390,20 @@ fn promotion_1() {
 fn promotion_2() {
     let position = "4k3/8/8/8/8/8/5p2/1K6 b - - 20 1";
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
     let expected = vec![
-        (Move::from_str("f2f1q"), "4k3/8/8/8/8/8/8/1K3q2 w - - 0 2"),
-        (Move::from_str("f2f1r"), "4k3/8/8/8/8/8/8/1K3r2 w - - 0 2"),
-        (Move::from_str("f2f1n"), "4k3/8/8/8/8/8/8/1K3n2 w - - 0 2"),
-        (Move::from_str("f2f1b"), "4k3/8/8/8/8/8/8/1K3b2 w - - 0 2"),
-        (Move::from_str("e8d7"), "8/3k4/8/8/8/8/5p2/1K6 w - - 21 2"),
-        (Move::from_str("e8e7"), "8/4k3/8/8/8/8/5p2/1K6 w - - 21 2"),
-        (Move::from_str("e8f7"), "8/5k2/8/8/8/8/5p2/1K6 w - - 21 2"),
-        (Move::from_str("e8d8"), "3k4/8/8/8/8/8/5p2/1K6 w - - 21 2"),
-        (Move::from_str("e8f8"), "5k2/8/8/8/8/8/5p2/1K6 w - - 21 2"),
+        (uci(position, "f2f1q"), "4k3/8/8/8/8/8/8/1K3q2 w - - 0 2"),
+        (uci(position, "f2f1r"), "4k3/8/8/8/8/8/8/1K3r2 w - - 0 2"),
+        (uci(position, "f2f1n"), "4k3/8/8/8/8/8/8/1K3n2 w - - 0 2"),
+        (uci(position, "f2f1b"), "4k3/8/8/8/8/8/8/1K3b2 w - - 0 2"),
+        (uci(position, "e8d7"), "8/3k4/8/8/8/8/5p2/1K6 w - - 21 2"),
+        (uci(position, "e8e7"), "8/4k3/8/8/8/8/5p2/1K6 w - - 21 2"),
+        (uci(position, "e8f7"), "8/5k2/8/8/8/8/5p2/1K6 w - - 21 2"),
+        (uci(position, "e8d8"), "3k4/8/8/8/8/8/5p2/1K6 w - - 21 2"),
+        (uci(position, "e8f8"), "5k2/8/8/8/8/8/5p2/1K6 w - - 21 2"),
     ];
     // Check that the generated moves match the expected ones.
     let expected_moves: HashSet<Move> = expected.iter().map(|(r#move, _)| *r#move).collect();
@@ -1410,9 +1414,10 @@ fn promotion_2() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
@@ -1420,216 +1425,217 @@ fn promotion_2() {
 fn promotion_3() {
     let position = "3k4/8/1K6/8/8/8/pppppppp/RRRRRRRR b - - 0 1";
     let generated_moves: HashSet<Move> = Board::from_fen(position)
+        .unwrap()
         .generate_moves()
         .into_iter()
         .collect();
     let expected = vec![
         (
-            Move::from_str("a2b1q"),
+            uci(position, "a2b1q"),
             "3k4/8/1K6/8/8/8/1ppppppp/RqRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("a2b1r"),
+            uci(position, "a2b1r"),
             "3k4/8/1K6/8/8/8/1ppppppp/RrRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("a2b1n"),
+            uci(position, "a2b1n"),
             "3k4/8/1K6/8/8/8/1ppppppp/RnRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("a2b1b"),
+            uci(position, "a2b1b"),
             "3k4/8/1K6/8/8/8/1ppppppp/RbRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("b2a1q"),
+            uci(position, "b2a1q"),
             "3k4/8/1K6/8/8/8/p1pppppp/qRRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("b2a1r"),
+            uci(position, "b2a1r"),
             "3k4/8/1K6/8/8/8/p1pppppp/rRRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("b2a1n"),
+            uci(position, "b2a1n"),
             "3k4/8/1K6/8/8/8/p1pppppp/nRRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("b2a1b"),
+            uci(position, "b2a1b"),
             "3k4/8/1K6/8/8/8/p1pppppp/bRRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("b2c1q"),
+            uci(position, "b2c1q"),
             "3k4/8/1K6/8/8/8/p1pppppp/RRqRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("b2c1r"),
+            uci(position, "b2c1r"),
             "3k4/8/1K6/8/8/8/p1pppppp/RRrRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("b2c1n"),
+            uci(position, "b2c1n"),
             "3k4/8/1K6/8/8/8/p1pppppp/RRnRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("b2c1b"),
+            uci(position, "b2c1b"),
             "3k4/8/1K6/8/8/8/p1pppppp/RRbRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("c2b1q"),
+            uci(position, "c2b1q"),
             "3k4/8/1K6/8/8/8/pp1ppppp/RqRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("c2b1r"),
+            uci(position, "c2b1r"),
             "3k4/8/1K6/8/8/8/pp1ppppp/RrRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("c2b1n"),
+            uci(position, "c2b1n"),
             "3k4/8/1K6/8/8/8/pp1ppppp/RnRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("c2b1b"),
+            uci(position, "c2b1b"),
             "3k4/8/1K6/8/8/8/pp1ppppp/RbRRRRRR w - - 0 2",
         ),
         (
-            Move::from_str("c2d1q"),
+            uci(position, "c2d1q"),
             "3k4/8/1K6/8/8/8/pp1ppppp/RRRqRRRR w - - 0 2",
         ),
         (
-            Move::from_str("c2d1r"),
+            uci(position, "c2d1r"),
             "3k4/8/1K6/8/8/8/pp1ppppp/RRRrRRRR w - - 0 2",
         ),
         (
-            Move::from_str("c2d1n"),
+            uci(position, "c2d1n"),
             "3k4/8/1K6/8/8/8/pp1ppppp/RRRnRRRR w - - 0 2",
         ),
         (
-            Move::from_str("c2d1b"),
+            uci(position, "c2d1b"),
             "3k4/8/1K6/8/8/8/pp1ppppp/RRRbRRRR w - - 0 2",
         ),
         (
-            Move::from_str("e2d1q"),
+            uci(position, "e2d1q"),
             "3k4/8/1K6/8/8/8/pppp1ppp/RRRqRRRR w - - 0 2",
         ),
         (
-            Move::from_str("e2d1r"),
+            uci(position, "e2d1r"),
             "3k4/8/1K6/8/8/8/pppp1ppp/RRRrRRRR w - - 0 2",
         ),
         (
-            Move::from_str("e2d1n"),
+            uci(position, "e2d1n"),
             "3k4/8/1K6/8/8/8/pppp1ppp/RRRnRRRR w - - 0 2",
         ),
         (
-            Move::from_str("e2d1b"),
+            uci(position, "e2d1b"),
             "3k4/8/1K6/8/8/8/pppp1ppp/RRRbRRRR w - - 0 2",
         ),
         (
-            Move::from_str("e2f1q"),
+            uci(position, "e2f1q"),
             "3k4/8/1K6/8/8/8/pppp1ppp/RRRRRqRR w - - 0 2",
         ),
         (
-            Move::from_str("e2f1r"),
+            uci(position, "e2f1r"),
             "3k4/8/1K6/8/8/8/pppp1ppp/RRRRRrRR w - - 0 2",
         ),
         (
-            Move::from_str("e2f1n"),
+            uci(position, "e2f1n"),
             "3k4/8/1K6/8/8/8/pppp1ppp/RRRRRnRR w - - 0 2",
         ),
         (
-            Move::from_str("e2f1b"),
+            uci(position, "e2f1b"),
             "3k4/8/1K6/8/8/8/pppp1ppp/RRRRRbRR w - - 0 2",
         ),
         (
-            Move::from_str("f2e1q"),
+            uci(position, "f2e1q"),
             "3k4/8/1K6/8/8/8/ppppp1pp/RRRRqRRR w - - 0 2",
         ),
         (
-            Move::from_str("f2e1r"),
+            uci(position, "f2e1r"),
             "3k4/8/1K6/8/8/8/ppppp1pp/RRRRrRRR w - - 0 2",
         ),
         (
-            Move::from_str("f2e1n"),
+            uci(position, "f2e1n"),
             "3k4/8/1K6/8/8/8/ppppp1pp/RRRRnRRR w - - 0 2",
         ),
         (
-            Move::from_str("f2e1b"),
+            uci(position, "f2e1b"),
             "3k4/8/1K6/8/8/8/ppppp1pp/RRRRbRRR w - - 0 2",
         ),
         (
-            Move::from_str("f2g1q"),
+            uci(position, "f2g1q"),
             "3k4/8/1K6/8/8/8/ppppp1pp/RRRRRRqR w - - 0 2",
         ),
         (
-            Move::from_str("f2g1r"),
+            uci(position, "f2g1r"),
             "3k4/8/1K6/8/8/8/ppppp1pp/RRRRRRrR w - - 0 2",
         ),
         (
-            Move::from_str("f2g1n"),
+            uci(position, "f2g1n"),
             "3k4/8/1K6/8/8/8/ppppp1pp/RRRRRRnR w - - 0 2",
         ),
         (
-            Move::from_str("f2g1b"),
+            uci(position, "f2g1b"),
             "3k4/8/1K6/8/8/8/ppppp1pp/RRRRRRbR w - - 0 2",
         ),
         (
-            Move::from_str("g2f1q"),
+            uci(position, "g2f1q"),
             "3k4/8/1K6/8/8/8/pppppp1p/RRRRRqRR w - - 0 2",
         ),
         (
-            Move::from_str("g2f1r"),
+            uci(position, "g2f1r"),
             "3k4/8/1K6/8/8/8/pppppp1p/RRRRRrRR w - - 0 2",
         ),
         (
-            Move::from_str("g2f1n"),
+            uci(position, "g2f1n"),
             "3k4/8/1K6/8/8/8/pppppp1p/RRRRRnRR w - - 0 2",
         ),
         (
-            Move::from_str("g2f1b"),
+            uci(position, "g2f1b"),
             "3k4/8/1K6/8/8/8/pppppp1p/RRRRRbRR w - - 0 2",
         ),
         (
-            Move::from_str("g2h1q"),
+            uci(position, "g2h1q"),
             "3k4/8/1K6/8/8/8/pppppp1p/RRRRRRRq w - - 0 2",
         ),
         (
-            Move::from_str("g2h1r"),
+            uci(position, "g2h1r"),
             "3k4/8/1K6/8/8/8/pppppp1p/RRRRRRRr w - - 0 2",
         ),
         (
-            Move::from_str("g2h1n"),
+            uci(position, "g2h1n"),
             "3k4/8/1K6/8/8/8/pppppp1p/RRRRRRRn w - - 0 2",
         ),
         (
-            Move::from_str("g2h1b"),
+            uci(position, "g2h1b"),
             "3k4/8/1K6/8/8/8/pppppp1p/RRRRRRRb w - - 0 2",
         ),
         (
-            Move::from_str("h2g1q"),
+            uci(position, "h2g1q"),
             "3k4/8/1K6/8/8/8/ppppppp1/RRRRRRqR w - - 0 2",
         ),
         (
-            Move::from_str("h2g1r"),
+            uci(position, "h2g1r"),
             "3k4/8/1K6/8/8/8/ppppppp1/RRRRRRrR w - - 0 2",
         ),
         (
-            Move::from_str("h2g1n"),
+            uci(position, "h2g1n"),
             "3k4/8/1K6/8/8/8/ppppppp1/RRRRRRnR w - - 0 2",
         ),
         (
-            Move::from_str("h2g1b"),
+            uci(position, "h2g1b"),
             "3k4/8/1K6/8/8/8/ppppppp1/RRRRRRbR w - - 0 2",
         ),
         (
-            Move::from_str("d8d7"),
+            uci(position, "d8d7"),
             "8/3k4/1K6/8/8/8/pppppppp/RRRRRRRR w - - 1 2",
         ),
         (
-            Move::from_str("d8e7"),
+            uci(position, "d8e7"),
             "8/4k3/1K6/8/8/8/pppppppp/RRRRRRRR w - - 1 2",
         ),
         (
-            Move::from_str("d8c8"),
+            uci(position, "d8c8"),
             "2k5/8/1K6/8/8/8/pppppppp/RRRRRRRR w - - 1 2",
         ),
         (
-            Move::from_str("d8e8"),
+            uci(position, "d8e8"),
             "4k3/8/1K6/8/8/8/pppppppp/RRRRRRRR w - - 1 2",
         ),
     ];
@@ -1642,9 +1648,90 @@ fn promotion_3() {
 
     // Check that applying each move results in the expected board state.
     for (r#move, expected_fen) in expected {
-        let mut board = Board::from_fen(position);
+        let mut board = Board::from_fen(position).unwrap();
         board.apply(r#move);
         assert_eq!(board.to_fen(), expected_fen);
+        assert_eq!(board.hash(), Board::from_fen(expected_fen).unwrap().hash());
         // TODO: Test undo's too
     }
 }
+
+/// Positions with captures, en pessant, promotions, castling and checks for both sides.
+const STAGED_POSITIONS: [&str; 6] = [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/Pp2P3/2N2Q1p/1PPBBPPP/R3K2R b KQkq a3 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+    "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+];
+
+#[test]
+fn captures_and_quiets() {
+    for fen in STAGED_POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        let captures = board.generate_captures();
+        let quiets = board.generate_quiets();
+        assert!(captures.iter().all(|m| m.is_tactical()), "{fen}");
+        assert!(quiets.iter().all(|m| !m.is_tactical()), "{fen}");
+
+        // Together they are every legal move, each exactly once.
+        let mut staged: Vec<Move> = captures.into_iter().chain(quiets).collect();
+        let mut all = board.generate_moves();
+        staged.sort();
+        all.sort();
+        assert_eq!(staged, all, "{fen}");
+    }
+}
+
+#[test]
+fn quiet_checks() {
+    let board = Board::from_fen("3k4/8/8/8/8/8/7P/R3K1N1 w Q - 0 1").unwrap();
+    let checks: HashSet<String> = board
+        .generate_quiet_checks()
+        .iter()
+        .map(|m| m.to_string())
+        .collect();
+    let expected: HashSet<String> = ["a1a8", "a1d1", "e1c1"]
+        .map(String::from)
+        .into_iter()
+        .collect();
+    assert_eq!(checks, expected);
+
+    for fen in STAGED_POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        let expected: Vec<Move> = board
+            .generate_quiets()
+            .into_iter()
+            .filter(|&m| board.gives_check(m))
+            .collect();
+        assert_eq!(board.generate_quiet_checks(), expected, "{fen}");
+    }
+}
+
+#[test]
+fn legal_moves_from_other_positions() {
+    let square = |s| square_from_algebraic(s).unwrap();
+    let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+    for m in board.generate_moves() {
+        assert!(board.is_legal(m), "{m}");
+    }
+    // Moves of the other side, or of pieces that aren't there, aren't legal.
+    let black = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+    let start =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    for m in black
+        .generate_moves()
+        .into_iter()
+        .chain(start.generate_moves())
+    {
+        assert!(!board.is_legal(m), "{m}");
+    }
+    // Neither is a move of the wrong kind, like a capture that is said to be quiet.
+    assert!(!board.is_legal(Move::new(square("a1"), square("a8"), MoveKind::Quiet)));
+    assert!(!board.is_legal(Move::new(square("e1"), square("e2"), MoveKind::Capture)));
+
+    // A pinned piece may not leave the line it is pinned along.
+    let pinned = Board::from_fen("4k3/4r3/8/8/8/8/4B3/4K3 w - - 0 1").unwrap();
+    assert!(!pinned.is_legal(Move::new(square("e2"), square("d3"), MoveKind::Quiet)));
+}
@@ -0,0 +1,110 @@
+use justok::board::Board;
+use justok::r#move::{Move, MoveKind};
+use justok::movepick::MovePicker;
+use justok::ordering::MoveOrdering;
+
+const POSITIONS: [&str; 5] = [
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/Pp2P3/2N2Q1p/1PPBBPPP/R3K2R b KQkq a3 0 1",
+    "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+    "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+];
+
+/// Every legal move is picked exactly once, whatever hash move and killers are given.
+#[test]
+fn picks_every_move_once() {
+    // A move from another position, like the start position.
+    let unrelated = Move::new(8, 24, MoveKind::DoublePush);
+    for fen in POSITIONS {
+        let board = Board::from_fen(fen).unwrap();
+        let mut legal = board.generate_moves();
+        legal.sort();
+
+        let quiets = board.generate_quiets();
+        let captures = board.generate_captures();
+        let suggestions = [
+            (None, [None, None]),
+            (
+                legal.first().copied(),
+                [quiets.first().copied(), quiets.last().copied()],
+            ),
+            (
+                quiets.first().copied(),
+                [quiets.first().copied(), quiets.first().copied()],
+            ),
+            (
+                Some(unrelated),
+                [captures.first().copied(), Some(unrelated)],
+            ),
+        ];
+        for (hash_move, killers) in suggestions {
+            let mut picked: Vec<Move> = MovePicker::new(&board, hash_move, killers).collect();
+            picked.sort();
+            assert_eq!(picked, legal, "{fen} {hash_move:?} {killers:?}");
+        }
+    }
+}
+
+#[test]
+fn picks_in_stages() {
+    // White can win the queen with the pawn or the knight, take a defended pawn with the knight or queen,
+    // or move quietly.
+    let board = Board::from_fen("4k3/8/3p4/2q1p3/1P6/3N4/8/Q3K3 w - - 0 1").unwrap();
+    let hash_move = Move::from_uci(&board, "e1d1").unwrap();
+    let killer = Move::from_uci(&board, "a1a7").unwrap();
+    let picked: Vec<String> = MovePicker::new(&board, Some(hash_move), [Some(killer), None])
+        .map(|m| m.to_string())
+        .collect();
+
+    assert_eq!(picked[0], "e1d1");
+    // The queen is captured by the pawn before the knight.
+    assert_eq!(picked[1..3], ["b4c5", "d3c5"]);
+    assert_eq!(picked[3], "a1a7");
+    // Risking a piece for the defended pawn is tried last.
+    assert_eq!(picked[picked.len() - 2..], ["d3e5", "a1e5"]);
+    assert_eq!(picked.len(), board.generate_moves().len());
+}
+
+#[test]
+fn picks_winning_captures_for_quiescence() {
+    let board = Board::from_fen("4k3/8/3p4/2q1p3/1P6/3N4/8/Q3K3 w - - 0 1").unwrap();
+    let picked: Vec<String> = MovePicker::captures(&board)
+        .map(|m| m.to_string())
+        .collect();
+    assert_eq!(picked, ["b4c5", "d3c5"]);
+
+    // Promoting on a square where the new piece can't be taken is as good as a capture.
+    let board = Board::from_fen("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+    let picked: Vec<String> = MovePicker::captures(&board)
+        .map(|m| m.to_string())
+        .collect();
+    assert_eq!(picked, ["a7b8q", "a7b8r", "a7b8b", "a7b8n"]);
+}
+
+#[test]
+fn picks_countermove_and_quiets_by_history() {
+    let board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+    let killer = Move::from_uci(&board, "a1a8").unwrap();
+    let countermove = Move::from_uci(&board, "e1f2").unwrap();
+    let picked: Vec<String> = MovePicker::new(&board, None, [Some(killer), None])
+        .with_countermove(Some(countermove))
+        .map(|m| m.to_string())
+        .collect();
+    assert_eq!(picked[..2], ["a1a8", "e1f2"]);
+    assert_eq!(picked.len(), board.generate_moves().len());
+
+    // Quiet moves that caused cutoffs come first, and moves that failed to come last.
+    let mut ordering = MoveOrdering::new();
+    let good = Move::from_uci(&board, "a1a5").unwrap();
+    let bad = Move::from_uci(&board, "a1b1").unwrap();
+    ordering.update(&board, 0, None, good, &[bad], 5);
+    let mut picker = MovePicker::new(&board, None, [None, None]);
+    let mut picked = Vec::new();
+    while let Some(m) = picker.pick(&ordering) {
+        picked.push(m);
+    }
+    assert_eq!(picked[0], good);
+    assert_eq!(picked[picked.len() - 1], bad);
+    assert_eq!(picked.len(), board.generate_moves().len());
+}
@@ -0,0 +1,91 @@
+use justok::Move;
+use justok::board::Board;
+use justok::ordering::{CutoffStats, MAX_HISTORY, MoveOrdering};
+use justok::search::{Limits, Searcher};
+
+const FEN: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+fn uci(board: &Board, text: &str) -> Move {
+    Move::from_uci(board, text).unwrap()
+}
+
+#[test]
+fn killers_and_countermoves() {
+    let board = Board::from_fen(FEN).unwrap();
+    let mut ordering = MoveOrdering::new();
+    let [first, second] = [uci(&board, "a2a3"), uci(&board, "g2g3")];
+    ordering.update(&board, 3, None, first, &[], 4);
+    ordering.update(&board, 3, None, second, &[], 4);
+    assert_eq!(ordering.killers(3), [Some(second), Some(first)]);
+    // A killer that cuts off again isn't stored twice.
+    ordering.update(&board, 3, None, second, &[], 4);
+    assert_eq!(ordering.killers(3), [Some(second), Some(first)]);
+    assert_eq!(ordering.killers(2), [None, None]);
+
+    // Black's answer to white castling is remembered by the king landing on g1.
+    let mut after = board;
+    let castle = uci(&board, "e1g1");
+    after.make(castle);
+    let answer = uci(&after, "a8b8");
+    assert_eq!(after.at(castle.to()).map(|p| p.to_char()), Some('K'));
+    ordering.update(&after, 1, Some(castle), answer, &[], 2);
+    assert_eq!(ordering.countermove(&after, Some(castle)), Some(answer));
+    assert_eq!(ordering.countermove(&after, None), None);
+
+    // Killers are forgotten in a new search, and everything when cleared.
+    ordering.new_search();
+    assert_eq!(ordering.killers(3), [None, None]);
+    assert_eq!(ordering.countermove(&after, Some(castle)), Some(answer));
+    ordering.clear();
+    assert_eq!(ordering.countermove(&after, Some(castle)), None);
+}
+
+#[test]
+fn history_gravity() {
+    let board = Board::from_fen(FEN).unwrap();
+    let mut ordering = MoveOrdering::new();
+    let [good, bad] = [uci(&board, "a2a3"), uci(&board, "g2g3")];
+    for _ in 0..1000 {
+        ordering.update(&board, 0, None, good, &[bad], 30);
+    }
+    let [good_score, bad_score] = [
+        ordering.history(&board, good),
+        ordering.history(&board, bad),
+    ];
+    assert!((1..=MAX_HISTORY).contains(&good_score));
+    assert!((-MAX_HISTORY..0).contains(&bad_score));
+
+    // A cutoff counts for less the more often the move has already caused one.
+    let mut fresh = MoveOrdering::new();
+    fresh.update(&board, 0, None, good, &[], 30);
+    let first = fresh.history(&board, good);
+    fresh.update(&board, 0, None, good, &[], 30);
+    assert!(fresh.history(&board, good) - first < first);
+
+    // Scores decay between searches, and are kept apart for each side.
+    ordering.new_search();
+    assert_eq!(ordering.history(&board, good), good_score / 2);
+    let mut black = board;
+    black.white_to_move = false;
+    assert_eq!(ordering.history(&black, good), 0);
+}
+
+#[test]
+fn first_move_cutoff_rate() {
+    assert_eq!(CutoffStats::default().first_move_rate(), 0.0);
+    let stats = CutoffStats {
+        cutoffs: 8,
+        first_move_cutoffs: 6,
+    };
+    assert_eq!(stats.first_move_rate(), 0.75);
+    assert_eq!(stats.to_string(), "cutoffs 8 first move cutoffs 6 (75.0%)");
+
+    let board = Board::from_fen(FEN).unwrap();
+    let limits = Limits {
+        depth: Some(5),
+        ..Default::default()
+    };
+    let report = Searcher::new().search(&board, &[], &limits, |_| {});
+    assert!(report.cutoffs.cutoffs > 0);
+    assert!(report.cutoffs.first_move_rate() > 0.8, "{}", report.cutoffs);
+}
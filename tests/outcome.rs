@@ -0,0 +1,111 @@
+use justok::Move;
+use justok::board::Board;
+use justok::outcome::GameOutcome;
+
+const STANDARD: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+#[test]
+fn checkmate_and_stalemate() {
+    let cases = [
+        (
+            "1R3k2/2R5/8/8/8/1K6/8/8 b - - 0 1",
+            GameOutcome::Checkmate { white_wins: true },
+        ),
+        (
+            "8/8/8/8/8/8/P1n5/K1k5 w - - 0 1",
+            GameOutcome::Checkmate { white_wins: false },
+        ),
+        // Checkmate on the last move stands, even after 75 moves.
+        (
+            "kr6/ppN5/8/8/8/8/2K5/8 b - - 150 90",
+            GameOutcome::Checkmate { white_wins: true },
+        ),
+        ("k7/1R6/2K5/8/8/8/8/8 b - - 0 1", GameOutcome::Stalemate),
+        (
+            "1k6/7b/2p2p2/4pP2/4K3/r7/8/8 w - e6 0 1",
+            GameOutcome::Stalemate,
+        ),
+    ];
+    for (fen, outcome) in cases {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(board.outcome(&[]), Some(outcome), "{fen}");
+    }
+    assert_eq!(Board::from_fen(STANDARD).unwrap().outcome(&[]), None);
+}
+
+#[test]
+fn insufficient_material() {
+    let cases = [
+        ("8/8/4k3/8/8/3K4/8/8 w - - 0 1", true),
+        ("8/8/4k3/8/8/3K4/8/5N2 w - - 0 1", true),
+        ("8/8/4k3/8/8/3K4/8/5b2 w - - 0 1", true),
+        // Bishops on squares of the same color, even on both sides.
+        ("8/3b4/4k3/8/8/3K4/8/5B2 w - - 0 1", true),
+        // Bishops on squares of different colors can checkmate.
+        ("8/8/4k3/8/8/3K4/8/4BB2 w - - 0 1", false),
+        ("8/8/4k3/8/8/3K4/8/4NN2 w - - 0 1", false),
+        ("8/8/4k3/8/8/3K4/8/4NB2 w - - 0 1", false),
+        ("8/8/4k3/8/8/3K4/4P3/8 w - - 0 1", false),
+        ("8/8/4k3/8/8/3K4/8/4R3 w - - 0 1", false),
+    ];
+    for (fen, insufficient) in cases {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(board.is_insufficient_material(), insufficient, "{fen}");
+        assert_eq!(
+            board.outcome(&[]) == Some(GameOutcome::InsufficientMaterial),
+            insufficient,
+            "{fen}"
+        );
+    }
+}
+
+#[test]
+fn move_rules() {
+    let cases = [
+        ("8/8/4k3/8/8/3K4/8/4R3 w - - 99 80", None),
+        (
+            "8/8/4k3/8/8/3K4/8/4R3 w - - 100 80",
+            Some(GameOutcome::FiftyMoveRule),
+        ),
+        (
+            "8/8/4k3/8/8/3K4/8/4R3 w - - 150 80",
+            Some(GameOutcome::SeventyFiveMoveRule),
+        ),
+    ];
+    for (fen, outcome) in cases {
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(board.outcome(&[]), outcome, "{fen}");
+    }
+}
+
+#[test]
+fn repetition() {
+    let mut board = Board::from_fen(STANDARD).unwrap();
+    let mut history = Vec::new();
+    let mut play = |board: &mut Board, moves: &[&str]| {
+        for m in moves {
+            history.push(board.hash());
+            board.make(Move::from_uci(board, m).unwrap());
+        }
+        board.outcome(&history)
+    };
+
+    // Moving the knights out and back repeats the starting position.
+    let knight_dance = ["g1f3", "g8f6", "f3g1", "f6g8"];
+    assert_eq!(play(&mut board, &knight_dance), None);
+    assert_eq!(
+        play(&mut board, &knight_dance),
+        Some(GameOutcome::ThreefoldRepetition)
+    );
+    assert_eq!(
+        play(&mut board, &knight_dance),
+        Some(GameOutcome::ThreefoldRepetition)
+    );
+    assert_eq!(
+        play(&mut board, &knight_dance),
+        Some(GameOutcome::FivefoldRepetition)
+    );
+
+    // A pawn move means earlier positions can't be repeated.
+    assert_eq!(play(&mut board, &["e2e4", "e7e5"]), None);
+}
@@ -1,42 +1,172 @@
 use justok::board::Board;
+use justok::perft::{Perft, PerftStats, perft, perft_stats};
 
-fn perft(board: Board, depth: usize) -> u128 {
-    let moves = board.generate_moves();
-    if depth == 1 {
-        return moves.len() as u128;
-    };
-
-    moves
-        .iter()
-        .map(|&moove| {
-            let mut board_with_move = board.clone();
-            board_with_move.apply(moove);
-            perft(board_with_move, depth - 1)
-        })
-        .sum()
+/// Counts positions on every available thread with a cache, which lets the tests afford the deeper counts.
+fn counter() -> Perft {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    Perft::new().threads(threads).cache_mb(64)
 }
 
 #[test]
 fn perft_standard() {
-    let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
-    let expected: [u128; 6] = [20, 400, 8902, 197281, 4865609, 119060324];
-    let actual = perft(board, 5);
-    assert_eq!(expected[4], actual);
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    let expected: [u64; 6] = [20, 400, 8902, 197281, 4865609, 119060324];
+    let actual = counter().count(&board, 6);
+    assert_eq!(expected[5], actual);
 }
 
 #[test]
 fn perft_kiwi_pete() {
     let board =
-        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1");
-    let expected: [u128; 6] = [48, 2039, 97862, 4085603, 193690690, 8031647685];
-    let actual = perft(board, 4);
-    assert_eq!(expected[3], actual);
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    let expected: [u64; 6] = [48, 2039, 97862, 4085603, 193690690, 8031647685];
+    let actual = counter().count(&board, 5);
+    assert_eq!(expected[4], actual);
 }
 
 #[test]
 fn perft_tricky() {
-    let board = Board::from_fen("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8");
-    let expected: [u128; 5] = [44, 1486, 62379, 2103487, 89941194];
-    let actual = perft(board, 4);
-    assert_eq!(expected[3], actual);
+    let board =
+        Board::from_fen("rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8").unwrap();
+    let expected: [u64; 5] = [44, 1486, 62379, 2103487, 89941194];
+    let actual = counter().count(&board, 5);
+    assert_eq!(expected[4], actual);
+}
+
+/// Checks, pins and the en pessant capture that exposes the king along the rank.
+#[test]
+fn perft_pins() {
+    let board = Board::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+    let expected: [u64; 6] = [14, 191, 2812, 43238, 674624, 11030083];
+    let actual = counter().count(&board, 6);
+    assert_eq!(expected[5], actual);
+}
+
+#[test]
+fn perft_checks() {
+    let board = Board::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1")
+        .unwrap();
+    let expected: [u64; 5] = [6, 264, 9467, 422333, 15833292];
+    let actual = counter().count(&board, 5);
+    assert_eq!(expected[4], actual);
+}
+
+/// Chess960 positions, where castling is written as the king capturing its own rook.
+#[test]
+fn perft_chess960() {
+    let positions: [(&str, [u64; 4]); 5] = [
+        (
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            [21, 528, 12189, 326672],
+        ),
+        (
+            "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+            [21, 807, 18002, 667366],
+        ),
+        (
+            "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9",
+            [20, 479, 10471, 273318],
+        ),
+        (
+            "qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9",
+            [22, 593, 13440, 382958],
+        ),
+        (
+            "1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9",
+            [28, 1120, 31058, 1171749],
+        ),
+    ];
+    for (fen, expected) in positions {
+        let board = Board::from_fen(fen).unwrap();
+        assert!(board.is_chess960());
+        for (depth, &nodes) in expected.iter().enumerate() {
+            assert_eq!(
+                perft(&board, depth + 1),
+                nodes,
+                "depth {} of {fen}",
+                depth + 1
+            );
+        }
+    }
+}
+
+/// Counting with threads and a cache gives the same counts as counting on a single thread without.
+#[test]
+fn perft_threads_and_cache() {
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    let parallel = Perft::new().threads(4).cache_mb(1);
+    for depth in 0..=3 {
+        assert_eq!(parallel.count(&board, depth), perft(&board, depth));
+    }
+}
+
+#[test]
+fn perft_divide() {
+    let board = Board::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+    let divide = counter().divide(&board, 3);
+    assert_eq!(divide.len(), 14);
+    assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 2812);
+
+    let (m, nodes) = divide
+        .iter()
+        .find(|(m, _)| m.to_string() == "e2e4")
+        .unwrap();
+    let mut after = board;
+    after.make(*m);
+    assert_eq!(*nodes, perft(&after, 2));
+}
+
+#[test]
+fn perft_stats_standard() {
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    let expected = PerftStats {
+        nodes: 197281,
+        captures: 1576,
+        checks: 469,
+        checkmates: 8,
+        ..Default::default()
+    };
+    assert_eq!(perft_stats(&board, 4, 1), expected);
+}
+
+#[test]
+fn perft_stats_kiwi_pete() {
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    let expected = PerftStats {
+        nodes: 97862,
+        captures: 17102,
+        en_pessants: 45,
+        castles: 3162,
+        promotions: 0,
+        checks: 993,
+        discovered_checks: 0,
+        double_checks: 0,
+        checkmates: 1,
+    };
+    assert_eq!(perft_stats(&board, 3, 2), expected);
+}
+
+/// Discovered checks and double checks, mostly from en pessant and the rook behind the pawn.
+#[test]
+fn perft_stats_pins() {
+    let board = Board::from_fen("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1").unwrap();
+    let expected = PerftStats {
+        nodes: 674624,
+        captures: 52051,
+        en_pessants: 1165,
+        castles: 0,
+        promotions: 0,
+        checks: 52950,
+        discovered_checks: 1292,
+        double_checks: 3,
+        checkmates: 0,
+    };
+    assert_eq!(perft_stats(&board, 5, 2), expected);
 }
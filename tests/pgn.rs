@@ -0,0 +1,145 @@
+use justok::Move;
+use justok::game::Game;
+use justok::pgn::{GameResult, Pgn, PgnError};
+use justok::san::SanError;
+
+const ANNOTATED: &str = r#"[Event "Casual \"blitz\" game"]
+[Site "Copenhagen"]
+[Date "2024.05.01"]
+[Round "-"]
+[White "Alice"]
+[Black "Bob"]
+[Result "1-0"]
+[TimeControl "180+2"]
+
+{Opening comment} 1. e4 e5 2. Nf3 $1 Nc6 (2... d6 {Philidor} 3. d4 (3. Bc4) 3... exd4)
+3. Bb5!? a6 ; The Morphy defence
+4. Ba4 Nf6 5. O-O 1-0
+"#;
+
+#[test]
+fn parse() {
+    let pgn = Pgn::parse(ANNOTATED).unwrap();
+    assert_eq!(pgn.tag("Event"), Some("Casual \"blitz\" game"));
+    assert_eq!(pgn.tag("TimeControl"), Some("180+2"));
+    assert_eq!(pgn.result, GameResult::WhiteWins);
+    assert_eq!(pgn.line.comments, vec!["Opening comment"]);
+
+    let moves: Vec<String> = pgn
+        .line
+        .moves
+        .iter()
+        .map(|m| m.r#move.to_string())
+        .collect();
+    assert_eq!(
+        moves,
+        [
+            "e2e4", "e7e5", "g1f3", "b8c6", "f1b5", "a7a6", "b5a4", "g8f6", "e1g1"
+        ]
+    );
+    assert_eq!(pgn.line.moves[2].nags, vec![1]);
+    assert_eq!(pgn.line.moves[4].nags, vec![5]);
+    assert_eq!(pgn.line.moves[5].comments, vec!["The Morphy defence"]);
+
+    // The variation replaces black's second move, and has a variation of its own.
+    let variation = &pgn.line.moves[3].variations[0];
+    assert_eq!(variation.moves[0].r#move.to_string(), "d7d6");
+    assert_eq!(variation.moves[0].comments, vec!["Philidor"]);
+    assert_eq!(
+        variation.moves[1].variations[0].moves[0].r#move.to_string(),
+        "f1c4"
+    );
+    assert_eq!(variation.moves[2].r#move.to_string(), "e5d4");
+
+    let game = pgn.game();
+    assert_eq!(game.ply(), 9);
+    assert_eq!(
+        game.board().to_fen(),
+        "r1bqkb1r/1ppp1ppp/p1n2n2/4p3/B3P3/5N2/PPPP1PPP/RNBQ1RK1 b kq - 3 5"
+    );
+}
+
+#[test]
+fn export() {
+    let pgn = Pgn::parse(ANNOTATED).unwrap();
+    let expected = r#"[Event "Casual \"blitz\" game"]
+[Site "Copenhagen"]
+[Date "2024.05.01"]
+[Round "-"]
+[White "Alice"]
+[Black "Bob"]
+[Result "1-0"]
+[TimeControl "180+2"]
+
+{Opening comment} 1. e4 e5 2. Nf3 $1 Nc6 (2... d6 {Philidor} 3. d4 (3. Bc4) 3...
+exd4) 3. Bb5 $5 a6 {The Morphy defence} 4. Ba4 Nf6 5. O-O 1-0
+"#;
+    assert_eq!(pgn.to_string(), expected);
+    assert_eq!(Pgn::parse(expected), Ok(pgn));
+}
+
+#[test]
+fn export_game() {
+    let mut game = Game::from_fen("k7/8/1K6/8/8/8/8/6R1 b - - 3 40").unwrap();
+    for m in ["a8b8", "g1g8"] {
+        game.push(Move::from_uci(game.board(), m).unwrap());
+    }
+    let pgn = Pgn::from_game(&game);
+    let expected = r#"[Event "?"]
+[Site "?"]
+[Date "????.??.??"]
+[Round "?"]
+[White "?"]
+[Black "?"]
+[Result "1-0"]
+[SetUp "1"]
+[FEN "k7/8/1K6/8/8/8/8/6R1 b - - 3 40"]
+
+40... Kb8 41. Rg8# 1-0
+"#;
+    assert_eq!(pgn.to_string(), expected);
+    assert_eq!(Pgn::parse(expected).unwrap().game().board(), game.board());
+}
+
+#[test]
+fn parse_all() {
+    let text = "[Event \"1\"]\n\n1. e4 e5 1/2-1/2\n\n[Event \"2\"]\n\n1. d4 *\n\n1. c4 0-1";
+    let games = Pgn::parse_all(text).unwrap();
+    assert_eq!(games.len(), 3);
+    assert_eq!(games[0].result, GameResult::Draw);
+    assert_eq!(games[1].tag("Event"), Some("2"));
+    assert_eq!(games[1].line.moves.len(), 1);
+    assert_eq!(games[2].result, GameResult::BlackWins);
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        ("[Event Casual]", PgnError::Tag("Event Casual".to_owned())),
+        (
+            "[FEN \"8/8/8 w - -\"]",
+            PgnError::Fen(justok::board::FenError::RankCount(3)),
+        ),
+        (
+            "[FEN \"4k3/8/8/8/8/8/8/8 w - - 0 1\"]",
+            PgnError::Position(justok::board::PositionError::KingCount {
+                white: true,
+                count: 0,
+            }),
+        ),
+        (
+            "1. e4 e5 2. Ke3",
+            PgnError::Move {
+                ply: 3,
+                error: SanError::Illegal("Ke3".to_owned()),
+            },
+        ),
+        ("(1. e4)", PgnError::Movetext("(".to_owned())),
+        ("1. e4 e5)", PgnError::Movetext(")".to_owned())),
+        ("1. e4 (1. d4", PgnError::UnexpectedEnd),
+        ("1. e4 {unfinished", PgnError::UnexpectedEnd),
+    ];
+    for (text, error) in cases {
+        assert_eq!(Pgn::parse(text), Err(error), "{text}");
+    }
+}
@@ -0,0 +1,105 @@
+use justok::Move;
+use justok::board::Board;
+use justok::san::SanError;
+
+/// Check that a move is written as the given SAN, and that reading the SAN gives back the move.
+fn assert_san(fen: &str, uci: &str, san: &str) {
+    let board = Board::from_fen(fen).unwrap();
+    let m = Move::from_uci(&board, uci).unwrap();
+    assert_eq!(m.to_san(&board), san, "{uci} in {fen}");
+    assert_eq!(Move::from_san(&board, san), Ok(m), "{san} in {fen}");
+}
+
+#[test]
+fn format_and_parse() {
+    let standard = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+    assert_san(standard, "e2e4", "e4");
+    assert_san(standard, "g1f3", "Nf3");
+
+    let kiwipete = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+    assert_san(kiwipete, "e1g1", "O-O");
+    assert_san(kiwipete, "e1c1", "O-O-O");
+    assert_san(kiwipete, "e2a6", "Bxa6");
+    assert_san(kiwipete, "d5e6", "dxe6");
+    assert_san(kiwipete, "e5f7", "Nxf7");
+    assert_san(kiwipete, "g2h3", "gxh3");
+
+    // En pessant is written like any other pawn capture.
+    assert_san(
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3",
+        "e5d6",
+        "exd6",
+    );
+
+    // Promotions, with check.
+    assert_san("8/4P3/8/8/8/8/k7/4K3 w - - 0 1", "e7e8q", "e8=Q");
+    assert_san("k7/4P3/8/8/8/8/8/4K3 w - - 0 1", "e7e8r", "e8=R+");
+    assert_san("3r4/4P3/8/8/8/8/k7/4K3 w - - 0 1", "e7d8n", "exd8=N");
+
+    // Checkmate.
+    assert_san(
+        "rnbqkbnr/pppp1ppp/8/4p3/6P1/5P2/PPPPP2P/RNBQKBNR b KQkq - 0 2",
+        "d8h4",
+        "Qh4#",
+    );
+}
+
+#[test]
+fn disambiguation() {
+    // Knights on b8 and f6 can both reach d7, so the file is enough.
+    assert_san(
+        "rn1qkb1r/pp3ppp/2p1pn2/3p4/3P4/2N1PN2/PP3PPP/R1BQKB1R b KQkq - 0 6",
+        "b8d7",
+        "Nbd7",
+    );
+    // Rooks on the same file are told apart by their rank.
+    assert_san("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1", "a1a3", "R1a3");
+    // Three queens where neither file nor rank alone is enough.
+    assert_san("4k3/8/8/8/Q1Q5/8/Q7/4K3 w - - 0 1", "a4b3", "Qa4b3");
+    // A pinned piece doesn't need to be told apart.
+    assert_san("4k3/8/8/8/8/8/2N1r3/4K1N1 w - - 0 1", "g1e2", "Nxe2");
+}
+
+#[test]
+fn parse_leniently() {
+    let board =
+        Board::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3").unwrap();
+    let en_pessant = Ok(Move::from_uci(&board, "e5d6").unwrap());
+    assert_eq!(Move::from_san(&board, "exd6 e.p."), en_pessant);
+    assert_eq!(Move::from_san(&board, "exd6e.p."), en_pessant);
+    assert_eq!(Move::from_san(&board, "ed6"), en_pessant);
+    assert_eq!(
+        Move::from_san(&board, "Qh5+!?"),
+        Ok(Move::from_uci(&board, "d1h5").unwrap())
+    );
+
+    let board = Board::from_fen("r3k2r/8/8/8/8/8/8/4K3 b kq - 0 1").unwrap();
+    assert_eq!(
+        Move::from_san(&board, "0-0-0"),
+        Ok(Move::from_uci(&board, "e8c8").unwrap())
+    );
+    let board = Board::from_fen("8/4P3/8/8/8/8/k7/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        Move::from_san(&board, "e8Q"),
+        Ok(Move::from_uci(&board, "e7e8q").unwrap())
+    );
+}
+
+#[test]
+fn errors() {
+    let board =
+        Board::from_fen("rn1qkb1r/pp3ppp/2p1pn2/3p4/3P4/2N1PN2/PP3PPP/R1BQKB1R b KQkq - 0 6")
+            .unwrap();
+    let cases = [
+        ("Nd7", SanError::Ambiguous("Nd7".to_owned())),
+        ("Nd8", SanError::Illegal("Nd8".to_owned())),
+        ("O-O", SanError::Illegal("O-O".to_owned())),
+        ("e4", SanError::Illegal("e4".to_owned())),
+        ("Xe5", SanError::Invalid("Xe5".to_owned())),
+        ("Nz7", SanError::Invalid("Nz7".to_owned())),
+        ("", SanError::Invalid("".to_owned())),
+    ];
+    for (san, error) in cases {
+        assert_eq!(Move::from_san(&board, san), Err(error), "{san}");
+    }
+}
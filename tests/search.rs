@@ -0,0 +1,191 @@
+use std::sync::atomic::Ordering;
+
+use justok::Move;
+use justok::board::Board;
+use justok::search::{Limits, Score, SearchOptions, Searcher};
+
+fn search(fen: &str, depth: usize) -> justok::search::Report {
+    let board = Board::from_fen(fen).unwrap();
+    let limits = Limits {
+        depth: Some(depth),
+        ..Default::default()
+    };
+    Searcher::new().search(&board, &[], &limits, |_| {})
+}
+
+fn uci(fen: &str, text: &str) -> Move {
+    Move::from_uci(&Board::from_fen(fen).unwrap(), text).unwrap()
+}
+
+#[test]
+fn finds_mate_in_one() {
+    let fen = "6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1";
+    let report = search(fen, 4);
+    assert_eq!(report.best_move(), Some(uci(fen, "a1a8")));
+    assert_eq!(report.score(), Score::Mate(1));
+    // There is no need to search past the mate.
+    assert_eq!(report.depth, 1);
+}
+
+#[test]
+fn finds_mate_in_two() {
+    let fen = "7k/8/8/8/8/8/R7/1R4K1 w - - 0 1";
+    let report = search(fen, 5);
+    // Either rook can cut off the king for the other to mate on the back rank.
+    assert!([uci(fen, "a2a7"), uci(fen, "b1b7")].contains(&report.best_move().unwrap()));
+    assert_eq!(report.score(), Score::Mate(2));
+    assert_eq!(report.pv.len(), 3);
+
+    // The other side sees that it is getting mated.
+    let report = search("6k1/R7/8/8/8/8/8/1R4K1 b - - 1 1", 4);
+    assert_eq!(report.score(), Score::Mate(-1));
+}
+
+#[test]
+fn wins_material() {
+    let fen = "4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1";
+    let report = search(fen, 3);
+    assert_eq!(report.best_move(), Some(uci(fen, "d2d5")));
+    assert!(matches!(report.score(), Score::Centipawns(cp) if cp >= 500));
+}
+
+/// The search looks past the end of its depth until captures run out.
+#[test]
+fn sees_recaptures() {
+    let fen = "4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1";
+    let report = search(fen, 1);
+    assert_ne!(report.best_move(), Some(uci(fen, "d1d5")));
+    assert!(matches!(report.score(), Score::Centipawns(cp) if cp > 600));
+}
+
+#[test]
+fn reports_every_iteration() {
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    let limits = Limits {
+        depth: Some(4),
+        ..Default::default()
+    };
+    let mut depths = Vec::new();
+    let report = Searcher::new().search(&board, &[], &limits, |r| {
+        depths.push(r.depth);
+        assert!(!r.pv.is_empty());
+    });
+    assert_eq!(depths, vec![1, 2, 3, 4]);
+    assert_eq!(report.depth, 4);
+    assert!(
+        board
+            .generate_moves()
+            .contains(&report.best_move().unwrap())
+    );
+    assert!(report.to_string().starts_with("depth 4 score cp "));
+}
+
+/// A search stopped before finishing an iteration still has a legal move to play.
+#[test]
+fn stops_with_a_move() {
+    let board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    let mut searcher = Searcher::new();
+    searcher.stop_flag().store(true, Ordering::Relaxed);
+    let report = searcher.search(&board, &[], &Limits::default(), |_| {});
+    assert_eq!(report.depth, 0);
+    assert!(
+        board
+            .generate_moves()
+            .contains(&report.best_move().unwrap())
+    );
+
+    let limits = Limits {
+        nodes: Some(1000),
+        ..Default::default()
+    };
+    let report = Searcher::new().search(&board, &[], &limits, |_| {});
+    assert!(report.nodes <= 1000);
+    assert!(
+        board
+            .generate_moves()
+            .contains(&report.best_move().unwrap())
+    );
+}
+
+#[test]
+fn game_over() {
+    let mated = search("R5k1/5ppp/8/8/8/8/5PPP/6K1 b - - 0 1", 3);
+    assert_eq!(mated.best_move(), None);
+    assert_eq!(mated.score(), Score::Mate(0));
+
+    let stalemate = search("k7/1R6/2K5/8/8/8/8/8 b - - 0 1", 3);
+    assert_eq!(stalemate.best_move(), None);
+    assert_eq!(stalemate.score(), Score::Centipawns(0));
+}
+
+/// The side behind in material steers into a repetition of an earlier position.
+#[test]
+fn draws_by_repetition() {
+    let fen = "6k1/8/8/8/8/8/q7/6K1 w - - 10 40";
+    let board = Board::from_fen(fen).unwrap();
+    let limits = Limits {
+        depth: Some(2),
+        ..Default::default()
+    };
+    let report = Searcher::new().search(&board, &[], &limits, |_| {});
+    assert!(matches!(report.score(), Score::Centipawns(cp) if cp <= -900));
+
+    let mut repeated = board;
+    repeated.make(uci(fen, "g1h1"));
+    let history = [repeated.hash(), board.hash()];
+    let report = Searcher::new().search(&board, &history, &limits, |_| {});
+    assert_eq!(report.best_move(), Some(uci(fen, "g1h1")));
+    assert_eq!(report.score(), Score::Centipawns(0));
+}
+
+/// Every part of the selective search can be switched off, and the search still finds the mates.
+#[test]
+fn options_can_be_switched_off() {
+    let fen = "7k/8/8/8/8/8/R7/1R4K1 w - - 0 1";
+    let board = Board::from_fen(fen).unwrap();
+    let limits = Limits {
+        depth: Some(5),
+        ..Default::default()
+    };
+    for i in 0..SearchOptions::default().switches().len() {
+        let mut options = SearchOptions::default();
+        *options.switches()[i].1 = false;
+        let mut searcher = Searcher::new();
+        searcher.set_options(options);
+        let report = searcher.search(&board, &[], &limits, |_| {});
+        assert_eq!(report.score(), Score::Mate(2), "{options:?}");
+    }
+}
+
+/// Pruning and reductions let the search go as deep with far fewer nodes,
+/// while searching with null windows and aspiration windows doesn't change the score.
+#[test]
+fn selectivity() {
+    let board =
+        Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+            .unwrap();
+    let limits = Limits {
+        depth: Some(5),
+        ..Default::default()
+    };
+    let search = |options: SearchOptions| {
+        let mut searcher = Searcher::new();
+        searcher.set_options(options);
+        searcher.search(&board, &[], &limits, |_| {})
+    };
+    let mut plain = SearchOptions::default();
+    for (_, switch) in plain.switches() {
+        *switch = false;
+    }
+    let windows = SearchOptions {
+        pvs: true,
+        aspiration_windows: true,
+        ..plain
+    };
+
+    let plain = search(plain);
+    assert_eq!(search(windows).value, plain.value);
+    assert!(search(SearchOptions::default()).nodes < plain.nodes / 2);
+}
@@ -0,0 +1,53 @@
+use justok::Move;
+use justok::board::Board;
+use justok::r#move::MoveKind;
+
+fn see(fen: &str, text: &str) -> i32 {
+    let board = Board::from_fen(fen).unwrap();
+    board.see(Move::from_uci(&board, text).unwrap())
+}
+
+#[test]
+fn captures() {
+    // An undefended pawn.
+    assert_eq!(see("4k3/8/8/3p4/8/8/8/3RK3 w - - 0 1", "d1d5"), 100);
+    // A pawn defended by a pawn costs the rook.
+    assert_eq!(see("4k3/8/2p5/3p4/8/8/8/3RK3 w - - 0 1", "d1d5"), -400);
+    // Winning the queen for the rook that recaptures.
+    assert_eq!(see("3rk3/8/8/8/8/8/3q4/3RK3 w - - 0 1", "d1d2"), 400);
+    // The king can't take a defended piece, which isn't a legal move but still has to score badly.
+    let board = Board::from_fen("3rk3/8/8/8/8/8/3q4/3RK3 w - - 0 1").unwrap();
+    assert!(board.see(Move::new(4, 11, MoveKind::Capture)) < -10_000);
+    // En pessant wins a pawn from an empty square.
+    assert_eq!(see("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1", "e5d6"), 100);
+    // Either side stops capturing when going on would lose material:
+    // white takes the pawn with the knight, and black doesn't recapture with the queen.
+    assert_eq!(see("3qk3/8/8/3p4/8/4N3/8/3RK3 w - - 0 1", "e3d5"), 100);
+    assert_eq!(see("3qk3/8/8/3p4/8/4N3/8/4K3 w - - 0 1", "e3d5"), -220);
+}
+
+/// Sliding pieces join the exchange once the pieces in front of them have captured.
+#[test]
+fn x_rays() {
+    // The second rook recaptures through the first.
+    assert_eq!(see("3rk3/8/8/3p4/8/8/3R4/3RK3 w - - 0 1", "d2d5"), 100);
+    // Black's rook behind the queen takes back the second rook, so the queen and rooks are traded.
+    assert_eq!(see("3rk3/3q4/8/3p4/8/8/3R4/3RK3 w - - 0 1", "d2d5"), 0);
+    // A bishop behind a queen on the diagonal.
+    assert_eq!(see("4k3/8/2r5/8/4Q3/5B2/8/4K3 w - - 0 1", "e4c6"), 500);
+    assert_eq!(
+        see("4k3/1b6/2r5/8/4Q3/5B2/8/4K3 w - - 0 1", "e4c6"),
+        500 - 900 + 330
+    );
+}
+
+#[test]
+fn quiet_moves_and_promotions() {
+    // Moving the rook where a pawn can take it.
+    assert_eq!(see("4k3/8/2p5/8/8/8/8/3RK3 w - - 0 1", "d1d5"), -500);
+    assert_eq!(see("4k3/8/8/8/8/8/8/3RK3 w - - 0 1", "d1d5"), 0);
+    // Promoting gains the difference between the new piece and the pawn, unless it can be taken.
+    assert_eq!(see("4k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7a8q"), 800);
+    assert_eq!(see("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7a8q"), -100);
+    assert_eq!(see("1r2k3/P7/8/8/8/8/8/4K3 w - - 0 1", "a7b8q"), 1300);
+}
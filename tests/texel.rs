@@ -0,0 +1,153 @@
+use justok::board::{Board, FenError, PositionError};
+use justok::eval::Evaluator;
+use justok::eval::params::{DEFAULT_PARAMS, MATERIAL, Params, Taper};
+use justok::texel::{Labels, Sample, SampleError, Tuner, parse_samples, sigmoid};
+
+/// Positions from a game played by picking moves in a fixed pattern, without captures pending.
+fn positions() -> Vec<Board> {
+    let mut board =
+        Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+    let mut positions = Vec::new();
+    for ply in 0..400 {
+        let moves = board.generate_moves();
+        if moves.is_empty() || board.half_move_clock() >= 100 {
+            break;
+        }
+        board.make(moves[(ply * 7 + 3) % moves.len()]);
+        if !board.is_in_check() && board.generate_captures().is_empty() {
+            positions.push(board);
+        }
+    }
+    positions
+}
+
+#[test]
+fn parses_results() {
+    let samples = parse_samples(
+        "4k3/8/8/8/8/8/8/3QK3 w - - 0 1 1-0\n\
+         \n\
+         4k3/8/8/8/8/8/8/3QK3 b - - 0 1 \"0-1\"\n\
+         4k3/8/8/8/8/8/8/3QK3 w - - c9 \"1/2-1/2\";\n\
+         4k3/8/8/8/8/8/8/3QK3 w - - 0 1 [0.25]\n\
+         4k3/8/8/8/8/8/8/3QK3 w - - 0 1 1\n\
+         4k3/8/8/8/8/8/8/3QK3 b - - 0 1 0\n",
+        Labels::Results,
+    )
+    .unwrap();
+    let results: Vec<f64> = samples.iter().map(|s| s.result).collect();
+    assert_eq!(results, [1.0, 0.0, 0.5, 0.25, 1.0, 0.0]);
+
+    assert_eq!(
+        parse_samples(
+            "4k3/8/8/8/8/8/8/3QK3 w - - 0 1 1-0\n4k3/8/8/8/8/8/8/3QK3 w - - 0 1 win",
+            Labels::Results
+        ),
+        Err(SampleError::Label(2, "win".to_owned()))
+    );
+    assert_eq!(
+        parse_samples("4k3/8/8/8/8/8/8/3QK3 w - - 0 1 [1.5]", Labels::Results),
+        Err(SampleError::Label(1, "[1.5]".to_owned()))
+    );
+    assert_eq!(
+        parse_samples("4k3/8/8/8/8/8/8/3QK3 w - - 0 1 400", Labels::Results),
+        Err(SampleError::Label(1, "400".to_owned()))
+    );
+    assert_eq!(
+        parse_samples("4k3/8/8/8/8/8/8/3QX3 w - - 0 1 1-0", Labels::Results),
+        Err(SampleError::Fen(1, FenError::UnknownPiece('X')))
+    );
+    assert_eq!(
+        parse_samples(
+            "4k3/8/8/8/8/8/8/3QK3 w - - 0 1 1-0\n4k3/8/8/8/8/8/8/p3K3 w - - 0 1 0.5",
+            Labels::Results
+        ),
+        Err(SampleError::Position(2, PositionError::PawnOnBackRank(0)))
+    );
+}
+
+#[test]
+fn parses_scores() {
+    let samples = parse_samples(
+        "4k3/8/8/8/8/8/8/3QK3 w - - 0 1 400\n\
+         4k3/8/8/8/8/8/8/3QK3 w - - 0 1 1\n\
+         4k3/8/8/8/8/8/8/3QK3 b - - 0 1 0\n\
+         4k3/8/8/8/8/8/8/3QK3 b - - 0 1 [-400];\n",
+        Labels::Scores,
+    )
+    .unwrap();
+    let results: Vec<f64> = samples.iter().map(|s| s.result).collect();
+    // A score is turned into the result it is expected to lead to, so 0 and 1 are about a draw.
+    let expected = [10.0 / 11.0, sigmoid(1.0, 1.0), 0.5, 1.0 / 11.0];
+    assert_eq!(results.len(), expected.len());
+    for (result, expected) in results.iter().zip(expected) {
+        assert!((result - expected).abs() < 1e-9);
+    }
+
+    assert_eq!(
+        parse_samples("4k3/8/8/8/8/8/8/3QK3 w - - 0 1 1-0", Labels::Scores),
+        Err(SampleError::Label(1, "1-0".to_owned()))
+    );
+    assert_eq!(
+        parse_samples("4k3/8/8/8/8/8/8/3QK3 w - - 0 1 0.5", Labels::Scores),
+        Err(SampleError::Label(1, "0.5".to_owned()))
+    );
+}
+
+/// A sample evaluates to what the evaluator scores the position for white.
+#[test]
+fn samples_evaluate_like_the_evaluator() {
+    let mut evaluator = Evaluator::new();
+    let weights: Vec<[f64; 2]> = DEFAULT_PARAMS
+        .values()
+        .iter()
+        .map(|v| [v.mg as f64, v.eg as f64])
+        .collect();
+    for board in positions() {
+        let sample = Sample::new(&evaluator.trace(&board), 0.5);
+        let score = match board.white_to_move {
+            true => evaluator.evaluate(&board),
+            false => -evaluator.evaluate(&board),
+        } as f64;
+        assert!(
+            (sample.evaluate(&weights) - score).abs() < 1.0,
+            "{}",
+            board.to_fen()
+        );
+    }
+}
+
+/// Tuning on positions labeled by an evaluation that values knights differently moves the knight's value towards it.
+#[test]
+fn tunes_towards_the_labels() {
+    let mut target = DEFAULT_PARAMS;
+    target.term_mut(MATERIAL)[1] = Taper { mg: 500, eg: 450 };
+    let labeler = Evaluator::with_params(target.clone());
+    let evaluator = Evaluator::new();
+    let samples: Vec<Sample> = positions()
+        .iter()
+        .map(|board| {
+            let trace = labeler.trace(board);
+            let score = match board.white_to_move {
+                true => trace.score,
+                false => -trace.score,
+            };
+            Sample::new(&evaluator.trace(board), sigmoid(score as f64, 1.0))
+        })
+        .collect();
+    assert!(samples.len() > 20);
+
+    let mut tuner = Tuner::new(&samples).learning_rate(2.0).threads(3);
+    let start = tuner.error(&DEFAULT_PARAMS);
+    assert!(tuner.error(&target) < 1e-6);
+    let mut errors = Vec::new();
+    let tuned: Params = tuner.tune(&DEFAULT_PARAMS, 200, |_, error| errors.push(error));
+    assert_eq!(errors.len(), 200);
+    assert_eq!(errors[0], start);
+    assert!(tuner.error(&tuned) < start / 4.0);
+    let knight = tuned.get(MATERIAL, 1);
+    assert!(knight.mg > DEFAULT_PARAMS.get(MATERIAL, 1).mg);
+
+    // The scaling that fits labels made with a scaling of 1 is close to 1.
+    let scaling = tuner.fit_scaling(&target);
+    assert!((scaling - 1.0).abs() < 0.01);
+}
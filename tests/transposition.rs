@@ -0,0 +1,124 @@
+use justok::Move;
+use justok::r#move::MoveKind;
+use justok::search::{Limits, MATE, Searcher};
+use justok::transposition::{Bound, Entry, TranspositionTable};
+
+fn e2e4() -> Move {
+    Move::new(12, 28, MoveKind::DoublePush)
+}
+
+#[test]
+fn stores_and_probes() {
+    let tt = TranspositionTable::new(1);
+    assert_eq!(tt.probe(42, 0), None);
+
+    tt.store(42, 3, 7, -123, Bound::Lower, Some(e2e4()));
+    assert_eq!(
+        tt.probe(42, 3),
+        Some(Entry {
+            r#move: Some(e2e4()),
+            value: -123,
+            depth: 7,
+            bound: Bound::Lower,
+        })
+    );
+    // Another position with the same bucket isn't mistaken for this one.
+    assert_eq!(tt.probe(42 + (1 << 40), 3), None);
+
+    // A result without a move keeps the move stored for the position.
+    tt.store(42, 3, 8, 50, Bound::Upper, None);
+    let entry = tt.probe(42, 0).unwrap();
+    assert_eq!(entry.r#move, Some(e2e4()));
+    assert_eq!(entry.bound, Bound::Upper);
+}
+
+/// Mates are stored relative to the position, and read back relative to the root it is probed from.
+#[test]
+fn adjusts_mate_scores() {
+    let tt = TranspositionTable::new(1);
+    // Mating in three plies from a position five plies from the root.
+    tt.store(1, 5, 3, MATE - 8, Bound::Exact, None);
+    assert_eq!(tt.probe(1, 5).unwrap().value, MATE - 8);
+    assert_eq!(tt.probe(1, 1).unwrap().value, MATE - 4);
+
+    tt.store(2, 4, 3, -MATE + 6, Bound::Exact, None);
+    assert_eq!(tt.probe(2, 0).unwrap().value, -MATE + 2);
+}
+
+#[test]
+fn prefers_deep_and_recent_results() {
+    let tt = TranspositionTable::new(1);
+    // A shallower bound doesn't replace a deeper result of the same search, but an exact score does.
+    tt.store(7, 0, 10, 1, Bound::Lower, None);
+    tt.store(7, 0, 2, 2, Bound::Upper, None);
+    assert_eq!(tt.probe(7, 0).unwrap().value, 1);
+    tt.store(7, 0, 2, 3, Bound::Exact, None);
+    assert_eq!(tt.probe(7, 0).unwrap().value, 3);
+
+    // A full bucket replaces its shallowest entry.
+    let mut tt = TranspositionTable::new(1);
+    let bucket = |i: u64| 5 + (i << 40);
+    for i in 0..4 {
+        tt.store(bucket(i), 0, 10 + i as usize, 0, Bound::Exact, None);
+    }
+    tt.store(bucket(4), 0, 1, 0, Bound::Exact, None);
+    assert_eq!(tt.probe(bucket(0), 0), None);
+    assert!((1..5).all(|i| tt.probe(bucket(i), 0).is_some()));
+
+    // Results of earlier searches go first, even if they are a little deeper.
+    tt.clear();
+    for i in 0..4 {
+        tt.store(bucket(i), 0, 5, 0, Bound::Exact, None);
+    }
+    tt.new_search();
+    tt.store(bucket(4), 0, 1, 0, Bound::Exact, None);
+    tt.store(bucket(5), 0, 1, 0, Bound::Exact, None);
+    assert!(tt.probe(bucket(4), 0).is_some());
+    assert!(tt.probe(bucket(5), 0).is_some());
+    assert_eq!(tt.probe(bucket(0), 0), None);
+    assert_eq!(tt.probe(bucket(1), 0), None);
+}
+
+#[test]
+fn hashfull_and_clear() {
+    let mut tt = TranspositionTable::new(1);
+    assert_eq!(tt.hashfull(), 0);
+    // Fill the first 250 buckets, which hold the first 1000 entries.
+    for bucket in 0..250 {
+        for i in 0..4 {
+            tt.store(bucket + (i << 40), 0, 1, 0, Bound::Exact, None);
+        }
+    }
+    assert_eq!(tt.hashfull(), 1000);
+    // Only results of the current search count.
+    tt.new_search();
+    assert_eq!(tt.hashfull(), 0);
+
+    tt.store(3, 0, 1, 0, Bound::Exact, None);
+    tt.clear();
+    assert_eq!(tt.probe(3, 0), None);
+}
+
+/// A searcher remembers positions from one search to the next, until it is cleared.
+#[test]
+fn search_reuses_results() {
+    let board = justok::board::Board::from_fen(
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    )
+    .unwrap();
+    let limits = Limits {
+        depth: Some(6),
+        ..Default::default()
+    };
+    let mut searcher = Searcher::new();
+    searcher.set_hash_mb(1);
+    let first = searcher.search(&board, &[], &limits, |_| {});
+    let second = searcher.search(&board, &[], &limits, |_| {});
+    assert!(second.nodes < first.nodes / 2);
+    assert_eq!(first.best_move(), second.best_move());
+    assert!(first.hashfull > 0);
+
+    searcher.clear();
+    let cleared = searcher.search(&board, &[], &limits, |_| {});
+    assert_eq!(cleared.nodes, first.nodes);
+}